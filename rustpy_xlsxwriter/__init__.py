@@ -69,6 +69,8 @@ from importlib.metadata import metadata as _metadata
 from importlib.metadata import version as _version
 
 from .rustpy_xlsxwriter import (
+    Blank,
+    ExcelError,
     Format,
     validate_sheet_name,
 )
@@ -167,10 +169,40 @@ _PER_SHEET_OPTIONS = (
     "banded_rows",
     "autofilter",
     "url_columns",
+    "image_columns",
     "totals_row",
     "totals_label",
     "totals_format",
     "formula_columns",
+    "conditional_formats",
+    "data_validation",
+    "table",
+    "charts",
+    "images",
+    "comments",
+    "overflow",
+    "wide_decimals",
+    "durations",
+    "nested_columns",
+    "timezone",
+    "int_overflow",
+    "id_columns",
+    "na_rep",
+    "nan_rep",
+    "inf_rep",
+    "error_columns",
+    "columns",
+    "missing",
+    "extra_keys",
+    "scan_rows",
+    "rename",
+    "header_groups",
+    "index",
+    "merge_index_cells",
+    "row_groups",
+    "column_groups",
+    "outline_column",
+    "outline_collapsed",
 )
 
 
@@ -200,6 +232,12 @@ class FastExcel:
         password: Optional[str] = None,
         autofit: bool = True,
         sanitize_formulas: bool = False,
+        utc_offsets: bool = False,
+        datetime_precision: Optional[int] = None,
+        properties: Optional[Dict[str, Any]] = None,
+        reproducible: bool = False,
+        parallel: bool = False,
+        max_threads: Optional[int] = None,
     ) -> None:
         """Create a new writer.
 
@@ -223,11 +261,56 @@ class FastExcel:
                 formulas (CSV-injection mitigation). Off by default to keep
                 output byte-identical. Has no effect on ``.xlsx`` output, where
                 values are already written as text cells.
+            utc_offsets: CSV/TSV only. When ``True``, timezone-aware
+                datetimes are written with their UTC offset, e.g.
+                ``2024-03-01T09:30:00+01:00``, in the zone the sheet's
+                ``timezone`` option chose. Naive values never get one.
+            datetime_precision: CSV/TSV only. Digits after the seconds of a
+                datetime, time or duration, 0 to 6, cut off rather than
+                rounded. By default they are as many as the value needs: none
+                for whole seconds, three for whole milliseconds, else six. ``.xlsx`` cells always
+                keep the microseconds; show them with a ``datetime_format``
+                such as ``"yyyy-mm-dd hh:mm:ss.000"``.
+            properties: Workbook document properties — ``title``,
+                ``subject``, ``author``, ``manager``, ``company``,
+                ``category``, ``keywords``, ``comment``, ``status``,
+                ``hyperlink_base``, ``created`` (a ``date``/``datetime``
+                pinning the creation date, which otherwise is the time of
+                writing) and ``custom``, a ``{name: value}`` dict of str, int,
+                float, bool, date or datetime values::
+
+                    properties={"author": "Finance", "company": "Acme",
+                                "custom": {"classification": "internal",
+                                           "generated_by": "nightly-export"}}
+
+                A malformed spec raises ``ValueError`` before anything is
+                written.
+            reproducible: Pin the workbook's creation timestamp — the only
+                part of an ``.xlsx`` that changes from run to run — so
+                identical input gives byte-identical files. The date is
+                ``SOURCE_DATE_EPOCH`` if set, else 1980-01-01; an explicit
+                ``properties["created"]`` wins. ``SOURCE_DATE_EPOCH`` is
+                honoured even without this flag. CSV output is always
+                reproducible.
+            parallel: Write the rows of sheets fed by an Arrow stream
+                (pyarrow, Polars, pandas with pyarrow) on a thread pool
+                instead of one sheet after another. Each such stream is read
+                into memory first; other sheets are written as usual. The file
+                is byte-for-byte the one the serial path writes. Only applies
+                when the workbook has more than one sheet.
+            max_threads: Cap on the threads ``parallel=True`` uses (default:
+                one per CPU core).
         """
         self._target = _coerce_target(target)
         self._password = password
+        self._properties = properties
+        self._reproducible = reproducible
+        self._parallel = parallel
+        self._max_threads = max_threads
         self._autofit = autofit
         self._sanitize_formulas = sanitize_formulas
+        self._utc_offsets = utc_offsets
+        self._datetime_precision = datetime_precision
         self._sheets: List[Tuple[str, Any]] = []
         self._float_format: Optional[str] = None
         self._datetime_format: Optional[str] = None
@@ -311,18 +394,48 @@ class FastExcel:
         column_widths: Optional[Union[Dict[str, float], List[float]]] = None,
         column_formats: Optional[Union[Dict[str, "Format"], List["Format"]]] = None,
         header_format: Optional["Format"] = None,
-        dedupe_strings: bool = False,
-        header_row: int = 0,
+        dedupe_strings: Optional[bool] = None,
+        header_row: Optional[int] = None,
         merge_ranges: Optional[List[Tuple]] = None,
         row_heights: Optional[Dict[int, float]] = None,
         row_formats: Optional[Dict[int, "Format"]] = None,
         banded_rows: Optional[str] = None,
-        autofilter: bool = False,
+        autofilter: Optional[bool] = None,
         url_columns: Optional[List[str]] = None,
+        image_columns: Optional[List[str]] = None,
         totals_row: Optional[Dict[str, str]] = None,
         totals_label: Optional[str] = None,
         totals_format: Optional["Format"] = None,
         formula_columns: Optional[Dict[str, str]] = None,
+        conditional_formats: Optional[Dict[str, Any]] = None,
+        data_validation: Optional[Dict[str, Dict[str, Any]]] = None,
+        table: Union[bool, Dict[str, Any], None] = None,
+        charts: Union[Dict[str, Any], List[Dict[str, Any]], None] = None,
+        images: Optional[List[Dict[str, Any]]] = None,
+        comments: Optional[Dict[Any, str]] = None,
+        overflow: Optional[str] = None,
+        wide_decimals: Optional[str] = None,
+        durations: Optional[str] = None,
+        nested_columns: Optional[str] = None,
+        timezone: Optional[str] = None,
+        int_overflow: Optional[str] = None,
+        id_columns: Optional[List[str]] = None,
+        na_rep: Optional[str] = None,
+        nan_rep: Optional[str] = None,
+        inf_rep: Optional[str] = None,
+        error_columns: Optional[List[str]] = None,
+        columns: Optional[List[str]] = None,
+        missing: Optional[str] = None,
+        extra_keys: Optional[str] = None,
+        scan_rows: Optional[int] = None,
+        rename: Optional[Dict[str, str]] = None,
+        header_groups: Optional[str] = None,
+        index: Optional[bool] = None,
+        merge_index_cells: Optional[bool] = None,
+        row_groups: Optional[List[Tuple[int, int, int]]] = None,
+        column_groups: Optional[List[Tuple[int, int, int]]] = None,
+        outline_column: Optional[str] = None,
+        outline_collapsed: Optional[bool] = None,
     ) -> "FastExcel":
         """Add a worksheet with data.
 
@@ -346,7 +459,8 @@ class FastExcel:
                 sheet is buffered in RAM and every string is hashed. Turn it on
                 per sheet, for sheets whose text actually repeats, and measure.
             header_row: 0-based row the header is written on; data follows it.
-                Raise it to leave room for merged banner headers above.
+                Raise it to leave room for merged banner headers above. Unset,
+                it is row 0, or the row below any ``header_groups`` rows.
             merge_ranges: Merged cells, as
                 ``(first_row, first_col, last_row, last_col, value[, format])``
                 tuples — e.g. ``[(0, 1, 0, 2, "Gender", banner_fmt)]`` for a
@@ -372,6 +486,13 @@ class FastExcel:
                 its 2083-character limit) is written as plain text instead, so a
                 stray non-link never aborts the export. The cell displays the
                 URL itself; per-cell display text is not supported.
+            image_columns: Column names whose cells hold images — ``bytes``
+                or a file path — embedded *in* the cell instead of written as
+                text, e.g. a column of product thumbnails. A value that is not
+                a readable image (corrupt bytes, a missing file, a number) is
+                left blank and reported in one ``UserWarning`` per column, so
+                one bad thumbnail never aborts the export. Needs Excel 365 or
+                2021+ to display; the sheet is buffered in memory.
             totals_row: ``{column_name: aggregate}`` written as Excel formulas
                 in a row below the data — ``{"amount": "sum"}`` becomes
                 ``=SUM(C2:C101)``. Valid aggregates: ``sum``, ``average``,
@@ -407,7 +528,200 @@ class FastExcel:
                 not, so ``=NOTAFUNC(A1)`` reaches the file and shows ``#NAME?``.
                 There is no ``{last}``: rows are still
                 streaming when these are written, so the final row is unknown;
-                use ``totals_row`` for whole-column formulas.
+                use ``totals_row`` for whole-column formulas. With ``table``,
+                ``[@price]*[@qty]`` structured references work too, and a
+                formula without ``{row}``/``{first}`` becomes a calculated
+                column that Excel extends to new rows.
+            conditional_formats: ``{column_name: rule}`` or
+                ``{column_name: [rule, ...]}`` — Excel conditional formatting
+                over the column's data rows. Each rule is a dict with a
+                ``type``::
+
+                    conditional_formats={
+                        "amount": [
+                            {"type": "cell", "criteria": "<", "value": 0,
+                             "format": Format().set_font_color("red")},
+                            {"type": "data_bar", "bar_color": "#638EC6"},
+                        ],
+                        "score": {"type": "3_color_scale"},
+                    }
+
+                Types: ``cell`` (``criteria`` ``== != > >= < <=`` with
+                ``value``, or ``between``/``not_between`` with
+                ``minimum``/``maximum``), ``2_color_scale``/``3_color_scale``
+                (``min_color``/``mid_color``/``max_color``, optional
+                ``min_type``/``min_value`` etc.), ``data_bar``
+                (``bar_color``, ``bar_solid``, ``bar_only``, …), ``icon_set``
+                (``icon_type`` such as ``"3_arrows"``), ``top``/``bottom``
+                (``value``, ``percent``) and ``formula`` (``criteria`` is the
+                formula; ``{col}``, ``{first}`` and ``{last}`` are the column
+                letter and the first and last data rows — on each part of a
+                split sheet, that part's own). ``cell``, ``top``, ``bottom`` and ``formula``
+                need a ``format``. The range follows the rows actually
+                written; a malformed rule raises ``ValueError`` before
+                anything is written.
+            data_validation: ``{column_name: validation}`` — restrict what
+                can be typed into the column's data cells. Each validation is
+                a dict with a ``validate`` key::
+
+                    data_validation={
+                        "status": {"validate": "list",
+                                   "source": ["open", "closed"]},
+                        "qty": {"validate": "whole", "criteria": "between",
+                                "minimum": 1, "maximum": 100,
+                                "error_message": "1 to 100 only"},
+                    }
+
+                Kinds: ``list`` (``source`` is a list of strings or a
+                ``"=$H$1:$H$5"`` range; ``dropdown=False`` hides the arrow),
+                ``whole``/``decimal``/``date``/``time``/``length`` (same
+                ``criteria`` as ``cell`` conditional formats; bounds may also
+                be ``"="`` formulas), ``custom`` (``value`` is the formula,
+                with ``{col}``/``{first}``/``{last}`` placeholders, as in a
+                ``formula`` conditional format) and ``any`` (message
+                only). Every kind takes ``input_title``/``input_message``,
+                ``error_title``/``error_message``, ``error_type`` (``stop``,
+                ``warning``, ``information``) and ``ignore_blank``. A
+                malformed validation raises ``ValueError`` before anything is
+                written.
+            table: ``True`` or a dict — write the header and data as a native
+                Excel table instead of plain cells, so users get filter
+                buttons, slicers and structured references::
+
+                    table={"name": "Sales", "style": "Table Style Medium 2"}
+
+                Options: ``name`` (default ``Table1``, ``Table2``, …),
+                ``style`` (as Excel names it; default Medium 9),
+                ``banded_rows``, ``banded_columns``, ``first_column``,
+                ``last_column``, ``autofilter``. ``totals_row`` and
+                ``totals_label`` become the table's totals row, as
+                ``SUBTOTAL`` formulas that skip filtered-out rows. Cannot be
+                combined with ``autofilter`` or ``banded_rows`` — the table
+                provides both. The sheet is buffered in memory rather than
+                streamed, since the table is laid over rows already written.
+            charts: one chart dict or a list of them, plotting this sheet's
+                columns by header name::
+
+                    charts=[{"type": "column", "categories": "month",
+                             "values": ["sales", "cost"], "title": "Sales",
+                             "secondary": {"type": "line",
+                                           "values": "margin"}}]
+
+                ``type`` is ``column``, ``bar``, ``line``, ``area``, ``pie``,
+                ``doughnut`` or ``scatter``; ``subtype`` picks ``stacked`` /
+                ``percent_stacked`` (or ``straight``, ``smooth``, … for
+                scatter). ``values`` is one column or a list, ``categories``
+                the x-axis column (required for scatter). Also ``title``,
+                ``x_axis_title``, ``y_axis_title``, ``legend`` (a position or
+                ``False``), ``width``/``height`` in pixels, ``anchor`` (a cell
+                such as ``"H2"``; default beside the data, stacked) and
+                ``sheet`` to put the chart on another sheet, created if it
+                does not exist. ``secondary`` adds a ``type``/``values`` pair
+                on a secondary y axis. Ranges cover the rows actually
+                written; a malformed chart raises ``ValueError`` before
+                anything is written.
+            images: a list of images floating over the sheet, each a dict
+                with ``anchor`` (a cell such as ``"E2"``) and ``image``
+                (``bytes`` or a file path), plus optional ``x_scale``,
+                ``y_scale``, ``x_offset``/``y_offset`` in pixels and
+                ``alt_text``::
+
+                    images=[{"anchor": "A1", "image": "logo.png",
+                             "x_scale": 0.5}]
+
+                Every image is read up front; a missing file or unsupported
+                format raises ``ValueError`` before anything is written.
+            comments: cell notes. ``{(row, col): text}`` notes fixed cells
+                (0-based, like ``merge_ranges``); ``{column: note_column}``
+                notes each ``column`` cell with the same row's
+                ``note_column`` value, and ``note_column`` itself is not
+                written::
+
+                    comments={"price": "price_source", (0, 0): "Exported nightly"}
+
+                Empty values add no note.
+            overflow: data rows past Excel's 1,048,576-row limit.
+                ``"error"`` (default) raises ``ValueError``, ``"truncate"``
+                drops them with a warning, and ``"split"`` continues on
+                ``"<name> (2)"``, ``"<name> (3)"``, … repeating the header,
+                column formats, freeze panes and autofilter.
+            wide_decimals: Arrow decimal values with more than 15 significant
+                digits, which Excel cannot hold exactly. ``"number"``
+                (default) writes the rounded number, ``"text"`` the exact
+                digits as text, and ``"error"`` raises ``ValueError``.
+            durations: how ``timedelta`` values and Arrow duration columns are
+                written. ``"elapsed"`` (default) writes Excel elapsed time
+                shown as ``[h]:mm:ss``; ``"seconds"`` writes the number of
+                seconds.
+            nested_columns: how list, struct and dict values are written.
+                ``"json"`` (default) writes a JSON string, ``"join"`` joins
+                list items with ``", "``, ``"explode"`` gives each struct
+                field (or ``dict`` key) its own ``parent.field`` column, and
+                ``"error"`` raises ``ValueError``.
+            timezone: the zone timezone-aware datetimes are shown in.
+                ``"keep"`` (default) writes each value's own wall time;
+                ``"utc"``, ``"local"``, an IANA name such as
+                ``"Europe/Berlin"`` or an offset such as ``"+05:30"``
+                converts them first. Naive values are written as they are.
+                A column mixing naive and aware values warns under
+                ``"keep"`` and raises ``ValueError`` otherwise.
+            int_overflow: integers past 2^53, which Excel cannot hold
+                exactly. ``"number"`` (default) writes the rounded number,
+                ``"text"`` the exact digits as text, and ``"error"`` raises
+                ``ValueError``.
+            id_columns: column names written as text whatever their type,
+                so IDs and codes keep every digit. Whole floats are written
+                without ``.0``.
+            na_rep: what ``None``, nulls and ``NaT`` are written as — a
+                text, an Excel error value such as ``"#N/A"``, or
+                ``"raise"`` to raise ``ValueError``. Blank by default.
+            nan_rep: the same for NaN.
+            inf_rep: the same for infinity; negative infinity's text gets a
+                leading ``-``.
+            error_columns: column names whose text cells spelling an Excel
+                error, such as ``"#N/A"``, become error cells. Elsewhere, use
+                ``ExcelError.NA`` and friends as values.
+            columns: the source columns to write, in order; the rest are
+                never read. For dict records, by default the first record's
+                keys, and values are matched to them by key, whatever order
+                each record has them in. For a DataFrame or Arrow source, a
+                name it lacks raises ``ValueError``.
+            missing: a record without one of the columns. ``"blank"``
+                (default) writes it as ``None``; ``"error"`` raises
+                ``ValueError``.
+            extra_keys: a key that is not one of the columns. ``"ignore"``
+                (default) leaves it out, ``"error"`` raises ``ValueError``,
+                and ``"append"`` adds it as a column after the others.
+            scan_rows: under ``extra_keys="append"``, how many records are
+                read ahead for new keys — by default all of a list or tuple
+                and the first 100 of any other iterable.
+            rename: ``{source name: header}`` — the header written for a
+                column instead of its name. Other column options name the
+                column by this header.
+            header_groups: a separator, such as ``"."``, that splits each
+                header into groups and a column name: ``"Q1.Revenue"`` writes
+                ``Revenue`` on the header row and ``Q1`` above it, merged over
+                the neighbouring ``Q1.`` columns. The group rows take the rows
+                just above the header row: left unset, ``header_row`` moves
+                down one row per level, and the freeze panes and autofilter
+                with it; a given one has to leave room. A pandas
+                ``MultiIndex`` is joined with the separator.
+                Column options still use the full header.
+            index: write a pandas DataFrame's index as its leading columns,
+                in bold. By default the index is left out.
+            merge_index_cells: under ``index=True``, merge each run of a
+                repeated outer ``MultiIndex`` label down its rows. On by
+                default.
+            row_groups: ``[(first_row, last_row, level)]`` — outline groups
+                Excel can fold away, on 0-based sheet rows, at levels 1 to 7.
+                A row covered twice takes the deeper level; neighbouring rows
+                on the same level make one group.
+            column_groups: ``[(first_col, last_col, level)]`` — the same for
+                columns.
+            outline_column: a column of row levels, 0 (ungrouped) to 7, read
+                but not written — the streaming alternative to
+                ``row_groups``.
+            outline_collapsed: write every row and column group folded away.
 
         Raises:
             ValueError: If the sheet name is invalid (validated on save), or a
@@ -416,7 +730,8 @@ class FastExcel:
         self._sheets.append((name, data))
         # Only options actually given are recorded, so the writers keep their
         # own defaults for the rest — and a CSV target only warns about options
-        # that were really set.
+        # that were really set. A given ``False``, ``0`` or ``{}`` is recorded
+        # too: it may differ from the writer's default.
         for option, value in {
             "column_width": column_width,
             "column_widths": column_widths,
@@ -430,12 +745,42 @@ class FastExcel:
             "banded_rows": banded_rows,
             "autofilter": autofilter,
             "url_columns": url_columns,
+            "image_columns": image_columns,
             "totals_row": totals_row,
             "totals_label": totals_label,
             "totals_format": totals_format,
             "formula_columns": formula_columns,
+            "conditional_formats": conditional_formats,
+            "data_validation": data_validation,
+            "table": table,
+            "charts": charts,
+            "images": images,
+            "comments": comments,
+            "overflow": overflow,
+            "wide_decimals": wide_decimals,
+            "durations": durations,
+            "nested_columns": nested_columns,
+            "timezone": timezone,
+            "int_overflow": int_overflow,
+            "id_columns": id_columns,
+            "na_rep": na_rep,
+            "nan_rep": nan_rep,
+            "inf_rep": inf_rep,
+            "error_columns": error_columns,
+            "columns": columns,
+            "missing": missing,
+            "extra_keys": extra_keys,
+            "scan_rows": scan_rows,
+            "rename": rename,
+            "header_groups": header_groups,
+            "index": index,
+            "merge_index_cells": merge_index_cells,
+            "row_groups": row_groups,
+            "column_groups": column_groups,
+            "outline_column": outline_column,
+            "outline_collapsed": outline_collapsed,
         }.items():
-            if value:
+            if value is not None:
                 self._per_sheet[option][name] = value
         return self
 
@@ -446,9 +791,17 @@ class FastExcel:
 
         ``autofit`` and ``sanitize_formulas`` are left out: the first is on by
         default so it would fire on every CSV write, and the second is CSV-only.
+        So is ``reproducible``: CSV output has no timestamp to pin,
+        ``overflow``: CSV has no row limit, ``wide_decimals`` and
+        ``int_overflow``: CSV always has the exact digits, and
+        ``nested_columns``, ``timezone``, the ``*_rep`` options, the
+        record-key options and ``columns``/``rename``, which CSV honours too.
+        ``parallel`` and ``max_threads`` only change how the file is written,
+        not what is in it.
         """
         workbook_wide = {
             "password": self._password,
+            "properties": self._properties,
             "float_format": self._float_format,
             "datetime_format": self._datetime_format,
             "index_columns": self._index_columns,
@@ -457,7 +810,25 @@ class FastExcel:
         }
         names = [name for name, value in workbook_wide.items() if value]
         names += [
-            option for option in _PER_SHEET_OPTIONS if self._per_sheet[option]
+            option
+            for option in _PER_SHEET_OPTIONS
+            if self._per_sheet[option]
+            and option
+            not in (
+                "overflow",
+                "wide_decimals",
+                "int_overflow",
+                "nested_columns",
+                "timezone",
+                "na_rep",
+                "nan_rep",
+                "inf_rep",
+                "columns",
+                "missing",
+                "extra_keys",
+                "scan_rows",
+                "rename",
+            )
         ]
         return names
 
@@ -485,7 +856,7 @@ class FastExcel:
                         f"CSV/TSV output supports a single sheet; got {len(self._sheets)}."
                     )
                 delimiter = "\t" if lower.endswith(".tsv") else ","
-                _, data = self._sheets[0]
+                name, data = self._sheets[0]
                 ignored = self._excel_only_options()
                 if ignored:
                     _warnings.warn(
@@ -500,6 +871,18 @@ class FastExcel:
                     self._target,
                     delimiter=delimiter,
                     sanitize_formulas=self._sanitize_formulas,
+                    nested_columns=self._per_sheet["nested_columns"].get(name),
+                    timezone=self._per_sheet["timezone"].get(name),
+                    utc_offsets=self._utc_offsets,
+                    datetime_precision=self._datetime_precision,
+                    na_rep=self._per_sheet["na_rep"].get(name),
+                    nan_rep=self._per_sheet["nan_rep"].get(name),
+                    inf_rep=self._per_sheet["inf_rep"].get(name),
+                    columns=self._per_sheet["columns"].get(name),
+                    missing=self._per_sheet["missing"].get(name),
+                    extra_keys=self._per_sheet["extra_keys"].get(name),
+                    scan_rows=self._per_sheet["scan_rows"].get(name),
+                    rename=self._per_sheet["rename"].get(name),
                 )
                 return
 
@@ -521,6 +904,8 @@ class FastExcel:
                 self._target,
                 sheet_name=sheet_name,
                 password=self._password,
+                properties=self._properties,
+                reproducible=self._reproducible,
                 freeze_row=freeze_row,
                 freeze_col=freeze_col,
                 float_format=self._float_format,
@@ -540,6 +925,10 @@ class FastExcel:
                 self._sheets,
                 self._target,
                 password=self._password,
+                properties=self._properties,
+                reproducible=self._reproducible,
+                parallel=self._parallel,
+                max_threads=self._max_threads,
                 freeze_panes=self._freeze_panes or None,
                 float_format=self._float_format,
                 datetime_format=self._datetime_format,
@@ -563,6 +952,9 @@ __all__ = [
     "FastExcel",
     # Format API
     "Format",
+    # Cell values
+    "ExcelError",
+    "Blank",
     # Functional API
     "write_csv",
     "write_worksheet",
@@ -57,6 +57,50 @@ MergeRange = Union[
 """One merged cell range: ``(first_row, first_col, last_row, last_col, value)``,
 optionally followed by a :class:`Format`."""
 
+ConditionalRule = Dict[str, Any]
+"""One conditional-format rule — a dict with a ``type`` key (``"cell"``,
+``"2_color_scale"``, ``"3_color_scale"``, ``"data_bar"``, ``"icon_set"``,
+``"top"``, ``"bottom"`` or ``"formula"``) plus that type's options."""
+
+ConditionalFormats = Dict[str, Union[ConditionalRule, List[ConditionalRule]]]
+"""Conditional formatting keyed by column name — one rule or a list of rules."""
+
+DataValidations = Dict[str, Dict[str, Any]]
+"""Data validation keyed by column name — a dict with a ``validate`` key
+(``"list"``, ``"whole"``, ``"decimal"``, ``"date"``, ``"time"``, ``"length"``,
+``"custom"`` or ``"any"``) plus that kind's options."""
+
+TableOptions = Union[bool, Dict[str, Any]]
+"""``True`` for a default Excel table, or a dict with ``name``, ``style``,
+``banded_rows``, ``banded_columns``, ``first_column``, ``last_column`` and
+``autofilter``."""
+
+Chart = Dict[str, Any]
+"""One chart — a dict with ``type`` (``"column"``, ``"bar"``, ``"line"``,
+``"area"``, ``"pie"``, ``"doughnut"`` or ``"scatter"``), ``values`` (column
+name or list of names) and optional ``categories``, ``subtype``, ``title``,
+axis titles, ``legend``, ``anchor``, ``width``, ``height``, ``sheet`` and
+``secondary``."""
+
+Charts = Union[Chart, List[Chart]]
+"""One :data:`Chart` or a list of them."""
+
+FloatingImage = Dict[str, Any]
+"""One image over the sheet — ``anchor`` (a cell such as ``"E2"``) and
+``image`` (``bytes`` or a file path), plus optional ``x_scale``, ``y_scale``,
+``x_offset``, ``y_offset`` and ``alt_text``."""
+
+DocProperties = Dict[str, Any]
+"""Workbook document properties — ``title``, ``subject``, ``author``,
+``manager``, ``company``, ``category``, ``keywords``, ``comment``, ``status``,
+``hyperlink_base``, ``created`` (a ``date``/``datetime``) and ``custom``, a
+``{name: value}`` dict of str, int, float, bool, date or datetime values."""
+
+Comments = Dict[Union[Tuple[int, int], str], str]
+"""Cell notes — ``{(row, col): text}`` for fixed cells, or
+``{column: note_column}`` to note each cell of ``column`` with the value of
+``note_column`` in the same row. A note column is not written as data."""
+
 SheetData = Union[Records, DataFrame]
 """Data accepted per sheet – either :data:`Records` or a :data:`DataFrame`."""
 
@@ -130,6 +174,43 @@ class Format:
     def set_checkbox(self) -> Format: ...
     def set_hyperlink(self) -> Format: ...
 
+# ---------------------------------------------------------------------------
+# Cell values
+# ---------------------------------------------------------------------------
+
+class ExcelError:
+    """An Excel error value, written as a real error cell so that lookups and
+    ``ISNA()`` see it as Excel would. ``str()`` gives Excel's spelling, and
+    CSV output writes that spelling.
+
+    Examples::
+
+        write_worksheet([{"price": ExcelError.NA}], "out.xlsx")
+    """
+
+    NA: ExcelError
+    """``#N/A``"""
+    DIV0: ExcelError
+    """``#DIV/0!``"""
+    NAME: ExcelError
+    """``#NAME?``"""
+    NULL: ExcelError
+    """``#NULL!``"""
+    NUM: ExcelError
+    """``#NUM!``"""
+    REF: ExcelError
+    """``#REF!``"""
+    VALUE: ExcelError
+    """``#VALUE!``"""
+
+class Blank:
+    """An empty cell. Unlike ``None`` it ignores ``na_rep``, and with a
+    ``format`` it carries that format — borders or a fill on a cell with no
+    value. CSV output writes an empty field.
+    """
+
+    def __init__(self, format: Optional[Format] = None) -> None: ...
+
 # ---------------------------------------------------------------------------
 # Builder class
 # ---------------------------------------------------------------------------
@@ -162,6 +243,12 @@ class FastExcel:
         password: Optional[str] = None,
         autofit: bool = True,
         sanitize_formulas: bool = False,
+        utc_offsets: bool = False,
+        datetime_precision: Optional[int] = None,
+        properties: Optional[DocProperties] = None,
+        reproducible: bool = False,
+        parallel: bool = False,
+        max_threads: Optional[int] = None,
     ) -> None:
         """Create a new writer.
 
@@ -175,6 +262,18 @@ class FastExcel:
             sanitize_formulas: CSV/TSV only. When ``True``, string fields
                 starting with ``= + - @`` are prefixed with ``'`` to neutralize
                 CSV formula injection. Off by default. No effect on ``.xlsx``.
+            utc_offsets: CSV/TSV only. Write timezone-aware datetimes with
+                their UTC offset. See :func:`write_csv`.
+            datetime_precision: CSV/TSV only. Digits after the seconds of a
+                datetime, time or duration. See :func:`write_csv`.
+            properties: Workbook document properties, see :data:`DocProperties`.
+                ``created`` pins the creation date (default: time of writing).
+            reproducible: Pin the creation timestamp so identical input gives
+                byte-identical files — to ``SOURCE_DATE_EPOCH`` if set, else
+                1980-01-01. ``properties["created"]`` wins over both.
+            parallel: Write Arrow-backed sheets on a thread pool. See
+                :func:`write_worksheets`.
+            max_threads: Cap on the threads ``parallel=True`` uses.
         """
         ...
 
@@ -280,17 +379,49 @@ def write_worksheet(
     column_formats: Optional[ColumnFormats] = None,
     header_format: Optional[Format] = None,
     dedupe_strings: bool = False,
-    header_row: int = 0,
+    header_row: Optional[int] = None,
     merge_ranges: Optional[List[MergeRange]] = None,
     row_heights: Optional[Dict[int, float]] = None,
     row_formats: Optional[Dict[int, Format]] = None,
     banded_rows: Optional[str] = None,
     autofilter: bool = False,
     url_columns: Optional[List[str]] = None,
+    image_columns: Optional[List[str]] = None,
     totals_row: Optional[Dict[str, str]] = None,
     totals_label: Optional[str] = None,
     totals_format: Optional[Format] = None,
     formula_columns: Optional[Dict[str, str]] = None,
+    conditional_formats: Optional[ConditionalFormats] = None,
+    data_validation: Optional[DataValidations] = None,
+    table: Optional[TableOptions] = None,
+    charts: Optional[Charts] = None,
+    images: Optional[List[FloatingImage]] = None,
+    comments: Optional[Comments] = None,
+    properties: Optional[DocProperties] = None,
+    reproducible: bool = False,
+    overflow: Optional[str] = None,
+    wide_decimals: Optional[str] = None,
+    durations: Optional[str] = None,
+    nested_columns: Optional[str] = None,
+    timezone: Optional[str] = None,
+    int_overflow: Optional[str] = None,
+    id_columns: Optional[List[str]] = None,
+    na_rep: Optional[str] = None,
+    nan_rep: Optional[str] = None,
+    inf_rep: Optional[str] = None,
+    error_columns: Optional[List[str]] = None,
+    columns: Optional[List[str]] = None,
+    missing: Optional[str] = None,
+    extra_keys: Optional[str] = None,
+    scan_rows: Optional[int] = None,
+    rename: Optional[Dict[str, str]] = None,
+    header_groups: Optional[str] = None,
+    index: bool = False,
+    merge_index_cells: bool = True,
+    row_groups: Optional[List[Tuple[int, int, int]]] = None,
+    column_groups: Optional[List[Tuple[int, int, int]]] = None,
+    outline_column: Optional[str] = None,
+    outline_collapsed: bool = False,
 ) -> None:
     """Write data to a **single** worksheet in an Excel file.
 
@@ -314,6 +445,8 @@ def write_worksheet(
             cost of buffering the sheet in memory (disables constant-memory
             mode). Off by default.
         header_row: 0-based row the header is written on; data follows it.
+            Defaults to row 0, or to the row below any ``header_groups``
+            rows.
         merge_ranges: ``(first_row, first_col, last_row, last_col, value[, format])``
             tuples. Must sit strictly above ``header_row``.
         row_heights: ``{row_index: height}`` in points.
@@ -321,6 +454,9 @@ def write_worksheet(
         banded_rows: Background colour shaded onto every other data row.
         autofilter: Add filter dropdowns over the header row and its data.
         url_columns: Column names whose text cells become clickable links.
+        image_columns: Column names whose ``bytes`` or file-path cells are
+            embedded as in-cell images. Unreadable values are left blank with
+            a ``UserWarning``; the sheet is buffered in memory.
             Values Excel rejects fall back to plain text.
         totals_row: ``{column_name: aggregate}`` written as formulas below the
             data. Valid: sum, average, count, min, max, product, stdev.
@@ -328,6 +464,162 @@ def write_worksheet(
         totals_format: Format applied to the whole totals row.
         formula_columns: ``{header: formula}`` appended after the data, one
             formula per row. ``{row}``/``{first}`` are substituted.
+        conditional_formats: ``{column_name: rule or [rules]}`` — Excel
+            conditional formatting over each column's data rows. A
+            ``formula`` rule's ``criteria`` may use ``{col}``, ``{first}`` and
+            ``{last}``: the column letter and the first and last data rows of
+            the sheet, or of each part of a split one. Malformed rules raise
+            ``ValueError``.
+        data_validation: ``{column_name: validation}`` — dropdown lists,
+            numeric/date/time ranges, text length limits or custom formulas
+            over each column's data rows. A ``custom`` formula takes the same
+            ``{col}``/``{first}``/``{last}`` placeholders as a ``formula``
+            conditional format. Malformed specs raise ``ValueError``.
+        table: Write the sheet as a native Excel table. ``totals_row`` becomes
+            its structured totals row and ``formula_columns`` may use
+            ``[@Column]`` references. Excludes ``autofilter`` and
+            ``banded_rows``; the sheet is buffered in memory.
+        charts: Charts over this sheet's columns, by header name. Ranges
+            follow the rows written; ``sheet`` places a chart on another
+            (possibly new) sheet. Malformed charts raise ``ValueError``.
+        images: Images floating over the sheet, anchored by cell. A missing or
+            unreadable image raises ``ValueError``.
+        comments: Cell notes — ``{(row, col): text}`` for fixed cells (0-based,
+            like ``merge_ranges``), or ``{column: note_column}`` to attach each
+            row's ``note_column`` value to that row's ``column`` cell. Note
+            columns are not written as data; empty values add no note.
+        properties: Workbook document properties — title, author, company,
+            custom properties and a fixed ``created`` date. Aware datetimes are
+            stored in UTC, naive ones as given.
+        reproducible: Pin the creation timestamp — the only run-to-run
+            difference in the file — so identical input gives byte-identical
+            output. Uses ``SOURCE_DATE_EPOCH`` if set (honoured even without
+            this flag), else 1980-01-01; ``properties["created"]`` wins.
+        overflow: What to do with data rows past Excel's 1,048,576-row limit.
+            ``"error"`` (default) raises ``ValueError``; ``"truncate"`` keeps
+            what fits and warns; ``"split"`` continues on ``"Sheet1 (2)"``,
+            ``"Sheet1 (3)"``, … with the same header, formats, freeze panes
+            and autofilter. Nothing is buffered. Cannot split a ``table``.
+        wide_decimals: What to do with Arrow decimal values that have more than
+            the 15 significant digits Excel keeps. ``"number"`` (default)
+            writes the rounded number; ``"text"`` writes the exact digits as
+            text; ``"error"`` raises ``ValueError`` naming the column. Decimal
+            cells get a number format with the column's scale, e.g. ``0.00``
+            for ``decimal128(12, 2)``, unless ``column_formats`` sets one.
+        durations: How ``datetime.timedelta`` values and Arrow duration columns
+            are written. ``"elapsed"`` (default) writes Excel elapsed time with
+            a ``[h]:mm:ss`` format, so 26 hours shows as ``26:00:00``;
+            ``"seconds"`` writes the number of seconds. ``datetime.time``
+            values and Arrow time columns are always time-of-day serials
+            formatted ``hh:mm:ss``. A ``column_formats`` entry wins over
+            either format.
+        nested_columns: How Arrow list, struct and map columns, and
+            ``list``/``tuple``/``dict`` values in records, are written.
+            ``"json"`` (default) writes a JSON string; ``"join"`` joins list
+            items with ``", "``; ``"explode"`` turns each struct field (each
+            key of a ``dict`` value) into its own ``parent.field`` column;
+            ``"error"`` raises ``ValueError``. Anything ``"join"`` or
+            ``"explode"`` cannot handle is written as JSON.
+        timezone: The zone timezone-aware Python datetimes and Arrow
+            timestamp columns are shown in. ``"keep"`` (default) writes each
+            value's own wall time — for Arrow, the wall time in the column's
+            zone; ``"utc"``, ``"local"``, an IANA name such as
+            ``"Europe/Berlin"`` or an offset such as ``"+05:30"`` converts
+            them first. Naive values are written as they are. A column that
+            mixes naive and aware datetimes warns under ``"keep"`` and raises
+            ``ValueError`` otherwise.
+        int_overflow: What to do with integers past 2^53, the largest an
+            Excel number holds exactly — Python ints, pandas/polars integer
+            columns and Arrow ``int64``/``uint64`` values alike. ``"number"``
+            (default) writes the rounded number; ``"text"`` writes the exact
+            digits as text; ``"error"`` raises ``ValueError`` naming the
+            column.
+        id_columns: Column names whose values are always written as text, so
+            IDs, account numbers and codes keep every digit and any leading
+            sign. Whole floats are written without ``.0`` (pandas turns an
+            integer column with gaps into floats), and Excel's "number
+            stored as text" marker is turned off for these cells.
+        na_rep: What a missing value — ``None``, an Arrow or polars null,
+            pandas' ``NaN`` in an object column or ``NaT`` — is written as.
+            Blank by default; otherwise a text, one of Excel's error values
+            (``"#N/A"``, ``"#DIV/0!"``, ``"#NAME?"``, ``"#NULL!"``,
+            ``"#NUM!"``, ``"#REF!"``, ``"#VALUE!"``) written as a real error
+            cell, or ``"raise"`` to raise ``ValueError`` naming the cell.
+        nan_rep: The same for a float NaN.
+        inf_rep: The same for ±infinity. Negative infinity's text gets a
+            leading ``-``; an error value is used as it is.
+        error_columns: Column names whose text cells spelling an Excel error —
+            ``"#N/A"``, ``"#DIV/0!"`` and the rest — are written as error
+            cells; other text stays text. For an Arrow string column, the one
+            way to get error cells; elsewhere :class:`ExcelError` values work
+            in any column.
+        columns: The source columns to write, in order. The rest are never
+            read: an Arrow stream is projected and a DataFrame cut down
+            before any value is converted, and a name the data lacks raises
+            ``ValueError``. For dict records, the header row — by default the
+            first record's keys; each record's values are matched to the
+            headers by key, so records with their keys in another order, or
+            with some left out, still line up.
+        missing: A record that lacks one of the columns. ``"blank"``
+            (default) writes it as ``None`` would be — blank unless ``na_rep``
+            says otherwise; ``"error"`` raises ``ValueError`` naming the row
+            and the column.
+        extra_keys: A record key that is not one of the columns. ``"ignore"``
+            (default) leaves the value out; ``"error"`` raises ``ValueError``;
+            ``"append"`` adds it as a column after the others. The header row
+            is written before the data, so new keys are gathered by reading
+            ahead ``scan_rows`` records; one first seen later raises
+            ``ValueError``.
+        scan_rows: Records read ahead for ``extra_keys="append"``. By default
+            all of a list or tuple, which is already in memory, and the first
+            100 of a generator or other iterable.
+        rename: ``{source name: header}`` — the header written for a column
+            in place of its name. Every other column option, such as
+            ``column_formats`` or ``url_columns``, names the column by this
+            header. A key that names no column warns and is skipped.
+        header_groups: A separator, such as ``"."``, that turns headers into
+            a multi-row header. Each header is split on it: the last part is
+            written on the header row and the parts before it on the rows
+            above, one level per row, with a merged cell over each run of
+            neighbouring columns in the same group. ``"Q1.Revenue"`` and
+            ``"Q1.Cost"`` get ``Q1`` merged over ``Revenue`` and ``Cost``.
+            The group rows are the ones just above the header row. Left
+            unset, ``header_row`` moves down one row per level, taking the
+            data, autofilter and freeze panes with it; an explicit
+            ``header_row`` must leave room for them (``header_row=1`` for one
+            level), and stays put. Group cells take ``header_format``, or are centred (and
+            bold under ``bold_headers``). Polars struct columns under
+            ``nested_columns="explode"`` are named ``parent.field`` already,
+            and a pandas ``MultiIndex`` is joined with the separator, leaving
+            out empty levels. Column options still name a column by its full
+            header. Cannot be combined with ``table``.
+        index: Write a pandas ``DataFrame``'s index as its leading columns,
+            in bold, one per level, headed by the level names — ``index``
+            for an unnamed index and ``level_0``, ``level_1``, … for unnamed
+            ``MultiIndex`` levels, as ``DataFrame.reset_index`` names them.
+            They stay in front when ``columns`` is given. With ``False``,
+            the default, the index is left out whether the frame is written
+            through Arrow or column by column. Other inputs have no index.
+        merge_index_cells: Under ``index=True``, merge each run of a
+            repeated label in an outer ``MultiIndex`` level down the rows it
+            spans, with the label at the top, as ``DataFrame.to_excel``
+            does. Such a sheet is kept in memory until save. Pass ``False``
+            to repeat the label on every row, which ``table`` requires.
+        row_groups: Outline groups as ``(first_row, last_row, level)``
+            tuples — 0-based sheet rows, levels 1 to 7 — that Excel folds
+            away under the ``+``/``-`` buttons beside the rows. Levels are
+            absolute: a row covered by more than one group takes the deepest
+            level, and neighbouring rows on the same level make one group,
+            as in Excel. A sheet with grouped rows is kept in memory until
+            save. Cannot be combined with ``outline_column``.
+        column_groups: Outline groups of columns, as ``(first_col,
+            last_col, level)`` tuples.
+        outline_column: A column holding each data row's outline level, 0
+            (ungrouped) to 7, for reports whose grouping is only known as
+            the rows stream in. It is read but not written, like a
+            ``comments`` source column, and ``None`` counts as 0.
+        outline_collapsed: Write the sheet with every row and column group
+            folded away, leaving the ungrouped rows and columns showing.
 
     Raises:
         ValueError: Invalid sheet name or unsupported data type.
@@ -360,10 +652,44 @@ def write_worksheets(
     banded_rows: Optional[Dict[str, str]] = None,
     autofilter: Optional[Dict[str, bool]] = None,
     url_columns: Optional[Dict[str, List[str]]] = None,
+    image_columns: Optional[Dict[str, List[str]]] = None,
     totals_row: Optional[Dict[str, Dict[str, str]]] = None,
     totals_label: Optional[Dict[str, str]] = None,
     totals_format: Optional[Dict[str, Format]] = None,
     formula_columns: Optional[Dict[str, Dict[str, str]]] = None,
+    conditional_formats: Optional[Dict[str, ConditionalFormats]] = None,
+    data_validation: Optional[Dict[str, DataValidations]] = None,
+    table: Optional[Dict[str, TableOptions]] = None,
+    charts: Optional[Dict[str, Charts]] = None,
+    images: Optional[Dict[str, List[FloatingImage]]] = None,
+    comments: Optional[Dict[str, Comments]] = None,
+    properties: Optional[DocProperties] = None,
+    reproducible: bool = False,
+    overflow: Optional[Dict[str, str]] = None,
+    parallel: bool = False,
+    max_threads: Optional[int] = None,
+    wide_decimals: Optional[Dict[str, str]] = None,
+    durations: Optional[Dict[str, str]] = None,
+    nested_columns: Optional[Dict[str, str]] = None,
+    timezone: Optional[Dict[str, str]] = None,
+    int_overflow: Optional[Dict[str, str]] = None,
+    id_columns: Optional[Dict[str, List[str]]] = None,
+    na_rep: Optional[Dict[str, str]] = None,
+    nan_rep: Optional[Dict[str, str]] = None,
+    inf_rep: Optional[Dict[str, str]] = None,
+    error_columns: Optional[Dict[str, List[str]]] = None,
+    columns: Optional[Dict[str, List[str]]] = None,
+    missing: Optional[Dict[str, str]] = None,
+    extra_keys: Optional[Dict[str, str]] = None,
+    scan_rows: Optional[Dict[str, int]] = None,
+    rename: Optional[Dict[str, Dict[str, str]]] = None,
+    header_groups: Optional[Dict[str, str]] = None,
+    index: Optional[Dict[str, bool]] = None,
+    merge_index_cells: Optional[Dict[str, bool]] = None,
+    row_groups: Optional[Dict[str, List[Tuple[int, int, int]]]] = None,
+    column_groups: Optional[Dict[str, List[Tuple[int, int, int]]]] = None,
+    outline_column: Optional[Dict[str, str]] = None,
+    outline_collapsed: Optional[Dict[str, bool]] = None,
 ) -> None:
     """Write data to **multiple** worksheets in an Excel file.
 
@@ -381,16 +707,81 @@ def write_worksheets(
             sheet name (``"general"`` applies to all). See
             :func:`write_worksheet` for the trade-off.
         header_row: Per-sheet header row index — dict keyed by sheet name.
+            A sheet without one moves its header below any ``header_groups``
+            rows, as in :func:`write_worksheet`.
         merge_ranges: Per-sheet merged cells — dict keyed by sheet name.
         row_heights: Per-sheet row heights — dict keyed by sheet name.
         row_formats: Per-sheet row formats — dict keyed by sheet name.
         banded_rows: Per-sheet alternating row colour — dict keyed by sheet name.
         autofilter: Per-sheet filter dropdowns — dict keyed by sheet name.
         url_columns: Per-sheet link columns — dict keyed by sheet name.
+        image_columns: Per-sheet in-cell image columns — dict keyed by sheet name.
         totals_row: Per-sheet totals formulas — dict keyed by sheet name.
         totals_label: Per-sheet totals label — dict keyed by sheet name.
         totals_format: Per-sheet totals row format — dict keyed by sheet name.
         formula_columns: Per-sheet computed columns — dict keyed by sheet name.
+        conditional_formats: Per-sheet conditional formatting — dict keyed by sheet name.
+        data_validation: Per-sheet data validation — dict keyed by sheet name.
+        table: Per-sheet Excel table — dict keyed by sheet name. Unnamed tables
+            are numbered ``Table1``, ``Table2``, …; names must be unique.
+        charts: Per-sheet charts — dict keyed by sheet name. A chart's
+            ``sheet`` may name any sheet in the workbook, or a new one.
+        images: Per-sheet floating images — dict keyed by sheet name.
+        comments: Per-sheet cell notes — dict keyed by sheet name.
+        properties: Workbook document properties — one dict for the whole
+            workbook, not keyed by sheet. See :func:`write_worksheet`.
+        reproducible: Byte-identical output for identical input. See
+            :func:`write_worksheet`.
+        overflow: Per-sheet handling of rows past Excel's row limit — dict
+            keyed by sheet name. Continuation sheets skip names already in use.
+        parallel: Write the rows of sheets fed by an Arrow stream on a thread
+            pool, each stream read into memory first. Other sheets, and any
+            that use ``overflow="split"`` or a ``row_formats`` entry below the
+            header, are written as usual. The file is identical to the serial
+            one.
+        max_threads: Cap on the threads ``parallel=True`` uses (default: one
+            per CPU core). Must be at least 1.
+        wide_decimals: Per-sheet handling of Arrow decimals past 15 significant
+            digits — dict keyed by sheet name. See :func:`write_worksheet`.
+        durations: Per-sheet duration output, ``"elapsed"`` or ``"seconds"`` —
+            dict keyed by sheet name. See :func:`write_worksheet`.
+        nested_columns: Per-sheet handling of list, struct and dict values —
+            dict keyed by sheet name. See :func:`write_worksheet`.
+        timezone: Per-sheet zone for timezone-aware datetimes — dict keyed by
+            sheet name. See :func:`write_worksheet`.
+        int_overflow: Per-sheet handling of integers past 2^53 — dict keyed by
+            sheet name. See :func:`write_worksheet`.
+        id_columns: Per-sheet columns written as text — dict keyed by sheet
+            name. See :func:`write_worksheet`.
+        na_rep: Per-sheet text for missing values — dict keyed by sheet name.
+            See :func:`write_worksheet`.
+        nan_rep: Per-sheet text for NaN — dict keyed by sheet name.
+        inf_rep: Per-sheet text for ±infinity — dict keyed by sheet name.
+        error_columns: Per-sheet columns whose error spellings become error
+            cells — dict keyed by sheet name.
+        columns: Per-sheet columns to write, in order — dict keyed by sheet
+            name. See :func:`write_worksheet`.
+        missing: Per-sheet handling of absent keys — dict keyed by sheet name.
+        extra_keys: Per-sheet handling of keys that are not columns — dict
+            keyed by sheet name.
+        scan_rows: Per-sheet read-ahead for ``extra_keys="append"`` — dict
+            keyed by sheet name.
+        rename: Per-sheet ``{source name: header}`` — dict keyed by sheet
+            name.
+        header_groups: Per-sheet header separator — dict keyed by sheet name.
+            See :func:`write_worksheet`.
+        index: Per-sheet flag to write a pandas index — dict keyed by sheet
+            name.
+        merge_index_cells: Per-sheet flag to merge repeated outer index
+            labels — dict keyed by sheet name.
+        row_groups: Per-sheet ``(first_row, last_row, level)`` outline
+            groups — dict keyed by sheet name. See :func:`write_worksheet`.
+        column_groups: Per-sheet ``(first_col, last_col, level)`` outline
+            groups — dict keyed by sheet name.
+        outline_column: Per-sheet column of row levels — dict keyed by sheet
+            name.
+        outline_collapsed: Per-sheet flag to write the outline groups folded
+            away — dict keyed by sheet name.
 
     Raises:
         ValueError: Invalid sheet name or unsupported data type.
@@ -413,6 +804,18 @@ def write_csv(
     file_name: FileTarget,
     delimiter: Optional[str] = None,
     sanitize_formulas: bool = False,
+    nested_columns: Optional[str] = None,
+    timezone: Optional[str] = None,
+    utc_offsets: bool = False,
+    datetime_precision: Optional[int] = None,
+    na_rep: Optional[str] = None,
+    nan_rep: Optional[str] = None,
+    inf_rep: Optional[str] = None,
+    columns: Optional[List[str]] = None,
+    missing: Optional[str] = None,
+    extra_keys: Optional[str] = None,
+    scan_rows: Optional[int] = None,
+    rename: Optional[Dict[str, str]] = None,
 ) -> None:
     """Write data to a CSV file.
 
@@ -424,6 +827,31 @@ def write_csv(
         sanitize_formulas: When ``True``, string fields starting with
             ``= + - @`` are prefixed with ``'`` to neutralize CSV formula
             injection. Off by default (output stays byte-identical).
+        nested_columns: How list, struct and dict values are written, as for
+            :func:`write_worksheet`.
+        timezone: The zone timezone-aware datetimes are written in, as for
+            :func:`write_worksheet`.
+        utc_offsets: When ``True``, timezone-aware datetimes are followed by
+            their ISO 8601 UTC offset, e.g. ``2024-03-01T09:30:00+01:00``.
+            Naive values never get one.
+        datetime_precision: Digits after the seconds of a datetime, time or
+            duration, 0 to 6, cut off rather than rounded. By default they
+            are as many as the value needs: none for whole seconds, three for
+            whole milliseconds, else six.
+        na_rep: What a missing value is written as, as for
+            :func:`write_worksheet`; an Excel error value is written as its
+            spelling, e.g. ``#N/A``. An empty field by default.
+        nan_rep: The same for NaN.
+        inf_rep: The same for ±infinity.
+        columns: The source columns to write, in order, as for
+            :func:`write_worksheet`.
+        missing: A record that lacks one of the columns, as for
+            :func:`write_worksheet`.
+        extra_keys: A record key that is not one of the columns, as for
+            :func:`write_worksheet`.
+        scan_rows: Records read ahead for ``extra_keys="append"``, as for
+            :func:`write_worksheet`.
+        rename: ``{source name: header}``, as for :func:`write_worksheet`.
 
     Examples:
         >>> write_csv([{"Name": "Alice", "Age": 30}], "out.csv")
@@ -507,6 +935,15 @@ __all__ = [
     "FreezePanesConfig",
     "ColumnWidths",
     "ColumnFormats",
+    "ConditionalRule",
+    "ConditionalFormats",
+    "DataValidations",
+    "TableOptions",
+    "Chart",
+    "Charts",
+    "FloatingImage",
+    "Comments",
+    "DocProperties",
     "SheetData",
     "SheetEntry",
     "SheetMap",
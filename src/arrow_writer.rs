@@ -1,13 +1,23 @@
+use std::sync::Arc;
+
 use arrow_array::cast::AsArray;
 use arrow_array::types::*;
 use arrow_array::{Array, ArrayRef, BooleanArray, RecordBatch};
-use arrow_schema::{DataType, TimeUnit};
+use arrow_schema::{DataType, Schema, TimeUnit};
 use pyo3::prelude::*;
 use rust_xlsxwriter::{ExcelDateTime, Format, Worksheet};
 
+use crate::csv_writer::CsvOptions;
 use crate::helpers::{
-    write_bool_opt, write_csv_escaped_guarded, write_num, write_number_opt, write_string_opt,
+    emit_duration_csv, emit_fraction_csv, emit_time_csv, value_err, write_bool_opt,
+    write_csv_escaped_guarded, write_duration, write_int_cell, write_num, write_number_opt,
+    write_string_opt, Durations, FractionDigits, IntOverflow, SECONDS_PER_DAY,
+};
+use crate::missing::{
+    emit_special_csv, excel_error, write_error_cell, write_special, Reps, Special,
 };
+use crate::nested::{NestedColumns, JOIN_SEPARATOR};
+use crate::timezone::Zone;
 use crate::worksheet::xlsx_err;
 
 /// Column type classification done once (outside the row loop) to avoid
@@ -32,6 +42,12 @@ enum ColKind {
     Date32,
     Date64,
     Timestamp(TimeUnit),
+    Time(TimeUnit),
+    Duration(TimeUnit),
+    Decimal128(u8, i8),
+    Decimal256(u8, i8),
+    /// A list, struct or map; see [`nested_text`].
+    Nested,
     Unsupported,
 }
 
@@ -55,10 +71,196 @@ fn classify(dt: &DataType) -> ColKind {
         DataType::Date32 => ColKind::Date32,
         DataType::Date64 => ColKind::Date64,
         DataType::Timestamp(unit, _) => ColKind::Timestamp(*unit),
+        DataType::Time32(unit) | DataType::Time64(unit) => ColKind::Time(*unit),
+        DataType::Duration(unit) => ColKind::Duration(*unit),
+        DataType::Decimal128(precision, scale) => ColKind::Decimal128(*precision, *scale),
+        DataType::Decimal256(precision, scale) => ColKind::Decimal256(*precision, *scale),
+        DataType::List(_)
+        | DataType::LargeList(_)
+        | DataType::FixedSizeList(..)
+        | DataType::Struct(_)
+        | DataType::Map(..) => ColKind::Nested,
+        // Written through its values; see [`CellSource`].
+        DataType::Dictionary(_, values) if !matches!(**values, DataType::Dictionary(..)) => {
+            classify(values)
+        }
         _ => ColKind::Unsupported,
     }
 }
 
+/// A column as the cell loops read it. A dictionary column — a pandas
+/// categorical, a Polars `Categorical` or `Enum` — is read through its values:
+/// each row points at one of the distinct values, which are stored once per
+/// batch rather than once per row. [`classify`] reports the values' kind.
+struct CellSource {
+    column: ArrayRef,
+    /// The array the cells are read from: the column itself, or its values.
+    values: ArrayRef,
+    /// Per-row index into `values`, for a dictionary column.
+    keys: Option<Vec<usize>>,
+}
+
+impl CellSource {
+    fn new(column: &ArrayRef) -> Self {
+        match column.as_any_dictionary_opt() {
+            Some(dict) => CellSource {
+                column: column.clone(),
+                values: dict.values().clone(),
+                // `normalized_keys` needs a value to point at; with none,
+                // every row is null.
+                keys: Some(if dict.values().is_empty() {
+                    Vec::new()
+                } else {
+                    dict.normalized_keys()
+                }),
+            },
+            None => CellSource {
+                column: column.clone(),
+                values: column.clone(),
+                keys: None,
+            },
+        }
+    }
+
+    /// The index into `values` holding row `row`, or `None` when it is null.
+    fn value_row(&self, row: usize) -> Option<usize> {
+        if self.column.is_null(row) {
+            return None;
+        }
+        let index = match &self.keys {
+            Some(keys) => *keys.get(row)?,
+            None => row,
+        };
+        (!self.values.is_null(index)).then_some(index)
+    }
+}
+
+/// Significant digits Excel keeps in a number.
+const EXCEL_DIGITS: u32 = 15;
+
+/// What to do with a decimal that has more significant digits than Excel
+/// keeps (`wide_decimals`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum WideDecimals {
+    /// Write the nearest number, as Excel would on typing it in.
+    #[default]
+    Number,
+    /// Write the exact digits as a text cell.
+    Text,
+    /// Raise, naming the column.
+    Error,
+}
+
+impl WideDecimals {
+    pub fn parse(value: Option<&str>) -> PyResult<Self> {
+        match value {
+            None | Some("number") => Ok(WideDecimals::Number),
+            Some("text") => Ok(WideDecimals::Text),
+            Some("error") => Ok(WideDecimals::Error),
+            Some(other) => Err(value_err(format!(
+                "wide_decimals must be 'number', 'text' or 'error', got '{other}'"
+            ))),
+        }
+    }
+}
+
+/// A decimal value as Excel will see it.
+struct DecimalCell {
+    number: f64,
+    /// More significant digits than Excel keeps, so `number` is rounded.
+    wide: bool,
+}
+
+impl DecimalCell {
+    /// From the unscaled integer of a decimal with `scale`; `exact` renders
+    /// the value and is only called when the fast conversion would round.
+    fn new(unscaled: i128, scale: i8, exact: impl FnOnce() -> String) -> Self {
+        let mut digits = unscaled.unsigned_abs();
+        while digits != 0 && digits.is_multiple_of(10) {
+            digits /= 10;
+        }
+        let wide = digits >= 10u128.pow(EXCEL_DIGITS);
+        // Both sides are exact in an f64 here, so one division rounds once.
+        let number = if unscaled.unsigned_abs() <= 1 << 53 && scale.unsigned_abs() <= 22 {
+            let power = 10f64.powi(i32::from(scale.unsigned_abs()));
+            if scale >= 0 {
+                unscaled as f64 / power
+            } else {
+                unscaled as f64 * power
+            }
+        } else {
+            exact().parse().unwrap_or(f64::NAN)
+        };
+        DecimalCell { number, wide }
+    }
+
+    /// A value past `i128`, always wider than Excel keeps.
+    fn wide(exact: &str) -> Self {
+        DecimalCell {
+            number: exact.parse().unwrap_or(f64::NAN),
+            wide: true,
+        }
+    }
+}
+
+/// The cell format of a decimal column: the row's own format with a number
+/// format showing the column's scale, `0.00` for `decimal(p, 2)`.
+fn decimal_format(pal: &crate::format::RowPalette, scale: i8) -> Format {
+    let num_format = if scale > 0 {
+        format!("0.{}", "0".repeat(scale as usize))
+    } else {
+        "0".to_string()
+    };
+    pal.text.clone().unwrap_or_default().set_num_format(num_format)
+}
+
+/// [`decimal_format`] for each decimal column of `kinds`, `None` elsewhere.
+fn decimal_formats(kinds: &[ColKind], pal: &crate::format::RowPalette) -> Vec<Option<Format>> {
+    kinds
+        .iter()
+        .map(|kind| match kind {
+            ColKind::Decimal128(_, scale) | ColKind::Decimal256(_, scale) => {
+                Some(decimal_format(pal, *scale))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Write one decimal cell. `num_fmt` carries the scale format, `text_fmt` is
+/// used when a wide value is kept as text.
+#[allow(clippy::too_many_arguments)]
+fn write_decimal(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    cell: DecimalCell,
+    exact: impl FnOnce() -> String,
+    wide_decimals: WideDecimals,
+    num_fmt: Option<&Format>,
+    text_fmt: Option<&Format>,
+    column: &str,
+    reps: &Reps,
+) -> PyResult<()> {
+    if cell.wide {
+        match wide_decimals {
+            WideDecimals::Number => {}
+            WideDecimals::Text => {
+                return write_string_opt(worksheet, row, col, &exact(), text_fmt);
+            }
+            WideDecimals::Error => {
+                return Err(value_err(format!(
+                    "column '{column}': {} has more than {EXCEL_DIGITS} significant digits and \
+would be rounded in Excel. Pass wide_decimals=\"text\" to keep the exact digits, or \
+wide_decimals=\"number\" to round",
+                    exact()
+                )));
+            }
+        }
+    }
+    write_num(worksheet, row, col, cell.number, num_fmt, reps)
+}
+
 /// Write an Arrow `RecordBatch` starting at `start_row`.
 /// Column types are classified once; each cell dispatches on the cached kind.
 ///
@@ -100,14 +302,27 @@ pub fn write_arrow_batch(
     banded: Option<&crate::format::RowPalette>,
     layout: &crate::helpers::SheetLayout,
     url_cols: &[bool],
+    image_cols: &mut crate::image::ImageColumns,
     formula_cols: &[crate::helpers::FormulaColumn],
     n_data_cols: usize,
+    wide_decimals: WideDecimals,
+    durations: Durations,
+    nested: NestedColumns,
+    zones: &[Option<Arc<Zone>>],
+    int_overflow: IntOverflow,
+    id_cols: &[bool],
+    error_cols: &[bool],
+    reps: &Reps,
 ) -> PyResult<()> {
     let num_cols = batch.num_columns();
     let num_rows = batch.num_rows();
 
-    let columns: Vec<ArrayRef> = (0..num_cols).map(|c| batch.column(c).clone()).collect();
-    let kinds: Vec<ColKind> = columns.iter().map(|c| classify(c.data_type())).collect();
+    let sources: Vec<CellSource> = batch.columns().iter().map(CellSource::new).collect();
+    let kinds: Vec<ColKind> = batch
+        .columns()
+        .iter()
+        .map(|c| classify(c.data_type()))
+        .collect();
     // Per-column format override is fixed for the whole column *within a
     // palette* — resolve both variants once instead of per cell in the row×col
     // loop below.
@@ -116,16 +331,21 @@ pub fn write_arrow_batch(
         Some(b) => (0..num_cols).map(|c| b.col(c)).collect(),
         None => Vec::new(),
     };
+    let plain_decimals = decimal_formats(&kinds, plain);
+    let banded_decimals = match banded {
+        Some(b) => decimal_formats(&kinds, b),
+        None => Vec::new(),
+    };
     let banding = layout.band_color.is_some();
 
     for row in 0..num_rows {
         let row_u32 = start_row + row as u32;
         let use_band = layout.is_banded(row_u32);
         let pal = if use_band { banded.unwrap_or(plain) } else { plain };
-        let overrides = if use_band && banded.is_some() {
-            &banded_cols
+        let (overrides, decimals) = if use_band && banded.is_some() {
+            (&banded_cols, &banded_decimals)
         } else {
-            &plain_cols
+            (&plain_cols, &plain_decimals)
         };
         // On a band row this carries the fill for cells that would otherwise
         // be written unformatted.
@@ -133,27 +353,49 @@ pub fn write_arrow_batch(
 
         for col_idx in 0..num_cols {
             let col_u16 = col_idx as u16;
-            let column = &columns[col_idx];
+            let column = &sources[col_idx].values;
             let col_override = overrides[col_idx].or(text_fmt);
 
-            if column.is_null(row) {
-                write_string_opt(worksheet, row_u32, col_u16, "", text_fmt)?;
+            let Some(row) = sources[col_idx].value_row(row) else {
+                write_special(worksheet, row_u32, col_u16, Special::Missing, reps, text_fmt)?;
+                continue;
+            };
+            if image_cols.is_image(col_idx) {
+                image_cols.write_arrow(worksheet, row_u32, col_u16, column, row, col_override)?;
                 continue;
             }
 
+            if id_cols.get(col_idx).copied().unwrap_or(false) {
+                if let Some(text) = id_text(column, kinds[col_idx], row) {
+                    write_string_opt(worksheet, row_u32, col_u16, &text, col_override)?;
+                    continue;
+                }
+            }
+
             macro_rules! write_int {
                 ($ty:ty) => {{
-                    let val = column.as_primitive::<$ty>().value(row) as f64;
-                    write_number_opt(worksheet, row_u32, col_u16, val, col_override)?;
+                    write_int_cell(
+                        worksheet,
+                        row_u32,
+                        col_u16,
+                        i128::from(column.as_primitive::<$ty>().value(row)),
+                        int_overflow,
+                        col_override,
+                        batch.schema_ref().field(col_idx).name(),
+                    )?;
                 }};
             }
             // A url_columns entry turns text cells into links; anything that
             // is not a valid URL falls back to plain text.
             let as_url = url_cols.get(col_idx).copied().unwrap_or(false);
+            // An error_columns entry turns error spellings into error cells.
+            let as_error = error_cols.get(col_idx).copied().unwrap_or(false);
             macro_rules! write_str {
                 ($val:expr) => {{
                     let val: &str = $val;
-                    if as_url && !val.is_empty() {
+                    if let Some(error) = as_error.then(|| excel_error(val)).flatten() {
+                        write_error_cell(worksheet, row_u32, col_u16, error, col_override)?
+                    } else if as_url && !val.is_empty() {
                         crate::helpers::write_url_or_text(
                             worksheet, row_u32, col_u16, val, col_override,
                         )?
@@ -174,7 +416,7 @@ pub fn write_arrow_batch(
                 ColKind::UInt64 => write_int!(UInt64Type),
                 ColKind::Float16 => {
                     let val = column.as_primitive::<Float16Type>().value(row).to_f64();
-                    write_number_opt(worksheet, row_u32, col_u16, val, col_override)?;
+                    write_num(worksheet, row_u32, col_u16, val, col_override, reps)?;
                 }
                 ColKind::Float32 => {
                     let val = column.as_primitive::<Float32Type>().value(row) as f64;
@@ -184,6 +426,7 @@ pub fn write_arrow_batch(
                         col_u16,
                         val,
                         overrides[col_idx].or(pal.float.as_ref()),
+                        reps,
                     )?;
                 }
                 ColKind::Float64 => {
@@ -194,6 +437,7 @@ pub fn write_arrow_batch(
                         col_u16,
                         val,
                         overrides[col_idx].or(pal.float.as_ref()),
+                        reps,
                     )?;
                 }
                 ColKind::Bool => {
@@ -233,7 +477,8 @@ pub fn write_arrow_batch(
                     )?;
                 }
                 ColKind::Timestamp(unit) => {
-                    let micros = timestamp_to_micros(column, unit, row);
+                    let instant = timestamp_to_micros(column, unit, row);
+                    let (micros, _) = wall_micros(instant, zones[col_idx].as_deref());
                     write_temporal(
                         worksheet,
                         row_u32,
@@ -244,6 +489,68 @@ pub fn write_arrow_batch(
                         text_fmt,
                     )?;
                 }
+                ColKind::Time(unit) => {
+                    let seconds = time_ticks(column, unit, row) as f64 / ticks_per_second(unit);
+                    write_number_opt(
+                        worksheet,
+                        row_u32,
+                        col_u16,
+                        seconds / SECONDS_PER_DAY,
+                        Some(overrides[col_idx].unwrap_or(&pal.time)),
+                    )?;
+                }
+                ColKind::Duration(unit) => {
+                    let seconds = duration_ticks(column, unit, row) as f64 / ticks_per_second(unit);
+                    write_duration(
+                        worksheet,
+                        row_u32,
+                        col_u16,
+                        seconds,
+                        durations,
+                        overrides[col_idx].unwrap_or(&pal.duration),
+                        col_override,
+                    )?;
+                }
+                ColKind::Decimal128(precision, scale) => {
+                    let value = column.as_primitive::<Decimal128Type>().value(row);
+                    let exact = || Decimal128Type::format_decimal(value, precision, scale);
+                    write_decimal(
+                        worksheet,
+                        row_u32,
+                        col_u16,
+                        DecimalCell::new(value, scale, exact),
+                        exact,
+                        wide_decimals,
+                        overrides[col_idx].or(decimals[col_idx].as_ref()),
+                        col_override,
+                        batch.schema_ref().field(col_idx).name(),
+                        reps,
+                    )?;
+                }
+                ColKind::Decimal256(precision, scale) => {
+                    let value = column.as_primitive::<Decimal256Type>().value(row);
+                    let exact = || Decimal256Type::format_decimal(value, precision, scale);
+                    let cell = match value.to_i128() {
+                        Some(small) => DecimalCell::new(small, scale, exact),
+                        None => DecimalCell::wide(&exact()),
+                    };
+                    write_decimal(
+                        worksheet,
+                        row_u32,
+                        col_u16,
+                        cell,
+                        exact,
+                        wide_decimals,
+                        overrides[col_idx].or(decimals[col_idx].as_ref()),
+                        col_override,
+                        batch.schema_ref().field(col_idx).name(),
+                        reps,
+                    )?;
+                }
+                ColKind::Nested => {
+                    let text = nested_text(column, row, nested);
+                    write_string_opt(worksheet, row_u32, col_u16, &text, col_override)?;
+                }
                 ColKind::Unsupported => {
                     write_string_opt(worksheet, row_u32, col_u16, "", text_fmt)?;
                 }
@@ -268,41 +575,293 @@ pub fn write_arrow_batch(
 
 /// Emit an Arrow `RecordBatch` as CSV rows (no header — caller writes it).
 /// Zero-copy over the Arrow buffers; only the output bytes are newly
-/// allocated.
+/// allocated. `first_row` is the batch's first data row, counted from 1, for
+/// errors.
 pub fn write_arrow_batch_csv(
     output: &mut Vec<u8>,
     batch: &RecordBatch,
     delim: u8,
-    sanitize: bool,
+    opts: &CsvOptions,
+    zones: &[Option<Arc<Zone>>],
+    first_row: usize,
 ) -> PyResult<()> {
+    let (sanitize, reps) = (opts.sanitize, &opts.reps);
     let num_cols = batch.num_columns();
     let num_rows = batch.num_rows();
 
-    let columns: Vec<ArrayRef> = (0..num_cols).map(|c| batch.column(c).clone()).collect();
-    let kinds: Vec<ColKind> = columns.iter().map(|c| classify(c.data_type())).collect();
+    let sources: Vec<CellSource> = batch.columns().iter().map(CellSource::new).collect();
+    let kinds: Vec<ColKind> = batch
+        .columns()
+        .iter()
+        .map(|c| classify(c.data_type()))
+        .collect();
 
     for row in 0..num_rows {
         for col_idx in 0..num_cols {
             if col_idx > 0 {
                 output.push(delim);
             }
-            let column = &columns[col_idx];
-            if column.is_null(row) {
+            let source = &sources[col_idx];
+            let data_row = first_row + row;
+            let Some(row) = source.value_row(row) else {
+                emit_special_csv(output, Special::Missing, reps, sanitize, data_row, col_idx)?;
                 continue;
+            };
+            let special = arrow_float(&source.values, kinds[col_idx], row).and_then(Special::of);
+            if let Some(special) = special {
+                emit_special_csv(output, special, reps, sanitize, data_row, col_idx)?;
+            } else if let ColKind::Nested = kinds[col_idx] {
+                let text = nested_text(&source.values, row, opts.nested);
+                write_csv_escaped_guarded(output, &text, sanitize);
+            } else if let (ColKind::Timestamp(unit), Some(zone)) =
+                (kinds[col_idx], &zones[col_idx])
+            {
+                let instant = timestamp_to_micros(&source.values, unit, row);
+                let (micros, offset) = wall_micros(instant, Some(zone));
+                let before = output.len();
+                emit_timestamp_csv(output, micros, opts.digits);
+                if opts.offsets && output.len() > before {
+                    let offset = offset.expect("a zone gives an offset");
+                    output.extend_from_slice(crate::timezone::format_offset(offset).as_bytes());
+                }
+            } else {
+                let (values, kind) = (&source.values, kinds[col_idx]);
+                emit_arrow_cell_csv(output, values, kind, row, sanitize, opts.digits);
             }
-            emit_arrow_cell_csv(output, column, kinds[col_idx], row, sanitize);
         }
         output.push(b'\n');
     }
     Ok(())
 }
 
+/// The text of each cell of an Arrow column, `None` for nulls: strings as they
+/// are, anything else rendered as the CSV writer would. For values that become
+/// text rather than a cell, such as notes.
+pub fn arrow_cell_texts(column: &ArrayRef) -> Vec<Option<String>> {
+    let source = CellSource::new(column);
+    let kind = classify(column.data_type());
+    (0..column.len())
+        .map(|row| Some(cell_text(&source.values, kind, source.value_row(row)?)))
+        .collect()
+}
+
+/// The text of the non-null cell at `row` of `values`, whose kind is `kind`;
+/// nested values as JSON.
+fn cell_text(values: &ArrayRef, kind: ColKind, row: usize) -> String {
+    match kind {
+        ColKind::Utf8 => values.as_string::<i32>().value(row).to_string(),
+        ColKind::LargeUtf8 => values.as_string::<i64>().value(row).to_string(),
+        ColKind::Utf8View => values.as_string_view().value(row).to_string(),
+        ColKind::Nested => nested_text(values, row, NestedColumns::Json),
+        _ => {
+            let mut out = Vec::new();
+            emit_arrow_cell_csv(&mut out, values, kind, row, false, FractionDigits::Auto);
+            String::from_utf8_lossy(&out).into_owned()
+        }
+    }
+}
+
+/// The text of a non-null `id_columns` cell: [`cell_text`], except that a
+/// whole float is written as its digits, `42` rather than `42.0`. `None` for
+/// NaN and infinity, which are written per `nan_rep` and `inf_rep` instead.
+fn id_text(values: &ArrayRef, kind: ColKind, row: usize) -> Option<String> {
+    let Some(float) = arrow_float(values, kind, row) else {
+        return Some(cell_text(values, kind, row));
+    };
+    if !float.is_finite() {
+        None
+    } else if float.fract() == 0.0 {
+        Some(format!("{float:.0}"))
+    } else {
+        Some(cell_text(values, kind, row))
+    }
+}
+
+/// The value at `row` of a float column, `None` for any other kind.
+fn arrow_float(values: &ArrayRef, kind: ColKind, row: usize) -> Option<f64> {
+    match kind {
+        ColKind::Float16 => Some(values.as_primitive::<Float16Type>().value(row).to_f64()),
+        ColKind::Float32 => Some(f64::from(values.as_primitive::<Float32Type>().value(row))),
+        ColKind::Float64 => Some(values.as_primitive::<Float64Type>().value(row)),
+        _ => None,
+    }
+}
+
+/// Refuse a schema with nested columns under `nested_columns="error"`, before
+/// anything is written.
+pub fn check_nested(schema: &Schema, nested: NestedColumns) -> PyResult<()> {
+    if nested != NestedColumns::Error {
+        return Ok(());
+    }
+    match schema
+        .fields()
+        .iter()
+        .find(|f| matches!(classify(f.data_type()), ColKind::Nested))
+    {
+        Some(field) => Err(crate::nested::nested_err(&format!(
+            "column '{}' holds {} values",
+            field.name(),
+            field.data_type()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The text of the nested cell at `row`: list items joined under `"join"`,
+/// JSON otherwise. Structs only get here as JSON — under `"explode"` they are
+/// columns by now.
+fn nested_text(column: &ArrayRef, row: usize, nested: NestedColumns) -> String {
+    let mut out = String::new();
+    match list_items(column, row) {
+        Some(items) if nested == NestedColumns::Join => {
+            for item in 0..items.len() {
+                if item > 0 {
+                    out.push_str(JOIN_SEPARATOR);
+                }
+                if let Some((values, row)) = resolve(&items, item) {
+                    match classify(values.data_type()) {
+                        ColKind::Nested => json_value(&mut out, &values, row),
+                        kind => out.push_str(&cell_text(&values, kind, row)),
+                    }
+                }
+            }
+        }
+        _ => json_value(&mut out, column, row),
+    }
+    out
+}
+
+/// The items of the list at `row`, if `column` is a list column.
+fn list_items(column: &ArrayRef, row: usize) -> Option<ArrayRef> {
+    match column.data_type() {
+        DataType::List(_) => Some(column.as_list::<i32>().value(row)),
+        DataType::LargeList(_) => Some(column.as_list::<i64>().value(row)),
+        DataType::FixedSizeList(..) => Some(column.as_fixed_size_list().value(row)),
+        _ => None,
+    }
+}
+
+/// The array and index holding the value at `row`, through a dictionary if
+/// need be, or `None` when it is null.
+fn resolve(column: &ArrayRef, row: usize) -> Option<(ArrayRef, usize)> {
+    if column.as_any_dictionary_opt().is_some() {
+        // One row, so the keys normalized are only the one needed.
+        let source = CellSource::new(&column.slice(row, 1));
+        let row = source.value_row(0)?;
+        return Some((source.values, row));
+    }
+    (!column.is_null(row)).then(|| (column.clone(), row))
+}
+
+/// Append the value at `row` as JSON, spaced as Python's `json.dumps`: numbers
+/// and booleans bare, other scalars as strings of their CSV text.
+fn json_value(out: &mut String, column: &ArrayRef, row: usize) {
+    let Some((column, row)) = resolve(column, row) else {
+        out.push_str("null");
+        return;
+    };
+    if let Some(items) = list_items(&column, row) {
+        out.push('[');
+        for item in 0..items.len() {
+            if item > 0 {
+                out.push_str(", ");
+            }
+            json_value(out, &items, item);
+        }
+        out.push(']');
+        return;
+    }
+    match column.data_type() {
+        DataType::Struct(fields) => {
+            let strukt = column.as_struct();
+            out.push('{');
+            for (idx, field) in fields.iter().enumerate() {
+                if idx > 0 {
+                    out.push_str(", ");
+                }
+                json_string(out, field.name());
+                out.push_str(": ");
+                json_value(out, strukt.column(idx), row);
+            }
+            out.push('}');
+        }
+        DataType::Map(..) => {
+            let entries = column.as_map().value(row);
+            out.push('{');
+            for entry in 0..entries.len() {
+                if entry > 0 {
+                    out.push_str(", ");
+                }
+                let key = match resolve(entries.column(0), entry) {
+                    Some((keys, row)) => cell_text(&keys, classify(keys.data_type()), row),
+                    None => "null".to_string(),
+                };
+                json_string(out, &key);
+                out.push_str(": ");
+                json_value(out, entries.column(1), entry);
+            }
+            out.push('}');
+        }
+        _ => {
+            let kind = classify(column.data_type());
+            let text = cell_text(&column, kind, row);
+            match kind {
+                ColKind::Unsupported => out.push_str("null"),
+                // Non-finite floats have no CSV text, and no JSON either.
+                _ if text.is_empty() && is_number(kind) => out.push_str("null"),
+                _ if is_number(kind) || matches!(kind, ColKind::Bool) => out.push_str(&text),
+                _ => json_string(out, &text),
+            }
+        }
+    }
+}
+
+fn is_number(kind: ColKind) -> bool {
+    matches!(
+        kind,
+        ColKind::Int8
+            | ColKind::Int16
+            | ColKind::Int32
+            | ColKind::Int64
+            | ColKind::UInt8
+            | ColKind::UInt16
+            | ColKind::UInt32
+            | ColKind::UInt64
+            | ColKind::Float16
+            | ColKind::Float32
+            | ColKind::Float64
+            | ColKind::Decimal128(..)
+            | ColKind::Decimal256(..)
+    )
+}
+
+/// Append `s` as a JSON string.
+fn json_string(out: &mut String, s: &str) {
+    use std::fmt::Write;
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 fn emit_arrow_cell_csv(
     output: &mut Vec<u8>,
     column: &ArrayRef,
     kind: ColKind,
     row: usize,
     sanitize: bool,
+    digits: FractionDigits,
 ) {
     use std::io::Write;
 
@@ -363,12 +922,32 @@ fn emit_arrow_cell_csv(
         }
         ColKind::Date64 => {
             let ms = column.as_primitive::<Date64Type>().value(row);
-            emit_timestamp_csv(output, ms * 1000);
+            emit_timestamp_csv(output, ms * 1000, digits);
         }
         ColKind::Timestamp(unit) => {
-            emit_timestamp_csv(output, timestamp_to_micros(column, unit, row));
+            emit_timestamp_csv(output, timestamp_to_micros(column, unit, row), digits);
+        }
+        ColKind::Time(unit) => {
+            let micros = ticks_to_micros(time_ticks(column, unit, row), unit);
+            emit_time_csv(output, micros as u64, digits);
+        }
+        ColKind::Duration(unit) => {
+            let micros = ticks_to_micros(duration_ticks(column, unit, row), unit);
+            emit_duration_csv(output, micros, digits);
         }
-        ColKind::Unsupported => {}
+        // The exact digits, which a float could not carry.
+        ColKind::Decimal128(precision, scale) => {
+            let value = column.as_primitive::<Decimal128Type>().value(row);
+            let text = Decimal128Type::format_decimal(value, precision, scale);
+            output.extend_from_slice(text.as_bytes());
+        }
+        ColKind::Decimal256(precision, scale) => {
+            let value = column.as_primitive::<Decimal256Type>().value(row);
+            let text = Decimal256Type::format_decimal(value, precision, scale);
+            output.extend_from_slice(text.as_bytes());
+        }
+        // Rendered by `nested_text`, which needs the policy.
+        ColKind::Nested | ColKind::Unsupported => {}
     }
 }
 
@@ -379,33 +958,106 @@ fn timestamp_to_micros(column: &ArrayRef, unit: TimeUnit, row: usize) -> i64 {
         TimeUnit::Second => column.as_primitive::<TimestampSecondType>().value(row) * 1_000_000,
         TimeUnit::Millisecond => column.as_primitive::<TimestampMillisecondType>().value(row) * 1_000,
         TimeUnit::Microsecond => column.as_primitive::<TimestampMicrosecondType>().value(row),
-        TimeUnit::Nanosecond => column.as_primitive::<TimestampNanosecondType>().value(row) / 1_000,
+        TimeUnit::Nanosecond => {
+            column.as_primitive::<TimestampNanosecondType>().value(row).div_euclid(1_000)
+        }
+    }
+}
+
+/// The instant `micros` as wall time in `zone`, with the offset that took;
+/// unchanged without a zone.
+fn wall_micros(micros: i64, zone: Option<&Zone>) -> (i64, Option<i32>) {
+    match zone {
+        None => (micros, None),
+        Some(zone) => {
+            let offset = zone.offset_at(micros.div_euclid(1_000_000));
+            (micros + i64::from(offset) * 1_000_000, Some(offset))
+        }
+    }
+}
+
+/// Ticks per second of a time or duration `unit`.
+fn ticks_per_second(unit: TimeUnit) -> f64 {
+    match unit {
+        TimeUnit::Second => 1.0,
+        TimeUnit::Millisecond => 1e3,
+        TimeUnit::Microsecond => 1e6,
+        TimeUnit::Nanosecond => 1e9,
+    }
+}
+
+/// `ticks` of a time or duration `unit` in microseconds; nanoseconds are cut
+/// to microseconds, toward zero.
+fn ticks_to_micros(ticks: i64, unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => ticks * 1_000_000,
+        TimeUnit::Millisecond => ticks * 1_000,
+        TimeUnit::Microsecond => ticks,
+        TimeUnit::Nanosecond => ticks / 1_000,
+    }
+}
+
+/// A time-of-day value at `row`, in ticks of its `unit` since midnight.
+/// Seconds and milliseconds are stored as `Time32`, finer units as `Time64`.
+fn time_ticks(column: &ArrayRef, unit: TimeUnit, row: usize) -> i64 {
+    match unit {
+        TimeUnit::Second => column.as_primitive::<Time32SecondType>().value(row) as i64,
+        TimeUnit::Millisecond => column.as_primitive::<Time32MillisecondType>().value(row) as i64,
+        TimeUnit::Microsecond => column.as_primitive::<Time64MicrosecondType>().value(row),
+        TimeUnit::Nanosecond => column.as_primitive::<Time64NanosecondType>().value(row),
+    }
+}
+
+/// A duration value at `row`, in ticks of its `unit`.
+fn duration_ticks(column: &ArrayRef, unit: TimeUnit, row: usize) -> i64 {
+    match unit {
+        TimeUnit::Second => column.as_primitive::<DurationSecondType>().value(row),
+        TimeUnit::Millisecond => column.as_primitive::<DurationMillisecondType>().value(row),
+        TimeUnit::Microsecond => column.as_primitive::<DurationMicrosecondType>().value(row),
+        TimeUnit::Nanosecond => column.as_primitive::<DurationNanosecondType>().value(row),
     }
 }
 
-/// Decompose micros-since-epoch into civil `(year, month, day, hour, minute,
-/// second)`. Returns `None` if the date is out of Excel's 0..=9999 range.
-fn micros_to_ymdhms(micros: i64) -> Option<(u16, u8, u8, u16, u8, u8)> {
-    let total_secs = micros / 1_000_000;
-    let days = total_secs / 86400;
-    let day_secs = (total_secs % 86400).unsigned_abs();
-    let (year, month, day) = chrono_from_days(days)?;
-    let hour = (day_secs / 3600) as u16;
-    let minute = ((day_secs % 3600) / 60) as u8;
-    let second = (day_secs % 60) as u8;
-    Some((year, month, day, hour, minute, second))
+/// A civil date and time, down to the microsecond.
+struct Civil {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u16,
+    minute: u8,
+    second: u8,
+    micros: u32,
 }
 
-fn emit_timestamp_csv(output: &mut Vec<u8>, micros: i64) {
+/// Decompose micros-since-epoch into a [`Civil`] time. Returns `None` if the
+/// date is out of Excel's 0..=9999 range. Instants before 1970 count back
+/// from the following midnight, so the time of day is never negative.
+fn micros_to_civil(micros: i64) -> Option<Civil> {
+    let total_secs = micros.div_euclid(1_000_000);
+    let day_secs = total_secs.rem_euclid(86_400) as u32;
+    let (year, month, day) = chrono_from_days(total_secs.div_euclid(86_400))?;
+    Some(Civil {
+        year,
+        month,
+        day,
+        hour: (day_secs / 3600) as u16,
+        minute: (day_secs % 3600 / 60) as u8,
+        second: (day_secs % 60) as u8,
+        micros: micros.rem_euclid(1_000_000) as u32,
+    })
+}
+
+fn emit_timestamp_csv(output: &mut Vec<u8>, micros: i64, digits: FractionDigits) {
     use std::io::Write;
-    let Some((y, m, d, hour, minute, second)) = micros_to_ymdhms(micros) else {
+    let Some(t) = micros_to_civil(micros) else {
         return;
     };
     let _ = write!(
         output,
         "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
-        y, m, d, hour, minute, second
+        t.year, t.month, t.day, t.hour, t.minute, t.second
     );
+    emit_fraction_csv(output, t.micros, digits);
 }
 
 pub fn set_datetime_column_formats(
@@ -415,9 +1067,10 @@ pub fn set_datetime_column_formats(
 ) -> PyResult<()> {
     for (col_idx, field) in batch.schema().fields().iter().enumerate() {
         if matches!(
-            field.data_type(),
-            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _)
+            classify(field.data_type()),
+            ColKind::Date32 | ColKind::Date64 | ColKind::Timestamp(_)
         ) {
+            crate::parallel::check_registered(datetime_fmt);
             worksheet
                 .set_column_format(col_idx as u16, datetime_fmt)
                 .map_err(xlsx_err)?;
@@ -426,6 +1079,86 @@ pub fn set_datetime_column_formats(
     Ok(())
 }
 
+/// Every format a constant-memory sheet of `schema` can register while its
+/// batches are written, in a fixed order: the column formats set before the
+/// first batch, then the cell formats of [`write_arrow_batch`], column by
+/// column for the plain palette and then the banded one.
+///
+/// Such sheets number formats workbook-wide on first use, so the numbering
+/// depends on which cell happens to come first. Registering this list before
+/// the first batch settles it up front, which is what lets sheets be written
+/// side by side (`parallel=True`) and still match the serial output. Debug
+/// builds assert that no cell is written with a format missing from it; see
+/// [`crate::parallel::Registered`].
+#[allow(clippy::too_many_arguments)]
+pub fn reachable_formats(
+    schema: &Schema,
+    datetime_fmt: &Format,
+    col_formats: &[Option<crate::format::Format>],
+    plain: &crate::format::RowPalette,
+    banded: Option<&crate::format::RowPalette>,
+    url_cols: &[bool],
+    id_cols: &[bool],
+    durations: Durations,
+) -> Vec<Format> {
+    let mut out = Vec::new();
+    let temporal = |kind| matches!(kind, ColKind::Date32 | ColKind::Date64 | ColKind::Timestamp(_));
+    if schema.fields().iter().any(|f| temporal(classify(f.data_type()))) {
+        out.push(datetime_fmt.clone());
+    }
+    out.extend(col_formats.iter().flatten().map(|f| f.inner.clone()));
+
+    let banding = banded.is_some();
+    for pal in std::iter::once(plain).chain(banded) {
+        let text = pal.text.as_ref();
+        for (col, field) in schema.fields().iter().enumerate() {
+            let col_override = pal.col(col);
+            if field.is_nullable() {
+                out.extend(text.cloned());
+            }
+            // NaN and infinity in a float ID column still take the float path.
+            if id_cols.get(col).copied().unwrap_or(false) {
+                out.extend(col_override.or(text).cloned());
+            }
+            match classify(field.data_type()) {
+                ColKind::Float32 | ColKind::Float64 => {
+                    out.extend(col_override.or(pal.float.as_ref()).cloned())
+                }
+                ColKind::Bool | ColKind::Unsupported => out.extend(text.cloned()),
+                ColKind::Utf8 | ColKind::LargeUtf8 | ColKind::Utf8View => {
+                    let fmt = col_override.or(text);
+                    // A link written without a format takes the hyperlink style.
+                    if fmt.is_none() && url_cols.get(col).copied().unwrap_or(false) {
+                        out.push(Format::new().set_hyperlink());
+                    }
+                    out.extend(fmt.cloned());
+                }
+                ColKind::Time(_) => out.push(col_override.unwrap_or(&pal.time).clone()),
+                ColKind::Duration(_) => match durations {
+                    Durations::Elapsed => {
+                        out.push(col_override.unwrap_or(&pal.duration).clone())
+                    }
+                    Durations::Seconds => out.extend(col_override.or(text).cloned()),
+                },
+                ColKind::Decimal128(_, scale) | ColKind::Decimal256(_, scale) => {
+                    out.push(col_override.cloned().unwrap_or_else(|| decimal_format(pal, scale)));
+                    // A wide value kept as text.
+                    out.extend(col_override.or(text).cloned());
+                }
+                ColKind::Date32 | ColKind::Date64 | ColKind::Timestamp(_) => {
+                    if banding {
+                        out.push(col_override.unwrap_or(&pal.datetime).clone());
+                    }
+                    // Out-of-range values are written as blanks.
+                    out.extend(text.cloned());
+                }
+                _ => out.extend(col_override.or(text).cloned()),
+            }
+        }
+    }
+    out
+}
+
 fn days_to_excel_date(days_since_epoch: i64) -> Option<ExcelDateTime> {
     let (y, m, d) = chrono_from_days(days_since_epoch)?;
     ExcelDateTime::from_ymd(y, m, d).ok()
@@ -436,10 +1169,11 @@ fn millis_to_excel_datetime(ms: i64) -> Option<ExcelDateTime> {
 }
 
 fn micros_to_excel_datetime(micros: i64) -> Option<ExcelDateTime> {
-    let (year, month, day, hour, minute, second) = micros_to_ymdhms(micros)?;
-    ExcelDateTime::from_ymd(year, month, day)
+    let t = micros_to_civil(micros)?;
+    let seconds = f64::from(t.second) + f64::from(t.micros) / 1e6;
+    ExcelDateTime::from_ymd(t.year, t.month, t.day)
         .ok()?
-        .and_hms(hour, minute, second)
+        .and_hms(t.hour, t.minute, seconds)
         .ok()
 }
 
@@ -456,7 +1190,7 @@ fn chrono_from_days(days: i64) -> Option<(u16, u8, u8)> {
     let m = if mp < 10 { mp + 3 } else { mp - 9 };
     let y = if m <= 2 { y + 1 } else { y };
 
-    if y < 0 || y > 9999 {
+    if !(0..=9999).contains(&y) {
         return None;
     }
     Some((y as u16, m as u8, d as u8))
@@ -6,14 +6,17 @@
 //! four hand-kept-in-sync copies the cascade used to live in.
 
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDate, PyDateTime, PyFloat, PyInt, PyString};
+use pyo3::types::{PyBool, PyDate, PyDateTime, PyDelta, PyFloat, PyInt, PyString, PyTime};
 
 use crate::helpers::ColType;
+use crate::missing::is_nat;
+use crate::sentinel::{Blank, ExcelError, Sentinel};
 
 /// A sink for one Python scalar. Implementors encode the per-target action for
 /// each detected type (formatting, escaping, etc.); the cascade itself only
 /// decides which method to call.
 pub trait CellWriter {
+    /// `None`, or pandas' `NaT`.
     fn write_none(&mut self) -> PyResult<()>;
     fn write_str(&mut self, s: &str) -> PyResult<()>;
     fn write_bool(&mut self, b: bool) -> PyResult<()>;
@@ -21,6 +24,12 @@ pub trait CellWriter {
     fn write_int(&mut self, i: &Bound<'_, PyInt>) -> PyResult<()>;
     fn write_datetime(&mut self, dt: &Bound<'_, PyDateTime>) -> PyResult<()>;
     fn write_date(&mut self, d: &Bound<'_, PyDate>) -> PyResult<()>;
+    fn write_time(&mut self, t: &Bound<'_, PyTime>) -> PyResult<()>;
+    fn write_timedelta(&mut self, d: &Bound<'_, PyDelta>) -> PyResult<()>;
+    /// A `list`, `tuple` or `dict`, rendered per `nested_columns`.
+    fn write_nested(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()>;
+    /// An `ExcelError` or `Blank`.
+    fn write_sentinel(&mut self, sentinel: Sentinel<'_>) -> PyResult<()>;
 }
 
 /// Full type cascade. Returns the detected [`ColType`] so callers can cache it
@@ -53,7 +62,11 @@ pub fn classify_and_write<W: CellWriter>(
         return Ok(ColType::Int);
     }
     if let Ok(dt) = value.cast::<PyDateTime>() {
-        w.write_datetime(dt)?;
+        if is_nat(dt) {
+            w.write_none()?;
+        } else {
+            w.write_datetime(dt)?;
+        }
         return Ok(ColType::DateTime);
     }
     // Date AFTER DateTime (datetime is a subclass of date).
@@ -61,6 +74,28 @@ pub fn classify_and_write<W: CellWriter>(
         w.write_date(d)?;
         return Ok(ColType::Date);
     }
+    if let Ok(t) = value.cast::<PyTime>() {
+        w.write_time(t)?;
+        return Ok(ColType::Time);
+    }
+    if let Ok(d) = value.cast::<PyDelta>() {
+        w.write_timedelta(d)?;
+        return Ok(ColType::Duration);
+    }
+    if crate::nested::is_nested(value) {
+        w.write_nested(value)?;
+        return Ok(ColType::Nested);
+    }
+    // Sentinels are left uncached, like `None`: they stand in for a value
+    // rather than decide the column's type.
+    if let Ok(error) = value.cast::<ExcelError>() {
+        w.write_sentinel(Sentinel::Error(error.get().spelling()))?;
+        return Ok(ColType::Unknown);
+    }
+    if let Ok(blank) = value.cast::<Blank>() {
+        w.write_sentinel(Sentinel::Blank(blank.get().format.as_ref()))?;
+        return Ok(ColType::Unknown);
+    }
     // numpy scalar fallback: bool before f64 (numpy.bool_ extracts as f64 too).
     if let Ok(val) = value.extract::<bool>() {
         w.write_bool(val)?;
@@ -118,7 +153,11 @@ pub fn try_cached<W: CellWriter>(
         }
         ColType::DateTime => {
             if let Ok(dt) = value.cast::<PyDateTime>() {
-                w.write_datetime(dt)?;
+                if is_nat(dt) {
+                    w.write_none()?;
+                } else {
+                    w.write_datetime(dt)?;
+                }
                 return Ok(true);
             }
         }
@@ -128,6 +167,24 @@ pub fn try_cached<W: CellWriter>(
                 return Ok(true);
             }
         }
+        ColType::Time => {
+            if let Ok(t) = value.cast::<PyTime>() {
+                w.write_time(t)?;
+                return Ok(true);
+            }
+        }
+        ColType::Duration => {
+            if let Ok(d) = value.cast::<PyDelta>() {
+                w.write_timedelta(d)?;
+                return Ok(true);
+            }
+        }
+        ColType::Nested => {
+            if crate::nested::is_nested(value) {
+                w.write_nested(value)?;
+                return Ok(true);
+            }
+        }
         ColType::Unknown => {}
     }
     Ok(false)
@@ -0,0 +1,465 @@
+//! Charts built from the written data, declared per sheet by header name.
+//!
+//! A chart is a drawing, not row data, so constant-memory mode is no obstacle:
+//! the ranges are resolved once the last row is written, and the charts are
+//! inserted after every sheet exists — which is what lets a chart target a
+//! sheet other than its data's, including one that has not been written yet.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyString};
+use rust_xlsxwriter::{Chart, ChartLegendPosition, ChartType, Workbook};
+
+use crate::helpers::{value_err, warn_py, OptionDict, SheetLayout};
+use crate::worksheet::xlsx_err;
+
+/// `rust_xlsxwriter`'s default chart height, in pixels.
+const DEFAULT_HEIGHT: u32 = 288;
+/// Default row height in pixels, used to stack charts without an anchor.
+const ROW_PIXELS: u32 = 20;
+
+enum Legend {
+    Hidden,
+    At(ChartLegendPosition),
+}
+
+/// The secondary-axis half of a combined chart.
+struct Secondary {
+    chart_type: ChartType,
+    values: Vec<String>,
+    y_axis_title: Option<String>,
+}
+
+/// One parsed `charts` entry. Columns are still header names; they become
+/// ranges in [`build_charts`], once the row count is known.
+pub struct ChartSpec {
+    label: String,
+    chart_type: ChartType,
+    categories: Option<String>,
+    values: Vec<String>,
+    secondary: Option<Secondary>,
+    title: Option<String>,
+    x_axis_title: Option<String>,
+    y_axis_title: Option<String>,
+    legend: Option<Legend>,
+    anchor: Option<(u32, u16)>,
+    width: Option<u32>,
+    height: Option<u32>,
+    sheet: Option<String>,
+}
+
+/// A finished chart waiting for its sheet. `anchor` is `None` when the caller
+/// left the position to us; [`place_charts`] then stacks it below the previous
+/// one, starting at `default_anchor`.
+pub struct PlacedChart {
+    sheet: String,
+    anchor: Option<(u32, u16)>,
+    default_anchor: (u32, u16),
+    height: u32,
+    chart: Chart,
+}
+
+fn is_pie(t: ChartType) -> bool {
+    matches!(t, ChartType::Pie | ChartType::Doughnut)
+}
+
+fn is_bar(t: ChartType) -> bool {
+    matches!(
+        t,
+        ChartType::Bar | ChartType::BarStacked | ChartType::BarPercentStacked
+    )
+}
+
+fn is_scatter(t: ChartType) -> bool {
+    matches!(
+        t,
+        ChartType::Scatter
+            | ChartType::ScatterStraight
+            | ChartType::ScatterStraightWithMarkers
+            | ChartType::ScatterSmooth
+            | ChartType::ScatterSmoothWithMarkers
+    )
+}
+
+/// Map `type` plus the optional `subtype` to a `ChartType`.
+fn chart_type(kind: &str, subtype: Option<&str>, label: &str) -> PyResult<ChartType> {
+    use ChartType as T;
+    let kind = kind.to_ascii_lowercase();
+    let subtype = subtype.map(str::to_ascii_lowercase);
+    let stacked = |plain, stacked, percent| match subtype.as_deref() {
+        None => Ok(plain),
+        Some("stacked") => Ok(stacked),
+        Some("percent_stacked") => Ok(percent),
+        Some(other) => Err(value_err(format!(
+            "{label}: unknown subtype '{other}' for a {kind} chart (valid: stacked, \
+percent_stacked)"
+        ))),
+    };
+    match kind.as_str() {
+        "column" => stacked(T::Column, T::ColumnStacked, T::ColumnPercentStacked),
+        "bar" => stacked(T::Bar, T::BarStacked, T::BarPercentStacked),
+        "line" => stacked(T::Line, T::LineStacked, T::LinePercentStacked),
+        "area" => stacked(T::Area, T::AreaStacked, T::AreaPercentStacked),
+        "pie" | "doughnut" => match subtype {
+            None => Ok(if kind == "pie" { T::Pie } else { T::Doughnut }),
+            Some(_) => Err(value_err(format!(
+                "{label}: a {kind} chart has no subtypes"
+            ))),
+        },
+        "scatter" => match subtype.as_deref() {
+            None => Ok(T::Scatter),
+            Some("straight") => Ok(T::ScatterStraight),
+            Some("straight_with_markers") => Ok(T::ScatterStraightWithMarkers),
+            Some("smooth") => Ok(T::ScatterSmooth),
+            Some("smooth_with_markers") => Ok(T::ScatterSmoothWithMarkers),
+            Some(other) => Err(value_err(format!(
+                "{label}: unknown subtype '{other}' for a scatter chart (valid: straight, \
+straight_with_markers, smooth, smooth_with_markers)"
+            ))),
+        },
+        other => Err(value_err(format!(
+            "{label}: unknown type '{other}' (valid: column, bar, line, area, pie, doughnut, \
+scatter)"
+        ))),
+    }
+}
+
+/// `values` — one header name or a non-empty list of them.
+fn columns(spec: &OptionDict, key: &str) -> PyResult<Vec<String>> {
+    let v = spec.require(key)?;
+    let names: Option<Vec<String>> = if v.cast::<PyString>().is_ok() {
+        v.extract().ok().map(|s| vec![s])
+    } else if let Ok(list) = v.cast::<PyList>() {
+        list.extract().ok()
+    } else {
+        None
+    };
+    match names {
+        Some(names) if !names.is_empty() => Ok(names),
+        _ => Err(value_err(format!(
+            "{}: '{key}' must be a column name or a non-empty list of column names",
+            spec.label
+        ))),
+    }
+}
+
+fn legend(spec: &OptionDict) -> PyResult<Option<Legend>> {
+    let Some(v) = spec.get("legend")? else {
+        return Ok(None);
+    };
+    if let Ok(b) = v.cast::<PyBool>() {
+        return Ok((!b.is_true()).then_some(Legend::Hidden));
+    }
+    let position: String = v.extract().map_err(|_| {
+        value_err(format!(
+            "{}: 'legend' must be a position or False",
+            spec.label
+        ))
+    })?;
+    Ok(Some(match position.to_ascii_lowercase().as_str() {
+        "right" => Legend::At(ChartLegendPosition::Right),
+        "left" => Legend::At(ChartLegendPosition::Left),
+        "top" => Legend::At(ChartLegendPosition::Top),
+        "bottom" => Legend::At(ChartLegendPosition::Bottom),
+        "top_right" => Legend::At(ChartLegendPosition::TopRight),
+        "none" => Legend::Hidden,
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown legend '{other}' (valid: right, left, top, bottom, top_right, \
+none)",
+                spec.label
+            )))
+        }
+    }))
+}
+
+fn size(spec: &OptionDict, key: &str) -> PyResult<Option<u32>> {
+    match spec.u32(key)? {
+        Some(0) => Err(value_err(format!(
+            "{}: '{key}' must be a positive number of pixels",
+            spec.label
+        ))),
+        other => Ok(other),
+    }
+}
+
+fn parse_secondary(spec: &OptionDict, primary: ChartType) -> PyResult<Option<Secondary>> {
+    let Some(v) = spec.get("secondary")? else {
+        return Ok(None);
+    };
+    let label = format!("{}['secondary']", spec.label);
+    if is_pie(primary) {
+        return Err(value_err(format!(
+            "{label}: a pie or doughnut chart has no axes to combine with"
+        )));
+    }
+    let sec = OptionDict::new(&v, label)?;
+    sec.check_keys(&["type", "subtype", "values", "y_axis_title"])?;
+    let kind = sec.str("type")?.unwrap_or_else(|| "line".to_string());
+    let chart_type = chart_type(&kind, sec.str("subtype")?.as_deref(), &sec.label)?;
+    // Excel draws these combinations wrongly or not at all.
+    if is_pie(chart_type) {
+        return Err(value_err(format!(
+            "{}: a pie or doughnut chart cannot be a secondary chart",
+            sec.label
+        )));
+    }
+    if is_bar(primary) != is_bar(chart_type) {
+        return Err(value_err(format!(
+            "{}: a bar chart can only be combined with another bar chart",
+            sec.label
+        )));
+    }
+    if is_scatter(primary) != is_scatter(chart_type) {
+        return Err(value_err(format!(
+            "{}: a scatter chart can only be combined with another scatter chart",
+            sec.label
+        )));
+    }
+    Ok(Some(Secondary {
+        chart_type,
+        values: columns(&sec, "values")?,
+        y_axis_title: sec.str("y_axis_title")?,
+    }))
+}
+
+fn parse_chart(spec: &OptionDict) -> PyResult<ChartSpec> {
+    spec.check_keys(&[
+        "type",
+        "subtype",
+        "categories",
+        "values",
+        "secondary",
+        "title",
+        "x_axis_title",
+        "y_axis_title",
+        "legend",
+        "anchor",
+        "width",
+        "height",
+        "sheet",
+    ])?;
+    let kind = spec
+        .str("type")?
+        .ok_or_else(|| value_err(format!("{}: missing required option 'type'", spec.label)))?;
+    let chart_type = chart_type(&kind, spec.str("subtype")?.as_deref(), &spec.label)?;
+    let values = columns(spec, "values")?;
+    let categories = spec.str("categories")?;
+
+    if is_pie(chart_type) {
+        if values.len() > 1 {
+            return Err(value_err(format!(
+                "{}: a {kind} chart shows one series; give a single 'values' column",
+                spec.label
+            )));
+        }
+        for key in ["x_axis_title", "y_axis_title"] {
+            if spec.get(key)?.is_some() {
+                return Err(value_err(format!(
+                    "{}: a {kind} chart has no axes, so '{key}' does not apply",
+                    spec.label
+                )));
+            }
+        }
+    }
+    if is_scatter(chart_type) && categories.is_none() {
+        return Err(value_err(format!(
+            "{}: a scatter chart needs 'categories', the column of x values",
+            spec.label
+        )));
+    }
+
+    let sheet = spec.str("sheet")?;
+    if let Some(name) = &sheet {
+        crate::utils::ensure_valid_sheet_name(name)?;
+    }
+    Ok(ChartSpec {
+        label: spec.label.clone(),
+        chart_type,
+        categories,
+        values,
+        secondary: parse_secondary(spec, chart_type)?,
+        title: spec.str("title")?,
+        x_axis_title: spec.str("x_axis_title")?,
+        y_axis_title: spec.str("y_axis_title")?,
+        legend: legend(spec)?,
+        anchor: spec.cell("anchor")?,
+        width: size(spec, "width")?,
+        height: size(spec, "height")?,
+        sheet,
+    })
+}
+
+/// Read `charts` — one chart dict or a list of them.
+pub fn resolve_charts(spec: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<ChartSpec>> {
+    let Some(spec) = spec else {
+        return Ok(Vec::new());
+    };
+    let items: Vec<Bound<'_, PyAny>> = if let Ok(list) = spec.cast::<PyList>() {
+        list.iter().collect()
+    } else if spec.cast::<PyDict>().is_ok() {
+        vec![spec.clone()]
+    } else {
+        return Err(value_err(
+            "charts must be a chart dict or a list of chart dicts".into(),
+        ));
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| parse_chart(&OptionDict::new(item, format!("charts[{i}]"))?))
+        .collect()
+}
+
+/// Turn each spec into a `Chart` over this sheet's data rows. Skipped when
+/// there is no data — every range would be empty. An unknown column warns and
+/// drops that series; a chart left without series is dropped the same way.
+pub fn build_charts(
+    specs: &[ChartSpec],
+    sheet_name: &str,
+    headers: &[String],
+    layout: &SheetLayout,
+    data_rows: u32,
+    py: Python,
+) -> PyResult<Vec<PlacedChart>> {
+    if specs.is_empty() || data_rows == 0 {
+        return Ok(Vec::new());
+    }
+    let header_row = layout.header_row();
+    let first = layout.first_data_row();
+    let last = first + data_rows - 1;
+    let column = |label: &str, name: &str| -> PyResult<Option<u16>> {
+        match headers.iter().position(|h| h == name) {
+            Some(col) => Ok(Some(col as u16)),
+            None => {
+                warn_py(py, &format!("{label}: unknown column '{name}', skipped"))?;
+                Ok(None)
+            }
+        }
+    };
+
+    let mut out = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let categories = match &spec.categories {
+            Some(name) => column(&spec.label, name)?,
+            None => None,
+        };
+        if is_scatter(spec.chart_type) && categories.is_none() {
+            continue;
+        }
+
+        let add_series =
+            |chart: &mut Chart, names: &[String], secondary: bool| -> PyResult<usize> {
+                let mut added = 0;
+                for name in names {
+                    let Some(col) = column(&spec.label, name)? else {
+                        continue;
+                    };
+                    let series = chart.add_series();
+                    series
+                        .set_name((sheet_name, header_row, col))
+                        .set_values((sheet_name, first, col, last, col))
+                        .set_secondary_axis(secondary);
+                    if let Some(cat) = categories {
+                        series.set_categories((sheet_name, first, cat, last, cat));
+                    }
+                    added += 1;
+                }
+                Ok(added)
+            };
+
+        let mut chart = Chart::new(spec.chart_type);
+        let mut added = add_series(&mut chart, &spec.values, false)?;
+        if let Some(sec) = &spec.secondary {
+            let mut combined = Chart::new(sec.chart_type);
+            if add_series(&mut combined, &sec.values, true)? > 0 {
+                chart.combine(&combined);
+                if let Some(t) = &sec.y_axis_title {
+                    chart.y2_axis().set_name(t);
+                }
+                added += 1;
+            }
+        }
+        if added == 0 || chart.validate().is_err() {
+            warn_py(
+                py,
+                &format!("{}: no series left to plot, chart skipped", spec.label),
+            )?;
+            continue;
+        }
+
+        if let Some(t) = &spec.title {
+            chart.title().set_name(t);
+        }
+        if let Some(t) = &spec.x_axis_title {
+            chart.x_axis().set_name(t);
+        }
+        if let Some(t) = &spec.y_axis_title {
+            chart.y_axis().set_name(t);
+        }
+        match &spec.legend {
+            Some(Legend::Hidden) => {
+                chart.legend().set_hidden();
+            }
+            Some(Legend::At(position)) => {
+                chart.legend().set_position(*position);
+            }
+            None => {}
+        }
+        if let Some(w) = spec.width {
+            chart.set_width(w);
+        }
+        if let Some(h) = spec.height {
+            chart.set_height(h);
+        }
+
+        let sheet = spec.sheet.clone().unwrap_or_else(|| sheet_name.to_string());
+        // Beside the data on its own sheet; top-left on any other.
+        let default_anchor = if sheet == sheet_name {
+            (header_row, headers.len() as u16 + 1)
+        } else {
+            (0, 0)
+        };
+        out.push(PlacedChart {
+            sheet,
+            anchor: spec.anchor,
+            default_anchor,
+            height: spec.height.unwrap_or(DEFAULT_HEIGHT),
+            chart,
+        });
+    }
+    Ok(out)
+}
+
+/// Insert the charts once every sheet has been written. A `sheet` that does
+/// not exist is added as an empty sheet at the end, so a chart can go on its
+/// own dashboard tab. Charts without an anchor are stacked top to bottom.
+pub fn place_charts(workbook: &mut Workbook, charts: Vec<PlacedChart>) -> PyResult<()> {
+    let mut next_row: HashMap<String, u32> = HashMap::new();
+    for placed in charts {
+        if workbook.worksheet_from_name(&placed.sheet).is_err() {
+            workbook
+                .add_worksheet()
+                .set_name(&placed.sheet)
+                .map_err(xlsx_err)?;
+        }
+        let worksheet = workbook
+            .worksheet_from_name(&placed.sheet)
+            .map_err(xlsx_err)?;
+        let (row, col) = match placed.anchor {
+            Some(anchor) => anchor,
+            None => {
+                let (first_row, col) = placed.default_anchor;
+                let row = next_row.entry(placed.sheet.clone()).or_insert(first_row);
+                let at = (*row, col);
+                *row += placed.height.div_ceil(ROW_PIXELS) + 1;
+                at
+            }
+        };
+        worksheet
+            .insert_chart(row, col, &placed.chart)
+            .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
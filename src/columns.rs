@@ -0,0 +1,175 @@
+//! Picking, ordering and renaming a sheet's columns (`columns`, `rename`).
+//!
+//! `columns` names the source columns to write, in order; `rename` maps a
+//! source name to the header written in its place. Every other column option
+//! names a column by that written header. A DataFrame or Arrow source is cut
+//! down before any of its values is converted; dict records pick their
+//! columns in [`crate::records`] instead, since a record may lack one.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, RecordBatchOptions, RecordBatchReader};
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use indexmap::IndexMap;
+use pyo3::prelude::*;
+use pyo3::Py;
+
+use crate::helpers::value_err;
+
+/// A sheet's `columns` and `rename`.
+#[derive(Clone, Default)]
+pub struct ColumnSelection {
+    columns: Option<Vec<String>>,
+    rename: IndexMap<String, String>,
+}
+
+impl ColumnSelection {
+    pub fn parse(
+        columns: Option<Vec<String>>,
+        rename: Option<IndexMap<String, String>>,
+    ) -> PyResult<Self> {
+        if let Some(columns) = &columns {
+            let mut seen = HashSet::new();
+            if let Some(twice) = columns.iter().find(|name| !seen.insert(name.as_str())) {
+                return Err(value_err(format!("columns: '{twice}' is listed twice")));
+            }
+        }
+        Ok(ColumnSelection {
+            columns,
+            rename: rename.unwrap_or_default(),
+        })
+    }
+
+    /// The source columns to write, if `columns` names them.
+    pub fn columns(&self) -> Option<&[String]> {
+        self.columns.as_deref()
+    }
+
+    /// When `columns` is given, put `names` at its front: a frame's index
+    /// columns are written whatever it lists.
+    pub fn lead_with(&mut self, names: &[String]) {
+        if let Some(columns) = &mut self.columns {
+            columns.retain(|name| !names.contains(name));
+            columns.splice(0..0, names.iter().cloned());
+        }
+    }
+
+    /// The headers written for the source columns `names`, warning about
+    /// `rename` keys that are not among `source`.
+    pub fn labels(&self, names: &[String], source: &[String], py: Python) -> PyResult<Vec<String>> {
+        if self.rename.is_empty() {
+            return Ok(names.to_vec());
+        }
+        let known: HashSet<&str> = source.iter().map(String::as_str).collect();
+        let warnings = py.import("warnings")?;
+        for old in self.rename.keys() {
+            if !known.contains(old.as_str()) {
+                let message = format!("rename: unknown column '{old}', skipped");
+                warnings.call_method1("warn", (message,))?;
+            }
+        }
+        Ok(names
+            .iter()
+            .map(|name| self.rename.get(name).unwrap_or(name).clone())
+            .collect())
+    }
+
+    /// The source columns to write, as positions in `source`, and their
+    /// headers. A name in `columns` that `source` lacks raises `ValueError`.
+    pub fn pick(&self, source: &[String], py: Python) -> PyResult<(Vec<usize>, Vec<String>)> {
+        let picked: Vec<usize> = match &self.columns {
+            None => (0..source.len()).collect(),
+            Some(columns) => columns
+                .iter()
+                .map(|name| {
+                    source.iter().position(|s| s == name).ok_or_else(|| {
+                        value_err(format!(
+                            "columns: unknown column '{name}'. The data has {}",
+                            source.join(", ")
+                        ))
+                    })
+                })
+                .collect::<PyResult<_>>()?,
+        };
+        let names: Vec<String> = picked.iter().map(|&i| source[i].clone()).collect();
+        let labels = self.labels(&names, source, py)?;
+        Ok((picked, labels))
+    }
+
+    /// `obj` with only the picked columns, when it is a pandas DataFrame that
+    /// has them all: pandas converts every column when it exports an Arrow
+    /// stream, picked or not. Anything else is projected by [`Self::project`].
+    pub fn narrow(&self, obj: &Py<PyAny>, py: Python) -> PyResult<Py<PyAny>> {
+        let bound = obj.bind(py);
+        let Some(columns) = &self.columns else {
+            return Ok(obj.clone_ref(py));
+        };
+        if bound.hasattr("get_column")? || !bound.hasattr("columns")? {
+            return Ok(obj.clone_ref(py));
+        }
+        let Ok(source) = bound.getattr("columns")?.extract::<Vec<String>>() else {
+            return Ok(obj.clone_ref(py));
+        };
+        if !columns.iter().all(|name| source.contains(name)) {
+            return Ok(obj.clone_ref(py));
+        }
+        Ok(bound.get_item(columns.clone())?.unbind())
+    }
+
+    /// `reader` with only the picked columns, in order, under their headers.
+    pub fn project(
+        &self,
+        reader: Box<dyn RecordBatchReader + Send>,
+        py: Python,
+    ) -> PyResult<Box<dyn RecordBatchReader + Send>> {
+        if self.columns.is_none() && self.rename.is_empty() {
+            return Ok(reader);
+        }
+        let schema = reader.schema();
+        let source: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+        let (indices, labels) = self.pick(&source, py)?;
+        let fields: Vec<_> = indices
+            .iter()
+            .zip(labels)
+            .map(|(&i, label)| Arc::new(schema.field(i).clone().with_name(label)))
+            .collect();
+        let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+        Ok(Box::new(Projected {
+            inner: reader,
+            indices,
+            schema,
+        }))
+    }
+}
+
+struct Projected {
+    inner: Box<dyn RecordBatchReader + Send>,
+    indices: Vec<usize>,
+    schema: SchemaRef,
+}
+
+impl Iterator for Projected {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = match self.inner.next()? {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e)),
+        };
+        let columns = self.indices.iter().map(|&i| batch.column(i).clone()).collect();
+        // With no column picked the row count has to be given.
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        Some(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        ))
+    }
+}
+
+impl RecordBatchReader for Projected {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
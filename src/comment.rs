@@ -0,0 +1,264 @@
+//! Cell notes (`comments`): fixed text on given cells, or a companion column
+//! whose values become notes on another column's cells.
+//!
+//! A companion column is read as data but never written — it is dropped from
+//! the headers before anything downstream (formats, links, totals, charts)
+//! sees them. Notes live apart from the cell table in `rust_xlsxwriter`, so
+//! adding one to a row constant-memory mode has already flushed is fine.
+
+use arrow_array::ArrayRef;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString, PyTuple};
+use rust_xlsxwriter::{Note, Worksheet};
+
+use crate::helpers::{value_err, warn_py};
+use crate::worksheet::xlsx_err;
+
+/// `comments` as given: fixed notes by cell, and `{target: source}` columns.
+#[derive(Default)]
+pub struct Comments {
+    cells: Vec<(u32, u16, String)>,
+    columns: Vec<(String, String)>,
+}
+
+/// Read `comments`. Keys are either `(row, col)` tuples with the note text,
+/// or a column name with the name of the column holding its notes.
+pub fn resolve_comments(spec: Option<&Bound<'_, PyAny>>) -> PyResult<Comments> {
+    let mut out = Comments::default();
+    let Some(spec) = spec else {
+        return Ok(out);
+    };
+    let dict = spec.cast::<PyDict>().map_err(|_| {
+        value_err("comments must be a dict of {(row, col): text} or {column: note_column}".into())
+    })?;
+    for (key, val) in dict.iter() {
+        if let Ok(cell) = key.cast::<PyTuple>() {
+            let (row, col): (u32, u16) = cell.extract().map_err(|_| {
+                value_err(format!(
+                    "comments: cell key {cell} must be a (row, col) pair of non-negative ints"
+                ))
+            })?;
+            if row > 1_048_575 || col > 16_383 {
+                return Err(value_err(format!(
+                    "comments: cell ({row}, {col}) is outside Excel's sheet limits"
+                )));
+            }
+            let text: String = val.extract().map_err(|_| {
+                value_err(format!("comments[({row}, {col})]: note must be a string"))
+            })?;
+            out.cells.push((row, col, text));
+        } else if let Ok(target) = key.cast::<PyString>() {
+            let target = target.to_str()?.to_string();
+            let source: String = val.extract().map_err(|_| {
+                value_err(format!(
+                    "comments['{target}']: must name the column holding the notes"
+                ))
+            })?;
+            if source == target {
+                return Err(value_err(format!(
+                    "comments['{target}']: a column cannot hold its own notes"
+                )));
+            }
+            out.columns.push((target, source));
+        } else {
+            return Err(value_err(format!(
+                "comments: key {key} must be a (row, col) tuple or a column name"
+            )));
+        }
+    }
+    if let Some((target, _)) = out
+        .columns
+        .iter()
+        .find(|(t, _)| out.columns.iter().any(|(_, s)| s == t))
+    {
+        return Err(value_err(format!(
+            "comments['{target}']: '{target}' is itself a note column, so it is not \
+written and cannot carry notes"
+        )));
+    }
+    Ok(out)
+}
+
+fn insert(worksheet: &mut Worksheet, row: u32, col: u16, text: &str) -> PyResult<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    // Excel's default "Author:" heading would read as part of the note.
+    let note = Note::new(text).add_author_prefix(false);
+    worksheet.insert_note(row, col, &note).map_err(xlsx_err)?;
+    Ok(())
+}
+
+impl Comments {
+    /// Insert the fixed `(row, col)` notes.
+    pub fn apply_cells(&self, worksheet: &mut Worksheet) -> PyResult<()> {
+        for (row, col, text) in &self.cells {
+            insert(worksheet, *row, *col, text)?;
+        }
+        Ok(())
+    }
+
+    /// Forget the fixed `(row, col)` notes, keeping the note columns.
+    pub fn drop_cells(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Resolve the column mapping against the source's own headers. Unknown
+    /// names warn and skip that pair, leaving any known column written as
+    /// ordinary data. `outline` names the `outline_column`, which is read but
+    /// not written either.
+    pub fn plan(
+        &self,
+        headers: &[String],
+        outline: Option<&str>,
+        py: Python,
+    ) -> PyResult<CommentPlan> {
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        for (target, source) in &self.columns {
+            let t = headers.iter().position(|h| h == target);
+            let s = headers.iter().position(|h| h == source);
+            match (t, s) {
+                (Some(t), Some(s)) => pairs.push((s, t)),
+                _ => {
+                    let missing = if t.is_none() { target } else { source };
+                    warn_py(
+                        py,
+                        &format!("comments: unknown column '{missing}', skipped"),
+                    )?;
+                }
+            }
+        }
+        let outline = match outline {
+            None => None,
+            Some(name) => {
+                let col = headers.iter().position(|h| h == name);
+                if col.is_none() {
+                    warn_py(py, &format!("outline_column: unknown column '{name}', skipped"))?;
+                }
+                col
+            }
+        };
+        if pairs.is_empty() && outline.is_none() {
+            return Ok(CommentPlan::default());
+        }
+        let mut out_cols = Vec::with_capacity(headers.len());
+        let mut next: u16 = 0;
+        for col in 0..headers.len() {
+            if pairs.iter().any(|&(s, _)| s == col) || outline == Some(col) {
+                out_cols.push(None);
+            } else {
+                out_cols.push(Some(next));
+                next += 1;
+            }
+        }
+        let targets = pairs
+            .into_iter()
+            .map(|(s, t)| {
+                // A note target is never a note source, but it may be the
+                // outline column.
+                let target = out_cols[t].ok_or_else(|| {
+                    value_err(format!(
+                        "comments: column '{}' is the outline_column, which is not written",
+                        headers[t]
+                    ))
+                })?;
+                Ok((s, target))
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(CommentPlan {
+            out_cols,
+            targets,
+            outline,
+        })
+    }
+}
+
+/// Where each source column lands once note columns, and the
+/// `outline_column`, are dropped.
+#[derive(Default)]
+pub struct CommentPlan {
+    /// Source column → output column, `None` for a dropped column. Empty when
+    /// none is dropped: every column is then written where it is.
+    out_cols: Vec<Option<u16>>,
+    /// Note column (source index) → the output column its notes go on.
+    targets: Vec<(usize, u16)>,
+    /// Source index of the `outline_column`.
+    outline: Option<usize>,
+}
+
+impl CommentPlan {
+    /// Output column for source column `col`, or `None` if it is dropped.
+    pub fn output_col(&self, col: usize) -> Option<u16> {
+        match self.out_cols.get(col) {
+            Some(out) => *out,
+            None => Some(col as u16),
+        }
+    }
+
+    /// Indices of the source columns written as data, in order.
+    pub fn kept(&self, n_cols: usize) -> Vec<usize> {
+        (0..n_cols)
+            .filter(|&c| self.output_col(c).is_some())
+            .collect()
+    }
+
+    pub fn drops_columns(&self) -> bool {
+        !self.out_cols.is_empty()
+    }
+
+    pub fn outline_col(&self) -> Option<usize> {
+        self.outline
+    }
+
+    /// `(source column, output column)` for every note column.
+    pub fn note_columns(&self) -> &[(usize, u16)] {
+        &self.targets
+    }
+
+    /// The headers actually written.
+    pub fn output_headers(&self, headers: &[String]) -> Vec<String> {
+        self.kept(headers.len())
+            .into_iter()
+            .map(|c| headers[c].clone())
+            .collect()
+    }
+
+    /// Note the target cell(s) of note column `col` with a Python value.
+    /// `None` and empty strings add no note; anything else is shown as text.
+    pub fn note_py(
+        &self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: usize,
+        value: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        if value.is_none() {
+            return Ok(());
+        }
+        let text = match value.cast::<PyString>() {
+            Ok(s) => s.to_str()?.to_string(),
+            Err(_) => value.str()?.to_str()?.to_string(),
+        };
+        for &(_, target) in self.targets.iter().filter(|(s, _)| *s == col) {
+            insert(worksheet, row, target, &text)?;
+        }
+        Ok(())
+    }
+
+    /// Note every row of an Arrow note column, starting at sheet row `start`.
+    pub fn note_arrow(
+        &self,
+        worksheet: &mut Worksheet,
+        start: u32,
+        target: u16,
+        column: &ArrayRef,
+    ) -> PyResult<()> {
+        let texts = crate::arrow_writer::arrow_cell_texts(column);
+        for (idx, text) in texts.iter().enumerate() {
+            if let Some(text) = text {
+                insert(worksheet, start + idx as u32, target, text)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+//! Conditional formatting rules, keyed by column header.
+//!
+//! Rules live in the worksheet footer (`<conditionalFormatting>`), not in the
+//! row data, so — like the autofilter — they are added after the last row has
+//! been flushed, with the range taken from the final row count. Parsing happens
+//! up front, before any cell is written, so a malformed rule raises instead of
+//! leaving a half-written file behind.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDate, PyDateTime, PyDict, PyList, PyString};
+use rust_xlsxwriter::{
+    ConditionalFormat2ColorScale, ConditionalFormat3ColorScale, ConditionalFormatCell,
+    ConditionalFormatCellRule, ConditionalFormatDataBar, ConditionalFormatFormula,
+    ConditionalFormatIconSet, ConditionalFormatIconType, ConditionalFormatTop,
+    ConditionalFormatTopRule, ConditionalFormatType, ConditionalFormatValue, Formula, Worksheet,
+};
+
+use crate::format::parse_color;
+use crate::helpers::{formula_problem, value_err, warn_py, OptionDict, SheetLayout};
+use crate::worksheet::xlsx_err;
+
+/// One parsed rule. Everything but the formula rule is a finished
+/// `rust_xlsxwriter` object waiting for its range; the formula rule keeps its
+/// template because `{col}`, `{first}` and `{last}` depend on where the
+/// column lands and how many rows the sheet takes.
+enum Rule {
+    Cell(ConditionalFormatCell),
+    TwoColorScale(ConditionalFormat2ColorScale),
+    ThreeColorScale(ConditionalFormat3ColorScale),
+    DataBar(ConditionalFormatDataBar),
+    IconSet(ConditionalFormatIconSet),
+    Top(ConditionalFormatTop),
+    Formula(String, rust_xlsxwriter::Format),
+}
+
+/// A rule plus the header name of the column it covers.
+pub struct ConditionalRule {
+    column: String,
+    rule: Rule,
+}
+
+/// Read a comparison value. A string starting with `=` is a formula
+/// (`"=$H$1"`), any other string is compared as text; numbers, bools and dates
+/// are passed through as Excel values.
+fn rule_value(spec: &OptionDict, key: &str) -> PyResult<ConditionalFormatValue> {
+    let v = spec.require(key)?;
+    if let Ok(s) = v.cast::<PyString>() {
+        let s = s.to_str()?;
+        return Ok(if s.starts_with('=') {
+            Formula::new(s).into()
+        } else {
+            s.into()
+        });
+    }
+    if let Ok(b) = v.cast::<PyBool>() {
+        return Ok(b.is_true().into());
+    }
+    if let Ok(dt) = v.cast::<PyDateTime>() {
+        return Ok(crate::helpers::py_datetime_to_excel(dt)?.into());
+    }
+    if let Ok(d) = v.cast::<PyDate>() {
+        return Ok(crate::helpers::py_date_to_excel(d)?.into());
+    }
+    let n: f64 = v.extract().map_err(|_| {
+        value_err(format!(
+            "{}: '{key}' must be a number, string, date or formula",
+            spec.label
+        ))
+    })?;
+    Ok(n.into())
+}
+
+fn cell_rule(spec: &OptionDict) -> PyResult<ConditionalFormatCellRule<ConditionalFormatValue>> {
+    let criteria = spec.str("criteria")?.ok_or_else(|| {
+        value_err(format!(
+            "{}: missing required option 'criteria'",
+            spec.label
+        ))
+    })?;
+    use ConditionalFormatCellRule as R;
+    Ok(match criteria.to_ascii_lowercase().as_str() {
+        "==" | "=" | "equal_to" => R::EqualTo(rule_value(spec, "value")?),
+        "!=" | "<>" | "not_equal_to" => R::NotEqualTo(rule_value(spec, "value")?),
+        ">" | "greater_than" => R::GreaterThan(rule_value(spec, "value")?),
+        ">=" | "greater_than_or_equal_to" => R::GreaterThanOrEqualTo(rule_value(spec, "value")?),
+        "<" | "less_than" => R::LessThan(rule_value(spec, "value")?),
+        "<=" | "less_than_or_equal_to" => R::LessThanOrEqualTo(rule_value(spec, "value")?),
+        "between" => R::Between(rule_value(spec, "minimum")?, rule_value(spec, "maximum")?),
+        "not_between" => R::NotBetween(rule_value(spec, "minimum")?, rule_value(spec, "maximum")?),
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown criteria '{other}' (valid: ==, !=, >, >=, <, <=, between, \
+not_between)",
+                spec.label
+            )))
+        }
+    })
+}
+
+/// Read one end of a color scale or data bar: `{end}_type` and `{end}_value`.
+/// Returns `None` when neither is given, leaving the crate's default
+/// (lowest/highest value in the range).
+fn scale_point(
+    spec: &OptionDict,
+    end: &str,
+) -> PyResult<Option<(ConditionalFormatType, ConditionalFormatValue)>> {
+    let type_key = format!("{end}_type");
+    let value_key = format!("{end}_value");
+    let Some(kind) = spec.str(&type_key)? else {
+        if spec.get(&value_key)?.is_some() {
+            return Err(value_err(format!(
+                "{}: '{value_key}' needs a '{type_key}' (num, percent, percentile or formula)",
+                spec.label
+            )));
+        }
+        return Ok(None);
+    };
+    let kind = match kind.to_ascii_lowercase().as_str() {
+        "num" | "number" => ConditionalFormatType::Number,
+        "percent" => ConditionalFormatType::Percent,
+        "percentile" => ConditionalFormatType::Percentile,
+        "formula" => ConditionalFormatType::Formula,
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown {type_key} '{other}' (valid: num, percent, percentile, formula)",
+                spec.label
+            )))
+        }
+    };
+    // The crate drops an out-of-range percent with a message on stderr, which
+    // would leave the scale on its default without a trace.
+    if matches!(
+        kind,
+        ConditionalFormatType::Percent | ConditionalFormatType::Percentile
+    ) {
+        let pct = spec.f64(&value_key)?.unwrap_or(-1.0);
+        if !(0.0..=100.0).contains(&pct) {
+            return Err(value_err(format!(
+                "{}: '{value_key}' must be a number in 0..100 for a {} point",
+                spec.label,
+                if kind == ConditionalFormatType::Percent {
+                    "percent"
+                } else {
+                    "percentile"
+                }
+            )));
+        }
+    }
+    Ok(Some((kind, rule_value(spec, &value_key)?)))
+}
+
+fn icon_type(name: &str, label: &str) -> PyResult<ConditionalFormatIconType> {
+    use ConditionalFormatIconType as I;
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "3_arrows" => I::ThreeArrows,
+        "3_arrows_gray" => I::ThreeArrowsGray,
+        "3_flags" => I::ThreeFlags,
+        "3_traffic_lights" => I::ThreeTrafficLights,
+        "3_traffic_lights_rimmed" => I::ThreeTrafficLightsWithRim,
+        "3_signs" => I::ThreeSigns,
+        "3_symbols_circled" => I::ThreeSymbolsCircled,
+        "3_symbols" => I::ThreeSymbols,
+        "3_stars" => I::ThreeStars,
+        "3_triangles" => I::ThreeTriangles,
+        "4_arrows" => I::FourArrows,
+        "4_arrows_gray" => I::FourArrowsGray,
+        "4_red_to_black" => I::FourRedToBlack,
+        "4_ratings" => I::FourHistograms,
+        "4_traffic_lights" => I::FourTrafficLights,
+        "5_arrows" => I::FiveArrows,
+        "5_arrows_gray" => I::FiveArrowsGray,
+        "5_ratings" => I::FiveHistograms,
+        "5_quarters" => I::FiveQuadrants,
+        "5_boxes" => I::FiveBoxes,
+        other => {
+            return Err(value_err(format!(
+                "{label}: unknown icon_type '{other}' (valid: 3_arrows, 3_arrows_gray, \
+3_flags, 3_traffic_lights, 3_traffic_lights_rimmed, 3_signs, 3_symbols_circled, 3_symbols, \
+3_stars, 3_triangles, 4_arrows, 4_arrows_gray, 4_red_to_black, 4_ratings, 4_traffic_lights, \
+5_arrows, 5_arrows_gray, 5_ratings, 5_quarters, 5_boxes)"
+            )))
+        }
+    })
+}
+
+fn color(spec: &OptionDict, key: &str) -> PyResult<Option<rust_xlsxwriter::Color>> {
+    spec.str(key)?
+        .map(|c| parse_color(&c).map_err(|e| value_err(format!("{}: {key}: {e}", spec.label))))
+        .transpose()
+}
+
+/// A rule that only highlights cells is useless without a format, so its
+/// absence raises rather than writing an invisible rule.
+fn required_format(spec: &OptionDict) -> PyResult<rust_xlsxwriter::Format> {
+    spec.format("format")?.ok_or_else(|| {
+        value_err(format!(
+            "{}: missing required option 'format' (a Format object)",
+            spec.label
+        ))
+    })
+}
+
+fn parse_rule(spec: &OptionDict) -> PyResult<Rule> {
+    let kind = spec
+        .str("type")?
+        .ok_or_else(|| value_err(format!("{}: missing required option 'type'", spec.label)))?;
+    Ok(match kind.to_ascii_lowercase().as_str() {
+        "cell" => {
+            spec.check_keys(&["type", "criteria", "value", "minimum", "maximum", "format"])?;
+            Rule::Cell(
+                ConditionalFormatCell::new()
+                    .set_rule(cell_rule(spec)?)
+                    .set_format(required_format(spec)?),
+            )
+        }
+        "2_color_scale" => {
+            spec.check_keys(&[
+                "type",
+                "min_type",
+                "min_value",
+                "min_color",
+                "max_type",
+                "max_value",
+                "max_color",
+            ])?;
+            let mut cf = ConditionalFormat2ColorScale::new();
+            if let Some((t, v)) = scale_point(spec, "min")? {
+                cf = cf.set_minimum(t, v);
+            }
+            if let Some((t, v)) = scale_point(spec, "max")? {
+                cf = cf.set_maximum(t, v);
+            }
+            if let Some(c) = color(spec, "min_color")? {
+                cf = cf.set_minimum_color(c);
+            }
+            if let Some(c) = color(spec, "max_color")? {
+                cf = cf.set_maximum_color(c);
+            }
+            Rule::TwoColorScale(cf)
+        }
+        "3_color_scale" => {
+            spec.check_keys(&[
+                "type",
+                "min_type",
+                "min_value",
+                "min_color",
+                "mid_type",
+                "mid_value",
+                "mid_color",
+                "max_type",
+                "max_value",
+                "max_color",
+            ])?;
+            let mut cf = ConditionalFormat3ColorScale::new();
+            if let Some((t, v)) = scale_point(spec, "min")? {
+                cf = cf.set_minimum(t, v);
+            }
+            if let Some((t, v)) = scale_point(spec, "mid")? {
+                cf = cf.set_midpoint(t, v);
+            }
+            if let Some((t, v)) = scale_point(spec, "max")? {
+                cf = cf.set_maximum(t, v);
+            }
+            if let Some(c) = color(spec, "min_color")? {
+                cf = cf.set_minimum_color(c);
+            }
+            if let Some(c) = color(spec, "mid_color")? {
+                cf = cf.set_midpoint_color(c);
+            }
+            if let Some(c) = color(spec, "max_color")? {
+                cf = cf.set_maximum_color(c);
+            }
+            Rule::ThreeColorScale(cf)
+        }
+        "data_bar" => {
+            spec.check_keys(&[
+                "type",
+                "min_type",
+                "min_value",
+                "max_type",
+                "max_value",
+                "bar_color",
+                "bar_border_color",
+                "bar_negative_color",
+                "bar_solid",
+                "bar_only",
+            ])?;
+            let mut cf = ConditionalFormatDataBar::new();
+            if let Some((t, v)) = scale_point(spec, "min")? {
+                cf = cf.set_minimum(t, v);
+            }
+            if let Some((t, v)) = scale_point(spec, "max")? {
+                cf = cf.set_maximum(t, v);
+            }
+            if let Some(c) = color(spec, "bar_color")? {
+                cf = cf.set_fill_color(c);
+            }
+            if let Some(c) = color(spec, "bar_border_color")? {
+                cf = cf.set_border_color(c);
+            }
+            if let Some(c) = color(spec, "bar_negative_color")? {
+                cf = cf.set_negative_fill_color(c);
+            }
+            if let Some(solid) = spec.bool("bar_solid")? {
+                cf = cf.set_solid_fill(solid);
+            }
+            if let Some(only) = spec.bool("bar_only")? {
+                cf = cf.set_bar_only(only);
+            }
+            Rule::DataBar(cf)
+        }
+        "icon_set" => {
+            spec.check_keys(&["type", "icon_type", "reverse_icons", "icons_only"])?;
+            let name = spec.str("icon_type")?.ok_or_else(|| {
+                value_err(format!(
+                    "{}: missing required option 'icon_type'",
+                    spec.label
+                ))
+            })?;
+            let cf = ConditionalFormatIconSet::new()
+                .set_icon_type(icon_type(&name, &spec.label)?)
+                .reverse_icons(spec.bool("reverse_icons")?.unwrap_or(false))
+                .show_icons_only(spec.bool("icons_only")?.unwrap_or(false));
+            Rule::IconSet(cf)
+        }
+        top @ ("top" | "bottom") => {
+            spec.check_keys(&["type", "value", "percent", "format"])?;
+            let n = spec.u32("value")?.unwrap_or(10);
+            if !(1..=1000).contains(&n) {
+                return Err(value_err(format!(
+                    "{}: 'value' must be in 1..1000, got {n}",
+                    spec.label
+                )));
+            }
+            let n = n as u16;
+            let percent = spec.bool("percent")?.unwrap_or(false);
+            let rule = match (top, percent) {
+                ("top", false) => ConditionalFormatTopRule::Top(n),
+                ("top", true) => ConditionalFormatTopRule::TopPercent(n),
+                (_, false) => ConditionalFormatTopRule::Bottom(n),
+                (_, true) => ConditionalFormatTopRule::BottomPercent(n),
+            };
+            Rule::Top(
+                ConditionalFormatTop::new()
+                    .set_rule(rule)
+                    .set_format(required_format(spec)?),
+            )
+        }
+        "formula" => {
+            spec.check_keys(&["type", "criteria", "format"])?;
+            let template = spec.str("criteria")?.ok_or_else(|| {
+                value_err(format!(
+                    "{}: missing required option 'criteria' (the formula)",
+                    spec.label
+                ))
+            })?;
+            if let Some(problem) = formula_problem(&template) {
+                return Err(value_err(format!(
+                    "{}: formula looks malformed: {problem}. Formula: {template}",
+                    spec.label
+                )));
+            }
+            Rule::Formula(template, required_format(spec)?)
+        }
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown type '{other}' (valid: cell, 2_color_scale, 3_color_scale, \
+data_bar, icon_set, top, bottom, formula)",
+                spec.label
+            )))
+        }
+    })
+}
+
+/// Read `conditional_formats` — `{column name: rule}` or
+/// `{column name: [rule, ...]}`, each rule a dict with a `type` key. Rules for
+/// one column are applied in the order given, which is also Excel's priority
+/// order.
+pub fn resolve_conditional_formats(
+    spec: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Vec<ConditionalRule>> {
+    let Some(spec) = spec else {
+        return Ok(Vec::new());
+    };
+    let dict = spec.cast::<PyDict>().map_err(|_| {
+        value_err("conditional_formats must be a dict of {column name: rule or [rules]}".into())
+    })?;
+    let mut out = Vec::new();
+    for (key, val) in dict.iter() {
+        let column: String = key
+            .extract()
+            .map_err(|_| value_err("conditional_formats keys must be column names".into()))?;
+        let rules: Vec<Bound<'_, PyAny>> = match val.cast::<PyList>() {
+            Ok(list) => list.iter().collect(),
+            Err(_) => vec![val],
+        };
+        for (i, rule) in rules.iter().enumerate() {
+            let label = if rules.len() == 1 {
+                format!("conditional_formats['{column}']")
+            } else {
+                format!("conditional_formats['{column}'][{i}]")
+            };
+            let rule = parse_rule(&OptionDict::new(rule, label)?)?;
+            out.push(ConditionalRule {
+                column: column.clone(),
+                rule,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Add every rule over its column's data rows. Like the totals row this is
+/// skipped when there is no data, since the range would be empty. Unknown
+/// column names warn and are skipped, matching `column_formats`.
+pub fn apply_conditional_formats(
+    worksheet: &mut Worksheet,
+    rules: &[ConditionalRule],
+    headers: &[String],
+    layout: &SheetLayout,
+    data_rows: u32,
+    py: Python,
+) -> PyResult<()> {
+    if rules.is_empty() || data_rows == 0 {
+        return Ok(());
+    }
+    let first = layout.first_data_row();
+    let last = first + data_rows - 1;
+
+    for ConditionalRule { column, rule } in rules {
+        let Some(col) = headers.iter().position(|h| h == column) else {
+            warn_py(
+                py,
+                &format!("conditional_formats: unknown column '{column}', skipped"),
+            )?;
+            continue;
+        };
+        let col = col as u16;
+        match rule {
+            Rule::Cell(cf) => worksheet.add_conditional_format(first, col, last, col, cf),
+            Rule::TwoColorScale(cf) => worksheet.add_conditional_format(first, col, last, col, cf),
+            Rule::ThreeColorScale(cf) => {
+                worksheet.add_conditional_format(first, col, last, col, cf)
+            }
+            Rule::DataBar(cf) => worksheet.add_conditional_format(first, col, last, col, cf),
+            Rule::IconSet(cf) => worksheet.add_conditional_format(first, col, last, col, cf),
+            Rule::Top(cf) => worksheet.add_conditional_format(first, col, last, col, cf),
+            Rule::Formula(template, fmt) => {
+                // Excel evaluates the formula relative to the top-left cell of
+                // the range, so `{col}{first}` is "this row's cell".
+                let letter = rust_xlsxwriter::utility::column_number_to_name(col);
+                let formula = template
+                    .replace("{col}", &letter)
+                    .replace("{first}", &(first + 1).to_string())
+                    .replace("{last}", &(last + 1).to_string());
+                let cf = ConditionalFormatFormula::new()
+                    .set_rule(formula.as_str())
+                    .set_format(fmt);
+                worksheet.add_conditional_format(first, col, last, col, &cf)
+            }
+        }
+        .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
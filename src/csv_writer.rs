@@ -2,12 +2,22 @@
 
 use std::io::Write;
 
+use indexmap::IndexMap;
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyInt, PyTimeAccess};
+use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyDelta, PyInt, PyTime, PyTimeAccess};
 use pyo3::Py;
 
 use crate::cell::{classify_and_write, try_cached, CellWriter};
-use crate::helpers::{write_bytes_to_target, write_csv_escaped_guarded, ColType};
+use crate::columns::ColumnSelection;
+use crate::helpers::{
+    emit_duration_csv, emit_fraction_csv, emit_time_csv, py_time_seconds, py_timedelta_micros,
+    write_bytes_to_target, write_csv_escaped_guarded, ColType, FractionDigits,
+};
+use crate::missing::{emit_special_csv, Reps, Special};
+use crate::nested::{py_nested_text, NestedColumns};
+use crate::records::{as_record, KeyMap, RecordKeys};
+use crate::sentinel::Sentinel;
+use crate::timezone::{py_utc_offset, Timezone, TzMix};
 
 /// Turn a failed `try_iter` into a clear message. Skipping the loop instead
 /// would write an empty file and report success.
@@ -23,15 +33,55 @@ fn not_iterable(_: PyErr) -> PyErr {
 /// `= + - @` are prefixed with a single quote so spreadsheet apps treat them
 /// as text rather than executable formulas (CSV-injection mitigation). It is
 /// off by default to keep output byte-identical for existing callers.
+///
+/// `nested_columns` renders list, struct and dict values as in a worksheet;
+/// under `"explode"` a struct or dict becomes `parent.field` columns.
+///
+/// `timezone` converts aware datetimes as in a worksheet. With `utc_offsets`
+/// an aware value is followed by its ISO 8601 offset, `+HH:MM`.
+///
+/// `datetime_precision` fixes the digits after a datetime's seconds, 0 to 6;
+/// by default they are as many as the value needs.
+///
+/// `na_rep`, `nan_rep` and `inf_rep` write missing values, NaN and ±infinity
+/// as in a worksheet; an Excel error value is written as its spelling.
+///
+/// `columns` and `rename` pick, order and relabel the columns as in a
+/// worksheet, and `missing`, `extra_keys` and `scan_rows` line dict records
+/// up with the header row.
 #[pyfunction]
-#[pyo3(signature = (records, file_name, delimiter = None, sanitize_formulas = false))]
+#[pyo3(signature = (records, file_name, delimiter = None, sanitize_formulas = false, nested_columns = None, timezone = None, utc_offsets = false, datetime_precision = None, na_rep = None, nan_rep = None, inf_rep = None, columns = None, missing = None, extra_keys = None, scan_rows = None, rename = None))]
+#[allow(clippy::too_many_arguments)]
 pub fn write_csv(
     py: Python,
     records: Py<PyAny>,
     file_name: Py<PyAny>,
     delimiter: Option<String>,
     sanitize_formulas: bool,
+    nested_columns: Option<String>,
+    timezone: Option<String>,
+    utc_offsets: bool,
+    datetime_precision: Option<u8>,
+    na_rep: Option<String>,
+    nan_rep: Option<String>,
+    inf_rep: Option<String>,
+    columns: Option<Vec<String>>,
+    missing: Option<String>,
+    extra_keys: Option<String>,
+    scan_rows: Option<usize>,
+    rename: Option<IndexMap<String, String>>,
 ) -> PyResult<()> {
+    let opts = CsvOptions {
+        sanitize: sanitize_formulas,
+        nested: NestedColumns::parse(nested_columns.as_deref())?,
+        timezone: Timezone::parse(py, timezone.as_deref())?,
+        offsets: utc_offsets,
+        digits: FractionDigits::parse(datetime_precision)?,
+        reps: Reps::parse(na_rep.as_deref(), nan_rep.as_deref(), inf_rep.as_deref()),
+    };
+    let selection = ColumnSelection::parse(columns, rename)?;
+    let record_keys = RecordKeys::parse(missing.as_deref(), extra_keys.as_deref(), scan_rows)?;
+    let nested = opts.nested;
     let delim = delimiter.unwrap_or_else(|| ",".to_string());
     let delim_bytes = delim.as_bytes();
     if delim_bytes.len() != 1 {
@@ -47,23 +97,38 @@ pub fn write_csv(
 
     // Fast path: Arrow zero-copy if the object exposes `__arrow_c_stream__`
     // (Pandas ≥2.0, Polars). Falls back to the per-object paths below on
-    // failure (e.g. empty Null-typed columns).
+    // failure (e.g. empty Null-typed columns). A nested column refused by
+    // `nested_columns="error"` is not such a failure, so that error stands,
+    // and neither is a value refused by the options, such as `nan_rep="raise"`,
+    // or a `columns` name the data lacks.
     if bound.hasattr("__arrow_c_stream__")? {
-        if write_csv_via_arrow(&records, py, &mut output, delim_byte, sanitize_formulas).is_ok() {
-            return write_bytes_to_target(py, &output, file_name);
+        let stream = selection
+            .narrow(&records, py)
+            .and_then(|obj| crate::arrow_ffi::stream_to_reader(&obj, py));
+        if let Ok(reader) = stream {
+            let reader = crate::nested::explode_reader(reader, nested);
+            let reader = selection.project(reader, py)?;
+            crate::arrow_writer::check_nested(&reader.schema(), nested)?;
+            match write_csv_via_arrow(py, reader, &mut output, delim_byte, &opts) {
+                Ok(()) => return write_bytes_to_target(py, &output, file_name),
+                Err(e) if e.is_instance_of::<pyo3::exceptions::PyValueError>(py) => return Err(e),
+                Err(_) => output.clear(),
+            }
         }
-        output.clear();
     }
 
+    let mut tz_mix = TzMix::default();
     if bound.hasattr("columns")? {
-        let columns: Vec<String> = bound.getattr("columns")?.extract()?;
+        let source: Vec<String> = bound.getattr("columns")?.extract()?;
+        let (picked, columns) = selection.pick(&source, py)?;
         write_csv_row_strings(&mut output, &columns, delim_byte, sanitize_formulas);
 
         if bound.hasattr("get_column")? {
-            // Polars
+            // Polars. Only the picked columns are fetched and converted.
             let mut col_lists: Vec<Py<PyAny>> = Vec::with_capacity(columns.len());
-            for header in &columns {
-                let col_series = records.call_method1(py, "get_column", (header.as_str(),))?;
+            for &src in &picked {
+                let header = source[src].as_str();
+                let col_series = records.call_method1(py, "get_column", (header,))?;
                 col_lists.push(col_series.call_method0(py, "to_list")?);
             }
             let nrows: usize = records.call_method0(py, "__len__")?.extract(py)?;
@@ -78,66 +143,62 @@ pub fn write_csv(
                         output.push(delim_byte);
                     }
                     let item = col_list.get_item(row)?;
-                    let mut sink = CsvCell::new(&mut output, sanitize_formulas);
+                    let mut sink = CsvCell::new(&mut output, &opts, row + 1, i, &mut tz_mix);
                     classify_and_write(&item, &mut sink)?;
                 }
                 output.push(b'\n');
             }
         } else {
-            // Pandas — iterate rows via `.values`
-            let values = records.getattr(py, "values")?;
+            // Pandas — iterate rows via `.values`, of the picked columns only.
+            let frame = match selection.columns() {
+                Some(_) => bound.get_item(picked.iter().map(|&i| &source[i]).collect::<Vec<_>>())?,
+                None => bound.clone(),
+            };
+            let values = frame.getattr("values")?;
             // Propagate rather than skip: silently emitting an empty file is
             // worse than saying the input could not be iterated.
-            for row_res in values.bind(py).try_iter()? {
+            for (row_idx, row_res) in values.try_iter()?.enumerate() {
                 let row = row_res?;
-                let mut first = true;
-                for item_res in row.try_iter()? {
+                for (i, item_res) in row.try_iter()?.enumerate() {
                     let item = item_res?;
-                    if !first {
+                    if i > 0 {
                         output.push(delim_byte);
                     }
-                    first = false;
-                    let mut sink = CsvCell::new(&mut output, sanitize_formulas);
+                    let mut sink = CsvCell::new(&mut output, &opts, row_idx + 1, i, &mut tz_mix);
                     classify_and_write(&item, &mut sink)?;
                 }
                 output.push(b'\n');
             }
         }
+        tz_mix.check(&columns, &opts.timezone, py)?;
     } else {
         // Records path (list of dicts / generator). First-row type cache
         // mirrors the Excel Records path — skips the full type cascade
         // after the first row when the column's Python type is stable.
-        let mut headers: Vec<String> = Vec::new();
         let mut headers_written = false;
-        let mut col_types: Vec<ColType> = Vec::new();
-
-        let rows: pyo3::Bound<'_, pyo3::types::PyIterator> =
-            bound.try_iter().map_err(not_iterable)?;
-        for row_res in rows {
-            let row_obj = row_res?;
-            let row_dict = row_obj.cast::<pyo3::types::PyDict>().map_err(|_| {
-                PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                    "Items in records must be dictionaries",
-                )
-            })?;
+        let rows = bound.try_iter().map_err(not_iterable)?;
+        let (source, rows) = record_keys.headers(selection.columns(), bound, rows, nested)?;
+        let keys = KeyMap::new(&record_keys, &source);
+        let headers = selection.labels(&source, &source, py)?;
+        let mut col_types: Vec<ColType> = vec![ColType::Unknown; headers.len()];
+        let mut slots = Vec::new();
+        let none = py.None().into_bound(py);
 
+        for (row_idx, row_res) in rows.enumerate() {
+            let row_dict = as_record(&row_res?, nested)?;
             if !headers_written {
-                for key in row_dict.keys().iter() {
-                    headers.push(key.extract::<String>()?);
-                }
                 write_csv_row_strings(&mut output, &headers, delim_byte, sanitize_formulas);
-                col_types.resize(headers.len(), ColType::Unknown);
                 headers_written = true;
             }
 
-            // Iterate the dict directly (insertion order == header order)
-            // to avoid allocating a fresh `values()` list per row.
-            for (col, (_key, value)) in row_dict.iter().enumerate() {
+            keys.align(&row_dict, &mut slots, || format!("data row {}", row_idx + 1))?;
+            for (col, value) in slots.drain(..).enumerate() {
+                let value = value.unwrap_or_else(|| none.clone());
                 if col > 0 {
                     output.push(delim_byte);
                 }
                 let cached = col_types.get(col).copied().unwrap_or(ColType::Unknown);
-                let mut sink = CsvCell::new(&mut output, sanitize_formulas);
+                let mut sink = CsvCell::new(&mut output, &opts, row_idx + 1, col, &mut tz_mix);
                 if !try_cached(&value, cached, &mut sink)? {
                     let detected = classify_and_write(&value, &mut sink)?;
                     if col < col_types.len() && col_types[col] == ColType::Unknown {
@@ -147,31 +208,33 @@ pub fn write_csv(
             }
             output.push(b'\n');
         }
-
+        tz_mix.check(&headers, &opts.timezone, py)?;
     }
 
     write_bytes_to_target(py, &output, file_name)
 }
 
 fn write_csv_via_arrow(
-    records: &Py<PyAny>,
     py: Python,
+    reader: Box<dyn arrow_array::RecordBatchReader + Send>,
     output: &mut Vec<u8>,
     delim: u8,
-    sanitize: bool,
+    opts: &CsvOptions,
 ) -> PyResult<()> {
-    let reader = crate::arrow_ffi::stream_to_reader(records, py)?;
     let schema = reader.schema();
+    let zones = opts.timezone.arrow_zones(py, &schema)?;
     let headers: Vec<String> = schema
         .fields()
         .iter()
         .map(|f| f.name().clone())
         .collect();
-    write_csv_row_strings(output, &headers, delim, sanitize);
+    write_csv_row_strings(output, &headers, delim, opts.sanitize);
 
+    let mut rows = 0;
     for batch_result in reader {
         let batch = batch_result.map_err(crate::arrow_ffi::batch_read_err)?;
-        crate::arrow_writer::write_arrow_batch_csv(output, &batch, delim, sanitize)?;
+        crate::arrow_writer::write_arrow_batch_csv(output, &batch, delim, opts, &zones, rows + 1)?;
+        rows += batch.num_rows();
     }
     Ok(())
 }
@@ -186,7 +249,7 @@ fn write_csv_row_strings(output: &mut Vec<u8>, values: &[String], delim: u8, san
     output.push(b'\n');
 }
 
-fn emit_datetime(output: &mut Vec<u8>, dt: &Bound<PyDateTime>) {
+fn emit_datetime(output: &mut Vec<u8>, dt: &Bound<PyDateTime>, digits: FractionDigits) {
     let _ = write!(
         output,
         "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
@@ -197,6 +260,7 @@ fn emit_datetime(output: &mut Vec<u8>, dt: &Bound<PyDateTime>) {
         dt.get_minute(),
         dt.get_second()
     );
+    emit_fraction_csv(output, dt.get_microsecond(), digits);
 }
 
 fn emit_date(output: &mut Vec<u8>, d: &Bound<PyDate>) {
@@ -209,29 +273,62 @@ fn emit_date(output: &mut Vec<u8>, d: &Bound<PyDate>) {
     );
 }
 
+/// The `write_csv` settings every cell consults.
+pub struct CsvOptions {
+    pub sanitize: bool,
+    pub nested: NestedColumns,
+    pub timezone: Timezone,
+    /// Follow aware datetimes with their UTC offset.
+    pub offsets: bool,
+    /// Digits after the seconds of a datetime.
+    pub digits: FractionDigits,
+    pub reps: Reps,
+}
+
 /// [`CellWriter`] sink that appends one Python scalar to a CSV byte buffer.
 /// The type-detection order lives in [`crate::cell`]; this only encodes the
 /// per-type CSV serialization (and the optional formula-injection guard for
 /// strings).
 struct CsvCell<'a> {
     output: &'a mut Vec<u8>,
-    sanitize: bool,
+    opts: &'a CsvOptions,
+    /// Data row, from 1, for errors.
+    row: usize,
+    col: usize,
+    tz_mix: &'a mut TzMix,
 }
 
 impl<'a> CsvCell<'a> {
-    fn new(output: &'a mut Vec<u8>, sanitize: bool) -> Self {
-        CsvCell { output, sanitize }
+    fn new(
+        output: &'a mut Vec<u8>,
+        opts: &'a CsvOptions,
+        row: usize,
+        col: usize,
+        tz_mix: &'a mut TzMix,
+    ) -> Self {
+        CsvCell {
+            output,
+            opts,
+            row,
+            col,
+            tz_mix,
+        }
+    }
+
+    fn write_special(&mut self, special: Special) -> PyResult<()> {
+        let opts = self.opts;
+        emit_special_csv(self.output, special, &opts.reps, opts.sanitize, self.row, self.col)
     }
 }
 
 impl CellWriter for CsvCell<'_> {
     fn write_none(&mut self) -> PyResult<()> {
-        // CSV: a null is an empty field — emit nothing.
-        Ok(())
+        // An empty field unless `na_rep` says otherwise.
+        self.write_special(Special::Missing)
     }
 
     fn write_str(&mut self, s: &str) -> PyResult<()> {
-        write_csv_escaped_guarded(self.output, s, self.sanitize);
+        write_csv_escaped_guarded(self.output, s, self.opts.sanitize);
         Ok(())
     }
 
@@ -242,10 +339,11 @@ impl CellWriter for CsvCell<'_> {
     }
 
     fn write_float(&mut self, f: f64) -> PyResult<()> {
-        if !f.is_nan() && !f.is_infinite() {
-            let mut buf = ryu::Buffer::new();
-            self.output.extend_from_slice(buf.format(f).as_bytes());
+        if let Some(special) = Special::of(f) {
+            return self.write_special(special);
         }
+        let mut buf = ryu::Buffer::new();
+        self.output.extend_from_slice(buf.format(f).as_bytes());
         Ok(())
     }
 
@@ -257,7 +355,14 @@ impl CellWriter for CsvCell<'_> {
     }
 
     fn write_datetime(&mut self, dt: &Bound<'_, PyDateTime>) -> PyResult<()> {
-        emit_datetime(self.output, dt);
+        self.tz_mix.see(self.col, dt)?;
+        let dt = self.opts.timezone.wall(dt)?;
+        emit_datetime(self.output, &dt, self.opts.digits);
+        if self.opts.offsets {
+            if let Some(offset) = py_utc_offset(&dt)? {
+                self.output.extend_from_slice(offset.as_bytes());
+            }
+        }
         Ok(())
     }
 
@@ -265,4 +370,29 @@ impl CellWriter for CsvCell<'_> {
         emit_date(self.output, d);
         Ok(())
     }
+
+    fn write_time(&mut self, t: &Bound<'_, PyTime>) -> PyResult<()> {
+        let micros = u64::from(py_time_seconds(t)) * 1_000_000 + u64::from(t.get_microsecond());
+        emit_time_csv(self.output, micros, self.opts.digits);
+        Ok(())
+    }
+
+    fn write_timedelta(&mut self, d: &Bound<'_, PyDelta>) -> PyResult<()> {
+        emit_duration_csv(self.output, py_timedelta_micros(d), self.opts.digits);
+        Ok(())
+    }
+
+    fn write_nested(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let text = py_nested_text(value, self.opts.nested)?;
+        write_csv_escaped_guarded(self.output, &text, self.opts.sanitize);
+        Ok(())
+    }
+
+    fn write_sentinel(&mut self, sentinel: Sentinel<'_>) -> PyResult<()> {
+        // An error is written as its spelling; a blank as an empty field.
+        if let Sentinel::Error(error) = sentinel {
+            self.output.extend_from_slice(error.as_bytes());
+        }
+        Ok(())
+    }
 }
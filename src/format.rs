@@ -321,6 +321,10 @@ pub struct RowPalette {
     pub text: Option<XlsxFormat>,
     pub float: Option<XlsxFormat>,
     pub datetime: XlsxFormat,
+    /// Time of day, `hh:mm:ss`.
+    pub time: XlsxFormat,
+    /// Elapsed time, `[h]:mm:ss` — the hours run past 24.
+    pub duration: XlsxFormat,
     pub cols: Vec<Option<Format>>,
 }
 
@@ -338,10 +342,14 @@ pub fn build_palettes(
     datetime_fmt: &XlsxFormat,
     band_color: Option<&str>,
 ) -> PyResult<(RowPalette, Option<RowPalette>)> {
+    let time_fmt = XlsxFormat::new().set_num_format("hh:mm:ss");
+    let duration_fmt = XlsxFormat::new().set_num_format("[h]:mm:ss");
     let plain = RowPalette {
         text: None,
         float: float_fmt.cloned(),
         datetime: datetime_fmt.clone(),
+        time: time_fmt.clone(),
+        duration: duration_fmt.clone(),
         cols: col_formats.to_vec(),
     };
 
@@ -361,6 +369,8 @@ pub fn build_palettes(
                 .set_background_color(fill),
         ),
         datetime: datetime_fmt.clone().set_background_color(fill),
+        time: time_fmt.set_background_color(fill),
+        duration: duration_fmt.set_background_color(fill),
         cols: col_formats
             .iter()
             .map(|c| {
@@ -388,6 +398,7 @@ pub fn apply_column_formats(
 ) -> PyResult<()> {
     for (col, fmt) in col_formats.iter().enumerate() {
         if let Some(f) = fmt {
+            crate::parallel::check_registered(&f.inner);
             worksheet
                 .set_column_format(col as u16, &f.inner)
                 .map_err(crate::worksheet::xlsx_err)?;
@@ -0,0 +1,202 @@
+//! Writing a pandas DataFrame's index (`index`, `merge_index_cells`).
+//!
+//! A pandas frame's Arrow stream carries its index as extra columns unless it
+//! is a plain `RangeIndex`, while the column-by-column fallback never sees it.
+//! So the frame is settled before either path reads it: under `index=True`
+//! the index levels become its leading columns, through `reset_index`, and
+//! otherwise any index is dropped. Index columns are written bold. With a
+//! `MultiIndex`, each run of a repeated label in an outer level is merged down
+//! the rows it spans, as `DataFrame.to_excel` does; the innermost level is
+//! written row by row.
+
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use rust_xlsxwriter::{Format, FormatAlign, Worksheet};
+
+use crate::data_types::WorksheetData;
+use crate::helpers::{value_err, SheetLayout};
+use crate::worksheet::xlsx_err;
+
+/// A sheet's `index` and `merge_index_cells`.
+pub struct FrameIndex {
+    write: bool,
+    merge: bool,
+}
+
+impl FrameIndex {
+    pub fn new(index: bool, merge_index_cells: bool) -> Self {
+        FrameIndex {
+            write: index,
+            merge: merge_index_cells,
+        }
+    }
+
+    /// `data` with its index turned into leading columns or dropped, and
+    /// those columns. Anything but a pandas frame has no index and is left
+    /// as it is.
+    pub fn apply(
+        &self,
+        data: WorksheetData,
+        py: Python,
+    ) -> PyResult<(WorksheetData, IndexColumns)> {
+        let (WorksheetData::ArrowDataFrame(obj) | WorksheetData::PandasDataFrame(obj)) = &data
+        else {
+            return Ok((data, IndexColumns::default()));
+        };
+        let frame = obj.bind(py);
+        // Polars has no index; `get_column` tells it apart.
+        if frame.hasattr("get_column")? || !frame.hasattr("reset_index")? {
+            return Ok((data, IndexColumns::default()));
+        }
+        let index = frame.getattr("index")?;
+        let (frame, columns) = if self.write {
+            let levels: usize = index.getattr("nlevels")?.extract()?;
+            let frame = frame.call_method0("reset_index")?;
+            let names = frame
+                .getattr("columns")?
+                .try_iter()?
+                .take(levels)
+                .map(|name| Ok(name?.str()?.to_string()))
+                .collect::<PyResult<_>>()?;
+            let runs = if self.merge && levels > 1 {
+                runs(&index)?
+            } else {
+                Vec::new()
+            };
+            (frame, IndexColumns { names, runs })
+        } else if index.get_type().name()? == "RangeIndex" {
+            return Ok((data, IndexColumns::default()));
+        } else {
+            let kwargs = [("drop", true)].into_py_dict(py)?;
+            let frame = frame.call_method("reset_index", (), Some(&kwargs))?;
+            (frame, IndexColumns::default())
+        };
+        let frame = frame.unbind();
+        let data = match data {
+            WorksheetData::ArrowDataFrame(_) => WorksheetData::ArrowDataFrame(frame),
+            _ => WorksheetData::PandasDataFrame(frame),
+        };
+        Ok((data, columns))
+    }
+}
+
+/// Runs of a repeated label in each outer level of a `MultiIndex`, as
+/// `(level, first row, last row)`. A run also ends where a level above it
+/// changes, so `("a", 1)` and `("b", 1)` keep their own cells.
+fn runs(index: &Bound<'_, PyAny>) -> PyResult<Vec<(u16, usize, usize)>> {
+    let mut codes = Vec::new();
+    for level in index.getattr("codes")?.try_iter()? {
+        let level = level?;
+        // NumPy arrays convert in one go; anything else element by element.
+        let level = if level.hasattr("tolist")? {
+            level.call_method0("tolist")?
+        } else {
+            level
+        };
+        codes.push(level.extract::<Vec<i64>>()?);
+    }
+    let rows = codes.first().map_or(0, Vec::len);
+    // Rows that start a run, at this level or one above it.
+    let mut starts = vec![false; rows];
+    let mut out = Vec::new();
+    for (level, codes) in codes.iter().enumerate().take(codes.len().saturating_sub(1)) {
+        for row in 1..rows {
+            starts[row] |= codes[row] != codes[row - 1];
+        }
+        let mut first = 0;
+        // Past the last row counts as a start, to close the final run.
+        for row in 1..=rows {
+            if starts.get(row).copied().unwrap_or(true) {
+                if row - 1 > first {
+                    out.push((level as u16, first, row - 1));
+                }
+                first = row;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// The columns a frame's index became under `index=True`.
+#[derive(Default)]
+pub struct IndexColumns {
+    /// Their source names, as `reset_index` gave them.
+    names: Vec<String>,
+    /// `(column, first row, last row)` of each run to merge, in source rows.
+    runs: Vec<(u16, usize, usize)>,
+}
+
+impl IndexColumns {
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Whether any cells are merged. Such a sheet is buffered: the data pass
+    /// writes every row of a run, and the cells under its label are blanked
+    /// again once it is done.
+    pub fn has_merges(&self) -> bool {
+        !self.runs.is_empty()
+    }
+
+    /// An Excel table cannot hold merged cells.
+    pub fn check_table(&self, has_table: bool) -> PyResult<()> {
+        if has_table && self.has_merges() {
+            return Err(value_err(
+                "merge_index_cells and table cannot be combined: a table cannot hold merged \
+cells; pass merge_index_cells=False"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the index columns bold and merge the runs that land on a sheet
+    /// whose first data row holds source row `first` and which takes up to
+    /// `capacity` rows. A run cut by the end of the sheet is merged on each
+    /// side. Returns the merged `(column, first row, last row)`, for
+    /// [`clear_merged`].
+    pub fn apply(
+        &self,
+        worksheet: &mut Worksheet,
+        layout: &SheetLayout,
+        first: usize,
+        capacity: u32,
+    ) -> PyResult<Vec<(u16, u32, u32)>> {
+        let fmt = index_format();
+        for col in 0..self.names.len() {
+            worksheet.set_column_format(col as u16, &fmt).map_err(xlsx_err)?;
+        }
+        let end = first + capacity as usize;
+        let top = layout.first_data_row();
+        let mut merged = Vec::new();
+        for &(col, a, b) in &self.runs {
+            let (a, b) = (a.max(first), b.min(end.saturating_sub(1)));
+            if b <= a {
+                continue;
+            }
+            let (r1, r2) = (top + (a - first) as u32, top + (b - first) as u32);
+            worksheet
+                .merge_range(r1, col, r2, col, "", &fmt)
+                .map_err(xlsx_err)?;
+            merged.push((col, r1, r2));
+        }
+        Ok(merged)
+    }
+}
+
+/// Blank the cells under each merged label, which the data pass filled in like
+/// any other.
+pub fn clear_merged(worksheet: &mut Worksheet, merged: &[(u16, u32, u32)]) -> PyResult<()> {
+    let fmt = index_format();
+    for &(col, r1, r2) in merged {
+        for row in r1 + 1..=r2 {
+            worksheet.write_blank(row, col, &fmt).map_err(xlsx_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Bold, and top-aligned so a merged label sits beside the first of its rows.
+fn index_format() -> Format {
+    Format::new().set_bold().set_align(FormatAlign::Top)
+}
@@ -0,0 +1,157 @@
+//! Grouped headers (`header_groups`): extra header rows above the column
+//! names, with one merged cell over each run of columns in the same group.
+//!
+//! `header_groups` is a separator, and each header is split on it:
+//! `"Q1.Revenue"` under `header_groups="."` writes `Revenue` on the header row
+//! and `Q1` above it, merged across the neighbouring `Q1.` columns. Polars
+//! struct columns under `nested_columns="explode"` are named this way already,
+//! and a pandas `MultiIndex` is joined with the separator first. The group
+//! rows sit directly above the header row. Left unset, `header_row` moves
+//! down to make room for them, taking the data, autofilter and freeze panes
+//! along; a given one has to leave room. Column options still name a column
+//! by its full header.
+
+use pyo3::prelude::*;
+use pyo3::types::IntoPyDict;
+use rust_xlsxwriter::{Format, FormatAlign, Worksheet};
+
+use crate::data_types::WorksheetData;
+use crate::helpers::{value_err, SheetLayout};
+use crate::worksheet::xlsx_err;
+
+/// A sheet's `header_groups` separator.
+pub struct HeaderGroups {
+    separator: String,
+}
+
+impl HeaderGroups {
+    pub fn parse(separator: Option<String>) -> PyResult<Option<Self>> {
+        match separator {
+            None => Ok(None),
+            Some(separator) if separator.is_empty() => Err(value_err(
+                "header_groups must be a separator such as \".\", got ''".to_string(),
+            )),
+            Some(separator) => Ok(Some(HeaderGroups { separator })),
+        }
+    }
+
+    /// The text written on the header row for `header`: its last part.
+    pub fn leaf<'a>(&self, header: &'a str) -> &'a str {
+        header.rsplit(self.separator.as_str()).next().unwrap_or(header)
+    }
+
+    /// The number of group rows `headers` need above the header row.
+    pub fn levels(&self, headers: &[String]) -> u32 {
+        headers
+            .iter()
+            .map(|h| h.matches(self.separator.as_str()).count() as u32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Write the group rows above the header row, which
+    /// [`SheetLayout::place_header`] has made room for. Group cells take `fmt`.
+    pub fn write(
+        &self,
+        worksheet: &mut Worksheet,
+        layout: &SheetLayout,
+        headers: &[String],
+        fmt: &Format,
+    ) -> PyResult<()> {
+        let parts: Vec<Vec<&str>> = headers
+            .iter()
+            .map(|h| h.split(self.separator.as_str()).collect())
+            .collect();
+        let levels = self.levels(headers) as usize;
+        if levels == 0 {
+            return Ok(());
+        }
+        let header_row = layout.header_row();
+        let top = header_row - levels as u32;
+        if let Some((r1, c1, r2, c2, ..)) = layout.merges.iter().find(|m| m.2 >= top) {
+            return Err(value_err(format!(
+                "header_groups: merge range ({r1}, {c1}, {r2}, {c2}) reaches row {r2}, but rows \
+{top} to {} hold the header groups. Raise header_row to make room",
+                header_row - 1
+            )));
+        }
+        for level in 0..levels {
+            let row = top + level as u32;
+            // Each column's group at this level, as the path down to it, so
+            // `2024.Q1` and `2025.Q1` stay apart. A column with fewer levels
+            // has none.
+            let groups: Vec<Option<&[&str]>> = parts
+                .iter()
+                .map(|p| (p.len() - 1 > level).then(|| &p[..=level]))
+                .collect();
+            let mut first = 0;
+            while first < groups.len() {
+                let mut last = first;
+                while last + 1 < groups.len() && groups[last + 1] == groups[first] {
+                    last += 1;
+                }
+                let (c1, c2) = (first as u16, last as u16);
+                match groups[first] {
+                    // Excel refuses a merge of a single cell.
+                    Some(path) if c1 < c2 => {
+                        worksheet.merge_range(row, c1, row, c2, path[level], fmt)
+                    }
+                    Some(path) => worksheet.write_string_with_format(row, c1, path[level], fmt),
+                    // Blank, but formatted like the rest of the header.
+                    None => (c1..=c2).try_fold(&mut *worksheet, |ws, col| {
+                        ws.write_blank(row, col, fmt)
+                    }),
+                }
+                .map_err(xlsx_err)?;
+                first = last + 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// `data` with a pandas `MultiIndex` header joined into single names with
+    /// the separator, leaving out empty levels.
+    pub fn flatten(&self, data: WorksheetData, py: Python) -> PyResult<WorksheetData> {
+        let (WorksheetData::ArrowDataFrame(obj) | WorksheetData::PandasDataFrame(obj)) = &data
+        else {
+            return Ok(data);
+        };
+        let frame = obj.bind(py);
+        let Ok(columns) = frame.getattr("columns") else {
+            return Ok(data);
+        };
+        let Ok(nlevels) = columns.getattr("nlevels") else {
+            return Ok(data);
+        };
+        if nlevels.extract::<usize>()? < 2 {
+            return Ok(data);
+        }
+        let mut names = Vec::new();
+        for column in columns.try_iter()? {
+            let mut parts = Vec::new();
+            for part in column?.try_iter()? {
+                let part = part?.str()?.to_string();
+                if !part.is_empty() {
+                    parts.push(part);
+                }
+            }
+            names.push(parts.join(&self.separator));
+        }
+        let kwargs = [("axis", 1)].into_py_dict(py)?;
+        let flat = frame.call_method("set_axis", (names,), Some(&kwargs))?.unbind();
+        Ok(match data {
+            WorksheetData::ArrowDataFrame(_) => WorksheetData::ArrowDataFrame(flat),
+            _ => WorksheetData::PandasDataFrame(flat),
+        })
+    }
+}
+
+/// The format of a group cell: `header_format` as given, else centred over
+/// its columns, and bold under `bold_headers`.
+pub fn group_format(header_fmt: Option<&Format>, bold_headers: bool) -> Format {
+    match header_fmt {
+        Some(fmt) => fmt.clone(),
+        None if bold_headers => Format::new().set_align(FormatAlign::Center).set_bold(),
+        None => Format::new().set_align(FormatAlign::Center),
+    }
+}
@@ -1,7 +1,12 @@
 //! Shared helpers for Excel/CSV writing paths.
 
+use std::sync::atomic::{AtomicU32, Ordering};
+
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyDict, PyList, PyTimeAccess};
+use pyo3::types::{
+    PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyDict, PyList, PyTime,
+    PyTimeAccess,
+};
 use pyo3::Py;
 use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet};
 
@@ -20,14 +25,17 @@ pub enum ColType {
     Int = 4,
     DateTime = 5,
     Date = 6,
+    Time = 7,
+    Duration = 8,
+    Nested = 9,
 }
 
-/// Convert a Python `datetime` to `ExcelDateTime`.
+/// Convert a Python `datetime` to `ExcelDateTime`, microseconds included.
 pub fn py_datetime_to_excel(dt: &Bound<PyDateTime>) -> PyResult<ExcelDateTime> {
     ExcelDateTime::from_ymd(
         dt.get_year() as u16,
-        dt.get_month() as u8,
-        dt.get_day() as u8,
+        dt.get_month(),
+        dt.get_day(),
     )
     .map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -37,8 +45,8 @@ pub fn py_datetime_to_excel(dt: &Bound<PyDateTime>) -> PyResult<ExcelDateTime> {
     })?
     .and_hms(
         dt.get_hour() as u16,
-        dt.get_minute() as u8,
-        dt.get_second() as u8,
+        dt.get_minute(),
+        f64::from(dt.get_second()) + f64::from(dt.get_microsecond()) / 1e6,
     )
     .map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -52,8 +60,8 @@ pub fn py_datetime_to_excel(dt: &Bound<PyDateTime>) -> PyResult<ExcelDateTime> {
 pub fn py_date_to_excel(d: &Bound<PyDate>) -> PyResult<ExcelDateTime> {
     ExcelDateTime::from_ymd(
         d.get_year() as u16,
-        d.get_month() as u8,
-        d.get_day() as u8,
+        d.get_month(),
+        d.get_day(),
     )
     .map_err(|e| {
         PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
@@ -63,6 +71,276 @@ pub fn py_date_to_excel(d: &Bound<PyDate>) -> PyResult<ExcelDateTime> {
     })
 }
 
+/// Whole seconds since midnight of a Python `time`. Any `tzinfo` is ignored.
+pub fn py_time_seconds(t: &Bound<PyTime>) -> u32 {
+    u32::from(t.get_hour()) * 3600 + u32::from(t.get_minute()) * 60 + u32::from(t.get_second())
+}
+
+/// A Python `time` as Excel stores a time of day: the fraction of a day since
+/// midnight.
+pub fn py_time_to_excel(t: &Bound<PyTime>) -> f64 {
+    let seconds = f64::from(py_time_seconds(t)) + f64::from(t.get_microsecond()) / 1e6;
+    seconds / SECONDS_PER_DAY
+}
+
+/// A Python `timedelta` in microseconds.
+pub fn py_timedelta_micros(d: &Bound<PyDelta>) -> i64 {
+    (i64::from(d.get_days()) * 86_400 + i64::from(d.get_seconds())) * 1_000_000
+        + i64::from(d.get_microseconds())
+}
+
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// How duration values are written (`durations`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durations {
+    /// Days, formatted `[h]:mm:ss` — Excel's elapsed time.
+    #[default]
+    Elapsed,
+    /// A plain number of seconds.
+    Seconds,
+}
+
+impl Durations {
+    pub fn parse(value: Option<&str>) -> PyResult<Self> {
+        match value {
+            None | Some("elapsed") => Ok(Durations::Elapsed),
+            Some("seconds") => Ok(Durations::Seconds),
+            Some(other) => Err(value_err(format!(
+                "durations must be 'elapsed' or 'seconds', got '{other}'"
+            ))),
+        }
+    }
+}
+
+/// Write one duration cell of `seconds`. `elapsed_fmt` is the `[h]:mm:ss`
+/// format, or a column's own; `number_fmt` is for plain seconds.
+pub fn write_duration(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    seconds: f64,
+    durations: Durations,
+    elapsed_fmt: &Format,
+    number_fmt: Option<&Format>,
+) -> PyResult<()> {
+    match durations {
+        Durations::Elapsed => {
+            write_number_opt(worksheet, row, col, seconds / SECONDS_PER_DAY, Some(elapsed_fmt))
+        }
+        Durations::Seconds => write_number_opt(worksheet, row, col, seconds, number_fmt),
+    }
+}
+
+/// Integers up to this magnitude are exact in an f64, and so in Excel.
+pub const MAX_EXACT_INT: u128 = 1 << 53;
+
+/// What to do with an integer past 2^53, which Excel cannot hold exactly
+/// (`int_overflow`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntOverflow {
+    /// Write the nearest number, dropping the low digits as Excel would.
+    #[default]
+    Number,
+    /// Write the exact digits as a text cell.
+    Text,
+    /// Raise, naming the column.
+    Error,
+}
+
+impl IntOverflow {
+    pub fn parse(value: Option<&str>) -> PyResult<Self> {
+        match value {
+            None | Some("number") => Ok(IntOverflow::Number),
+            Some("text") => Ok(IntOverflow::Text),
+            Some("error") => Ok(IntOverflow::Error),
+            Some(other) => Err(value_err(format!(
+                "int_overflow must be 'number', 'text' or 'error', got '{other}'"
+            ))),
+        }
+    }
+
+    /// The error for `digits` in `column` under [`IntOverflow::Error`].
+    fn err(column: &str, digits: &str) -> PyErr {
+        value_err(format!(
+            "column '{column}': {digits} is past 2^53 and would be rounded in Excel. Pass \
+int_overflow=\"text\" to keep the exact digits, or int_overflow=\"number\" to round"
+        ))
+    }
+}
+
+/// Write one integer cell, applying `int_overflow` past [`MAX_EXACT_INT`].
+pub fn write_int_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: i128,
+    overflow: IntOverflow,
+    fmt: Option<&Format>,
+    column: &str,
+) -> PyResult<()> {
+    if value.unsigned_abs() <= MAX_EXACT_INT {
+        return write_number_opt(worksheet, row, col, value as f64, fmt);
+    }
+    match overflow {
+        IntOverflow::Number => write_number_opt(worksheet, row, col, value as f64, fmt),
+        IntOverflow::Text => write_string_opt(worksheet, row, col, &value.to_string(), fmt),
+        IntOverflow::Error => Err(IntOverflow::err(column, &value.to_string())),
+    }
+}
+
+/// [`write_int_cell`] for a Python integer, which may be past even `i128`.
+pub fn write_py_int(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: &Bound<'_, PyAny>,
+    overflow: IntOverflow,
+    fmt: Option<&Format>,
+    column: &str,
+) -> PyResult<()> {
+    if let Ok(value) = value.extract::<i128>() {
+        return write_int_cell(worksheet, row, col, value, overflow, fmt, column);
+    }
+    match overflow {
+        IntOverflow::Number => write_number_opt(worksheet, row, col, value.extract()?, fmt),
+        IntOverflow::Text => write_string_opt(worksheet, row, col, &value.str()?.to_cow()?, fmt),
+        IntOverflow::Error => Err(IntOverflow::err(column, &value.str()?.to_cow()?)),
+    }
+}
+
+/// Write a value of an `id_columns` column as text: integers and whole
+/// floats as their digits, anything else as `str()` shows it. `None`, NaN and
+/// infinity follow `reps`.
+pub fn write_py_id(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    value: &Bound<'_, PyAny>,
+    fmt: Option<&Format>,
+    reps: &crate::missing::Reps,
+) -> PyResult<()> {
+    use crate::missing::{write_special, Special};
+    // A pandas integer column with gaps comes through as floats.
+    let text = if value.is_none() {
+        return write_special(worksheet, row, col, Special::Missing, reps, fmt);
+    } else if let Ok(f) = value.cast::<pyo3::types::PyFloat>() {
+        match f.value() {
+            f if !f.is_finite() => return write_num(worksheet, row, col, f, fmt, reps),
+            f if f.fract() == 0.0 => format!("{f:.0}"),
+            _ => value.str()?.to_string(),
+        }
+    } else {
+        value.str()?.to_string()
+    };
+    write_string_opt(worksheet, row, col, &text, fmt)
+}
+
+/// Turn off Excel's "number stored as text" flag on the data rows of the
+/// `id_columns` columns, whose digits are text on purpose.
+pub fn ignore_id_warnings(
+    worksheet: &mut Worksheet,
+    id_columns: Option<&Vec<String>>,
+    headers: &[String],
+    layout: &SheetLayout,
+    data_rows: u32,
+) -> PyResult<()> {
+    let Some(names) = id_columns else {
+        return Ok(());
+    };
+    if data_rows == 0 {
+        return Ok(());
+    }
+    let first = layout.first_data_row();
+    let last = first + data_rows - 1;
+    for (col, header) in headers.iter().enumerate() {
+        if names.contains(header) {
+            let col = col as u16;
+            worksheet
+                .ignore_error_range(
+                    first,
+                    col,
+                    last,
+                    col,
+                    rust_xlsxwriter::IgnoreError::NumberStoredAsText,
+                )
+                .map_err(xlsx_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// A time of day for CSV, `HH:MM:SS`, from microseconds since midnight; the
+/// part second follows `digits` as it does for datetimes.
+pub fn emit_time_csv(output: &mut Vec<u8>, micros: u64, digits: FractionDigits) {
+    use std::io::Write;
+    let seconds = micros / 1_000_000;
+    let _ = write!(
+        output,
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    emit_fraction_csv(output, (micros % 1_000_000) as u32, digits);
+}
+
+/// Digits after the seconds of a CSV datetime (`datetime_precision`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum FractionDigits {
+    /// As many as the value needs: none for whole seconds, three for whole
+    /// milliseconds, else six.
+    #[default]
+    Auto,
+    /// Exactly this many, 0 to 6, cut off rather than rounded.
+    Fixed(u8),
+}
+
+impl FractionDigits {
+    pub fn parse(value: Option<u8>) -> PyResult<Self> {
+        match value {
+            None => Ok(FractionDigits::Auto),
+            Some(digits @ 0..=6) => Ok(FractionDigits::Fixed(digits)),
+            Some(other) => Err(value_err(format!(
+                "datetime_precision must be between 0 and 6, got {other}"
+            ))),
+        }
+    }
+}
+
+/// The fraction of a second after a CSV datetime's seconds, `.123`.
+pub fn emit_fraction_csv(output: &mut Vec<u8>, micros: u32, digits: FractionDigits) {
+    let digits = match digits {
+        FractionDigits::Auto if micros == 0 => 0,
+        FractionDigits::Auto if micros.is_multiple_of(1000) => 3,
+        FractionDigits::Auto => 6,
+        FractionDigits::Fixed(digits) => usize::from(digits),
+    };
+    if digits > 0 {
+        let text = format!("{micros:06}");
+        output.push(b'.');
+        output.extend_from_slice(&text.as_bytes()[..digits]);
+    }
+}
+
+/// A duration for CSV as the `[h]:mm:ss` cell shows it: `26:03:04`, with a
+/// leading `-` when negative, from microseconds. The part second follows
+/// `digits` as it does for datetimes.
+pub fn emit_duration_csv(output: &mut Vec<u8>, micros: i64, digits: FractionDigits) {
+    use std::io::Write;
+    let sign = if micros < 0 { "-" } else { "" };
+    let abs = micros.unsigned_abs();
+    let seconds = abs / 1_000_000;
+    let _ = write!(
+        output,
+        "{sign}{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    emit_fraction_csv(output, (abs % 1_000_000) as u32, digits);
+}
+
 /// Row-level layout for one sheet, resolved from Python before any cell is
 /// written.
 ///
@@ -73,8 +351,13 @@ pub fn py_date_to_excel(d: &Bound<PyDate>) -> PyResult<ExcelDateTime> {
 /// [`SheetLayout::apply`], before the first data cell.
 #[derive(Default)]
 pub struct SheetLayout {
-    /// Row index the header is written on. Data starts at `header_row + 1`.
-    pub header_row: u32,
+    /// Row index the header is written on, as given. Read it through
+    /// [`SheetLayout::header_row`], which also places an unset one.
+    header_row: Option<u32>,
+    /// `header_groups` rows an unset header row sits below, counted from the
+    /// headers by [`SheetLayout::place_header`] before the first one is
+    /// written.
+    group_rows: AtomicU32,
     /// `(first_row, first_col, last_row, last_col, value, format)`.
     pub merges: Vec<(u32, u16, u32, u16, String, Option<Format>)>,
     pub row_heights: Vec<(u32, f64)>,
@@ -92,6 +375,11 @@ pub struct SheetLayout {
     pub totals: Vec<(String, TotalsCell)>,
     pub totals_label: Option<String>,
     pub totals_format: Option<Format>,
+    /// Group rows written directly above the header row, with the header
+    /// cells themselves.
+    pub header_groups: Option<crate::header_groups::HeaderGroups>,
+    /// Row and column outline groups, and the column giving row levels.
+    pub outline: crate::outline::Outline,
 }
 
 /// A computed column: a header and a formula template appended after the data
@@ -266,6 +554,20 @@ pub enum TotalsCell {
     Formula(String),
 }
 
+impl TotalsCell {
+    /// The formula text for the column with letter `letter`, whose data spans
+    /// the 1-based rows `first..=last`.
+    pub fn render(&self, letter: &str, first: u32, last: u32) -> String {
+        match self {
+            TotalsCell::Aggregate(f) => format!("={f}({letter}{first}:{letter}{last})"),
+            TotalsCell::Formula(t) => t
+                .replace("{col}", letter)
+                .replace("{first}", &first.to_string())
+                .replace("{last}", &last.to_string()),
+        }
+    }
+}
+
 /// Map an aggregate name to its Excel function.
 fn excel_function(name: &str) -> Option<&'static str> {
     match name.to_ascii_lowercase().as_str() {
@@ -281,9 +583,45 @@ fn excel_function(name: &str) -> Option<&'static str> {
 }
 
 impl SheetLayout {
+    /// Row index the header is written on: `header_row`, or, left unset, the
+    /// row below any `header_groups` rows.
+    pub fn header_row(&self) -> u32 {
+        self.header_row.unwrap_or(self.group_rows.load(Ordering::Relaxed))
+    }
+
     /// First data row.
     pub fn first_data_row(&self) -> u32 {
-        self.header_row + 1
+        self.header_row() + 1
+    }
+
+    /// Make room for the `header_groups` rows over `headers`. Called before
+    /// anything is written below them: an unset header row moves down under
+    /// the group rows, and everything placed from it — the data, autofilter
+    /// and freeze panes — moves with it. A given one has to leave room.
+    pub fn place_header(&self, headers: &[String]) -> PyResult<()> {
+        let Some(groups) = &self.header_groups else {
+            return Ok(());
+        };
+        let levels = groups.levels(headers);
+        match self.header_row {
+            None => self.group_rows.store(levels, Ordering::Relaxed),
+            Some(header_row) if header_row < levels => {
+                return Err(value_err(format!(
+                    "header_groups: the headers have {levels} group level(s), written in the \
+rows above the header row; pass header_row={levels} or more, or leave it unset, got {header_row}"
+                )));
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// The frozen row count `row`, moved down with an unset header row.
+    pub fn freeze_row(&self, row: u32) -> u32 {
+        match self.header_row {
+            None if row > 0 => row + self.group_rows.load(Ordering::Relaxed),
+            _ => row,
+        }
     }
 
     /// True when `row` (an absolute sheet row) is a shaded band row. The first
@@ -292,7 +630,13 @@ impl SheetLayout {
         self.band_color.is_some() && (row - self.first_data_row()) % 2 == 1
     }
 
-    /// Emit merges, row heights and row formats. Must run before data rows.
+    /// True when a totals row (formulas or just a label) follows the data.
+    pub fn has_totals(&self) -> bool {
+        !self.totals.is_empty() || self.totals_label.is_some()
+    }
+
+    /// Emit merges, row heights, row formats and outline groups. Must run
+    /// before data rows.
     pub fn apply(&self, worksheet: &mut Worksheet) -> PyResult<()> {
         for (r1, c1, r2, c2, value, fmt) in &self.merges {
             let blank = Format::new();
@@ -308,7 +652,7 @@ impl SheetLayout {
         for (row, fmt) in &self.row_formats {
             worksheet.set_row_format(*row, fmt).map_err(xlsx_err)?;
         }
-        Ok(())
+        self.outline.apply(worksheet)
     }
 
     /// Add the filter over `header_row..=header_row + data_rows`. Safe to call
@@ -325,9 +669,9 @@ impl SheetLayout {
         }
         worksheet
             .autofilter(
-                self.header_row,
+                self.header_row(),
                 0,
-                self.header_row + data_rows,
+                self.header_row() + data_rows,
                 (num_columns - 1) as u16,
             )
             .map_err(xlsx_err)?;
@@ -343,7 +687,7 @@ impl SheetLayout {
         data_rows: u32,
         py: Python,
     ) -> PyResult<()> {
-        if (self.totals.is_empty() && self.totals_label.is_none()) || data_rows == 0 {
+        if !self.has_totals() || data_rows == 0 {
             return Ok(());
         }
         let row = self.first_data_row() + data_rows;
@@ -366,13 +710,7 @@ impl SheetLayout {
                 used_first_column = true;
             }
             let letter = rust_xlsxwriter::utility::column_number_to_name(col as u16);
-            let formula = match function {
-                TotalsCell::Aggregate(f) => format!("={f}({letter}{first}:{letter}{last})"),
-                TotalsCell::Formula(t) => t
-                    .replace("{col}", &letter)
-                    .replace("{first}", &first.to_string())
-                    .replace("{last}", &last.to_string()),
-            };
+            let formula = function.render(&letter, first, last);
             match &self.totals_format {
                 Some(fmt) => worksheet
                     .write_formula_with_format(row, col as u16, formula.as_str(), fmt)
@@ -402,10 +740,132 @@ drop one of them or move the aggregate to another column"
     }
 }
 
-fn value_err(msg: String) -> PyErr {
+pub fn value_err(msg: String) -> PyErr {
     PyErr::new::<pyo3::exceptions::PyValueError, _>(msg)
 }
 
+/// One option dict from Python — a conditional-format rule, a validation, and
+/// so on — with the label used to prefix its error messages
+/// (`conditional_formats['amount']`).
+///
+/// Every accessor treats an explicit `None` like a missing key, and every
+/// type mismatch is a `ValueError` naming the key, so a typo in a nested spec
+/// points at the exact entry instead of surfacing as a bare `TypeError`.
+pub struct OptionDict<'py> {
+    dict: Bound<'py, PyDict>,
+    pub label: String,
+}
+
+impl<'py> OptionDict<'py> {
+    pub fn new(obj: &Bound<'py, PyAny>, label: String) -> PyResult<Self> {
+        let dict = obj
+            .cast::<PyDict>()
+            .map_err(|_| value_err(format!("{label} must be a dict")))?
+            .clone();
+        Ok(OptionDict { dict, label })
+    }
+
+    /// Reject keys outside `allowed`, so a misspelt option raises instead of
+    /// being silently ignored.
+    pub fn check_keys(&self, allowed: &[&str]) -> PyResult<()> {
+        for key in self.dict.keys().iter() {
+            let key: String = key.extract()?;
+            if !allowed.contains(&key.as_str()) {
+                return Err(value_err(format!(
+                    "{}: unknown option '{key}' (valid: {})",
+                    self.label,
+                    allowed.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+        Ok(self.dict.get_item(key)?.filter(|v| !v.is_none()))
+    }
+
+    pub fn require(&self, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.get(key)?
+            .ok_or_else(|| value_err(format!("{}: missing required option '{key}'", self.label)))
+    }
+
+    fn extract<T>(&self, key: &str, value: Bound<'py, PyAny>, what: &str) -> PyResult<T>
+    where
+        T: for<'a> FromPyObject<'a, 'py, Error = PyErr>,
+    {
+        value
+            .extract()
+            .map_err(|_| value_err(format!("{}: '{key}' must be {what}", self.label)))
+    }
+
+    pub fn str(&self, key: &str) -> PyResult<Option<String>> {
+        self.get(key)?
+            .map(|v| self.extract(key, v, "a string"))
+            .transpose()
+    }
+
+    pub fn f64(&self, key: &str) -> PyResult<Option<f64>> {
+        self.get(key)?
+            .map(|v| self.extract(key, v, "a number"))
+            .transpose()
+    }
+
+    pub fn u32(&self, key: &str) -> PyResult<Option<u32>> {
+        self.get(key)?
+            .map(|v| self.extract(key, v, "a non-negative int"))
+            .transpose()
+    }
+
+    pub fn bool(&self, key: &str) -> PyResult<Option<bool>> {
+        self.get(key)?
+            .map(|v| self.extract(key, v, "a bool"))
+            .transpose()
+    }
+
+    /// An A1-style cell such as `"H2"`, as a zero-based `(row, col)`.
+    pub fn cell(&self, key: &str) -> PyResult<Option<(u32, u16)>> {
+        let Some(cell) = self.str(key)? else {
+            return Ok(None);
+        };
+        let bad = || {
+            value_err(format!(
+                "{}: '{key}' must be a cell like \"H2\", got '{cell}'",
+                self.label
+            ))
+        };
+        let upper = cell.trim().to_ascii_uppercase();
+        let split = upper.find(|c: char| c.is_ascii_digit()).ok_or_else(bad)?;
+        let (letters, digits) = upper.split_at(split);
+        if letters.is_empty()
+            || letters.len() > 3
+            || !letters.chars().all(|c| c.is_ascii_uppercase())
+            || !digits.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(bad());
+        }
+        let col = rust_xlsxwriter::utility::column_name_to_number(letters);
+        let row: u32 = digits.parse().map_err(|_| bad())?;
+        if row == 0 || row > 1_048_576 || col > 16_383 {
+            return Err(bad());
+        }
+        Ok(Some((row - 1, col)))
+    }
+
+    pub fn format(&self, key: &str) -> PyResult<Option<Format>> {
+        match self.get(key)? {
+            Some(v) => Ok(Some(
+                v.extract::<crate::format::Format>()
+                    .map_err(|_| {
+                        value_err(format!("{}: '{key}' must be a Format object", self.label))
+                    })?
+                    .inner,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
 /// Read a `{row_index: value}` mapping into a sorted `Vec<(u32, T)>`.
 fn row_keyed<T>(
     spec: Option<&Bound<'_, PyAny>>,
@@ -431,7 +891,7 @@ fn row_keyed<T>(
 /// constant-memory mode would otherwise drop without raising.
 #[allow(clippy::too_many_arguments)]
 pub fn resolve_layout(
-    header_row: u32,
+    header_row: Option<u32>,
     merge_ranges: Option<&Bound<'_, PyAny>>,
     row_heights: Option<&Bound<'_, PyAny>>,
     row_formats: Option<&Bound<'_, PyAny>>,
@@ -440,6 +900,11 @@ pub fn resolve_layout(
     totals_row: Option<&Bound<'_, PyAny>>,
     totals_label: Option<String>,
     totals_format: Option<Format>,
+    header_groups: Option<String>,
+    row_groups: Option<&Bound<'_, PyAny>>,
+    column_groups: Option<&Bound<'_, PyAny>>,
+    outline_column: Option<String>,
+    outline_collapsed: bool,
 ) -> PyResult<SheetLayout> {
     let mut totals = Vec::new();
     if let Some(spec) = totals_row {
@@ -500,6 +965,7 @@ pub fn resolve_layout(
             }
             // A merge can only be written before the rows it covers are
             // flushed, and headers/data start at `header_row`.
+            let header_row = header_row.unwrap_or(0);
             if r2 >= header_row {
                 return Err(value_err(format!(
                     "merge range ({r1}, {c1}, {r2}, {c2}) reaches row {r2}, but the header row is {header_row} and data follows it. \
@@ -540,6 +1006,7 @@ Merged ranges must sit strictly above the header row — raise header_row to at
 
     Ok(SheetLayout {
         header_row,
+        group_rows: AtomicU32::new(0),
         merges,
         row_heights: heights,
         row_formats: formats,
@@ -548,18 +1015,27 @@ Merged ranges must sit strictly above the header row — raise header_row to at
         totals,
         totals_label,
         totals_format,
+        header_groups: crate::header_groups::HeaderGroups::parse(header_groups)?,
+        outline: crate::outline::Outline::parse(
+            row_groups,
+            column_groups,
+            outline_column,
+            outline_collapsed,
+        )?,
     })
 }
 
-/// Write a header cell on `row`, optionally bold, and mark the column
-/// as an index (bold) column if listed in `index_columns`.
-/// When `header_fmt` is `Some`, it wins over `bold_headers` for the cell itself.
+/// Write `text` as the header cell of the column named `header` on `row`,
+/// optionally bold, and mark the column as an index (bold) column if listed in
+/// `index_columns`. When `header_fmt` is `Some`, it wins over `bold_headers`
+/// for the cell itself.
 #[allow(clippy::too_many_arguments)]
 pub fn write_header(
     worksheet: &mut Worksheet,
     row: u32,
     col: u16,
     header: &str,
+    text: &str,
     bold_headers: bool,
     bold_fmt: &Format,
     index_columns: Option<&Vec<String>>,
@@ -567,17 +1043,17 @@ pub fn write_header(
 ) -> PyResult<()> {
     if let Some(fmt) = header_fmt {
         worksheet
-            .write_string_with_format(row, col, header, fmt)
+            .write_string_with_format(row, col, text, fmt)
             .map_err(xlsx_err)?;
         return Ok(());
     }
     if bold_headers {
         worksheet
-            .write_string_with_format(row, col, header, bold_fmt)
+            .write_string_with_format(row, col, text, bold_fmt)
             .map_err(xlsx_err)?;
     } else {
         worksheet
-            .write_string(row, col, header)
+            .write_string(row, col, text)
             .map_err(xlsx_err)?;
     }
     if let Some(cols) = index_columns {
@@ -588,23 +1064,32 @@ pub fn write_header(
     Ok(())
 }
 
-/// Write every header cell for a sheet on `row` via [`write_header`].
+/// Write every header cell for a sheet on its header row via
+/// [`write_header`], and any group rows above it, placing the header row
+/// first.
 #[allow(clippy::too_many_arguments)]
 pub fn write_all_headers(
     worksheet: &mut Worksheet,
-    row: u32,
+    layout: &SheetLayout,
     headers: &[String],
     bold_headers: bool,
     bold_fmt: &Format,
     index_columns: Option<&Vec<String>>,
     header_fmt: Option<&Format>,
 ) -> PyResult<()> {
+    layout.place_header(headers)?;
+    let groups = layout.header_groups.as_ref();
+    if let Some(groups) = groups {
+        let fmt = crate::header_groups::group_format(header_fmt, bold_headers);
+        groups.write(worksheet, layout, headers, &fmt)?;
+    }
     for (col, header) in headers.iter().enumerate() {
         write_header(
             worksheet,
-            row,
+            layout.header_row(),
             col as u16,
             header,
+            groups.map_or(header.as_str(), |g| g.leaf(header)),
             bold_headers,
             bold_fmt,
             index_columns,
@@ -614,18 +1099,20 @@ pub fn write_all_headers(
     Ok(())
 }
 
-/// Write a numeric cell with optional float format. NaN/Inf → empty string.
+/// Write a numeric cell with optional float format. NaN and ±inf are written
+/// as `nan_rep` and `inf_rep` say, a blank by default.
 pub fn write_num(
     worksheet: &mut Worksheet,
     row: u32,
     col: u16,
     val: f64,
     float_fmt: Option<&Format>,
+    reps: &crate::missing::Reps,
 ) -> PyResult<()> {
-    if val.is_nan() || val.is_infinite() {
-        // Keep the format on the blank so a banded row has no unshaded hole.
-        write_string_opt(worksheet, row, col, "", float_fmt)?;
+    if let Some(special) = crate::missing::Special::of(val) {
+        crate::missing::write_special(worksheet, row, col, special, reps, float_fmt)?;
     } else if let Some(fmt) = float_fmt {
+        crate::parallel::check_registered(fmt);
         worksheet
             .write_number_with_format(row, col, val, fmt)
             .map_err(xlsx_err)?;
@@ -636,8 +1123,8 @@ pub fn write_num(
 }
 
 /// Write a numeric cell, with an optional explicit format. Unlike [`write_num`]
-/// this does NOT guard NaN/Inf — callers use it for integer values (and Arrow
-/// integral/float16 columns) that can never be NaN/Inf.
+/// this does NOT guard NaN/Inf — callers use it for integers, times and
+/// durations, which can never be NaN/Inf.
 pub fn write_number_opt(
     worksheet: &mut Worksheet,
     row: u32,
@@ -646,6 +1133,7 @@ pub fn write_number_opt(
     fmt: Option<&Format>,
 ) -> PyResult<()> {
     if let Some(fmt) = fmt {
+        crate::parallel::check_registered(fmt);
         worksheet
             .write_number_with_format(row, col, val, fmt)
             .map_err(xlsx_err)?;
@@ -664,6 +1152,7 @@ pub fn write_string_opt(
     fmt: Option<&Format>,
 ) -> PyResult<()> {
     if let Some(fmt) = fmt {
+        crate::parallel::check_registered(fmt);
         worksheet
             .write_string_with_format(row, col, val, fmt)
             .map_err(xlsx_err)?;
@@ -693,20 +1182,25 @@ pub fn write_url_or_text(
         None => worksheet.write_url(row, col, val).is_ok(),
     };
     if wrote {
+        // Without a format the link takes the hyperlink style.
+        #[cfg(debug_assertions)]
+        crate::parallel::check_registered(fmt.unwrap_or(&Format::new().set_hyperlink()));
         return Ok(());
     }
     write_string_opt(worksheet, row, col, val, fmt)
 }
 
-/// Resolve `url_columns` (column names) to their positions in `headers`.
-/// Unknown names warn and are skipped, matching `column_formats`.
-pub fn resolve_url_columns(
-    url_columns: Option<&Vec<String>>,
+/// Resolve a list of column names such as `url_columns` or `id_columns`,
+/// given as `option`, to a flag per position in `headers`. Unknown names
+/// warn and are skipped, matching `column_formats`.
+pub fn resolve_column_flags(
+    option: &str,
+    names: Option<&Vec<String>>,
     headers: &[String],
     py: Python,
 ) -> PyResult<Vec<bool>> {
     let mut flags = vec![false; headers.len()];
-    let Some(names) = url_columns else {
+    let Some(names) = names else {
         return Ok(flags);
     };
     let warnings = py.import("warnings")?;
@@ -716,7 +1210,7 @@ pub fn resolve_url_columns(
             None => {
                 warnings.call_method1(
                     "warn",
-                    (format!("url_columns: unknown column '{name}', skipped"),),
+                    (format!("{option}: unknown column '{name}', skipped"),),
                 )?;
             }
         }
@@ -733,6 +1227,7 @@ pub fn write_bool_opt(
     fmt: Option<&Format>,
 ) -> PyResult<()> {
     if let Some(fmt) = fmt {
+        crate::parallel::check_registered(fmt);
         worksheet
             .write_boolean_with_format(row, col, val, fmt)
             .map_err(xlsx_err)?;
@@ -752,6 +1247,7 @@ pub fn write_datetime_opt(
     fmt: Option<&Format>,
 ) -> PyResult<()> {
     if let Some(fmt) = fmt {
+        crate::parallel::check_registered(fmt);
         worksheet
             .write_datetime_with_format(row, col, val, fmt)
             .map_err(xlsx_err)?;
@@ -794,13 +1290,17 @@ pub fn write_csv_escaped_guarded(output: &mut Vec<u8>, val: &str, guard: bool) {
 }
 
 /// Save a workbook to a file path or writable buffer.
+///
+/// Assembling and compressing the zip is the slowest step of a large export
+/// and needs no Python, so it runs with the GIL released. Only the buffer's
+/// `.write` call happens with it held again.
 pub fn save_workbook(
     py: Python,
     workbook: &mut Workbook,
     file_or_buffer: Py<PyAny>,
 ) -> PyResult<()> {
     if let Ok(file_name) = file_or_buffer.extract::<String>(py) {
-        workbook.save(&file_name).map_err(|e| {
+        py.detach(|| workbook.save(&file_name)).map_err(|e| {
             PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
                 "Failed to save workbook: {}",
                 e
@@ -809,7 +1309,7 @@ pub fn save_workbook(
         return Ok(());
     }
 
-    let buffer = workbook.save_to_buffer().map_err(|e| {
+    let buffer = py.detach(|| workbook.save_to_buffer()).map_err(|e| {
         // Match the file-save path (PyIOError) so a save failure surfaces as
         // OSError regardless of whether the target is a path or a buffer.
         PyErr::new::<pyo3::exceptions::PyIOError, _>(format!(
@@ -850,7 +1350,7 @@ fn is_valid_width(w: f64) -> bool {
 }
 
 /// Emit a Python `UserWarning` from Rust.
-fn warn_py(py: Python, msg: &str) -> PyResult<()> {
+pub fn warn_py(py: Python, msg: &str) -> PyResult<()> {
     py.import("warnings")?.call_method1("warn", (msg,))?;
     Ok(())
 }
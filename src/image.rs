@@ -0,0 +1,269 @@
+//! Images: floating ones anchored over the sheet (`images`) and in-cell ones
+//! rendered from a column of bytes or file paths (`image_columns`).
+//!
+//! A floating image is part of the sheet's spec, so a bad one raises before
+//! anything is written. An image column is data: one unreadable thumbnail in
+//! a catalog should not abort the export, so it is left blank and reported in
+//! a single warning per column — the same leniency `url_columns` shows text
+//! that is not a link.
+
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef};
+use arrow_schema::DataType;
+use pyo3::prelude::*;
+use pyo3::types::{PyByteArray, PyBytes, PyList, PyString};
+use rust_xlsxwriter::{Format, Image, Worksheet, XlsxError};
+
+use crate::helpers::{value_err, warn_py, write_string_opt, OptionDict};
+use crate::worksheet::xlsx_err;
+
+/// Where an image comes from: raw bytes or a file path.
+enum Source {
+    Bytes(Vec<u8>),
+    Path(String),
+}
+
+impl Source {
+    /// `bytes`/`bytearray`, a `str` path or an `os.PathLike`. `None` for
+    /// anything else — including an empty string, which is simply no image.
+    fn from_py(value: &Bound<'_, PyAny>) -> PyResult<Option<Source>> {
+        if let Ok(b) = value.cast::<PyBytes>() {
+            return Ok(Some(Source::Bytes(b.as_bytes().to_vec())));
+        }
+        if let Ok(b) = value.cast::<PyByteArray>() {
+            return Ok(Some(Source::Bytes(b.to_vec())));
+        }
+        if let Ok(s) = value.cast::<PyString>() {
+            return Ok(Some(Source::Path(s.to_str()?.to_string())));
+        }
+        if value.hasattr("__fspath__")? {
+            let path = value.call_method0("__fspath__")?;
+            return Ok(path.extract::<String>().ok().map(Source::Path));
+        }
+        Ok(None)
+    }
+
+    fn load(&self) -> Result<Image, XlsxError> {
+        match self {
+            Source::Bytes(b) => Image::new_from_buffer(b),
+            Source::Path(p) => Image::new(p),
+        }
+    }
+}
+
+/// One `images` entry, loaded and ready to insert.
+pub struct FloatingImage {
+    row: u32,
+    col: u16,
+    x_offset: u32,
+    y_offset: u32,
+    image: Image,
+}
+
+fn parse_image(spec: &OptionDict) -> PyResult<FloatingImage> {
+    spec.check_keys(&[
+        "anchor", "image", "x_scale", "y_scale", "x_offset", "y_offset", "alt_text",
+    ])?;
+    let (row, col) = spec
+        .cell("anchor")?
+        .ok_or_else(|| value_err(format!("{}: missing required option 'anchor'", spec.label)))?;
+    let source = Source::from_py(&spec.require("image")?)?.ok_or_else(|| {
+        value_err(format!(
+            "{}: 'image' must be bytes or a file path",
+            spec.label
+        ))
+    })?;
+    let mut image = source
+        .load()
+        .map_err(|e| value_err(format!("{}: cannot read image: {e}", spec.label)))?;
+    for key in ["x_scale", "y_scale"] {
+        if let Some(scale) = spec.f64(key)? {
+            if !(scale.is_finite() && scale > 0.0) {
+                return Err(value_err(format!(
+                    "{}: '{key}' must be a positive number",
+                    spec.label
+                )));
+            }
+            image = if key == "x_scale" {
+                image.set_scale_width(scale)
+            } else {
+                image.set_scale_height(scale)
+            };
+        }
+    }
+    if let Some(alt) = spec.str("alt_text")? {
+        image = image.set_alt_text(alt);
+    }
+    Ok(FloatingImage {
+        row,
+        col,
+        x_offset: spec.u32("x_offset")?.unwrap_or(0),
+        y_offset: spec.u32("y_offset")?.unwrap_or(0),
+        image,
+    })
+}
+
+/// Read `images` — a list of `{anchor, image, …}` dicts. Every image is read
+/// here, so a missing file or unsupported format raises up front.
+pub fn resolve_images(spec: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<FloatingImage>> {
+    let Some(spec) = spec else {
+        return Ok(Vec::new());
+    };
+    let list = spec
+        .cast::<PyList>()
+        .map_err(|_| value_err("images must be a list of image dicts".into()))?;
+    list.iter()
+        .enumerate()
+        .map(|(i, item)| parse_image(&OptionDict::new(&item, format!("images[{i}]"))?))
+        .collect()
+}
+
+/// Insert the floating images. They sit over the cells rather than in them,
+/// so constant-memory mode does not care that the rows are already flushed.
+pub fn apply_images(worksheet: &mut Worksheet, images: &[FloatingImage]) -> PyResult<()> {
+    for img in images {
+        worksheet
+            .insert_image_with_offset(img.row, img.col, &img.image, img.x_offset, img.y_offset)
+            .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
+
+/// `image_columns` resolved against the headers, plus a tally of the cells
+/// that could not be rendered so they are reported once per column.
+#[derive(Default)]
+pub struct ImageColumns {
+    flags: Vec<bool>,
+    /// Per column: cells left blank, and the first such sheet row.
+    failed: Vec<(u32, u32)>,
+}
+
+impl ImageColumns {
+    /// Resolve column names to positions. Unknown names warn and are skipped,
+    /// matching `url_columns`.
+    pub fn resolve(
+        image_columns: Option<&Vec<String>>,
+        headers: &[String],
+        py: Python,
+    ) -> PyResult<Self> {
+        let mut flags = vec![false; headers.len()];
+        for name in image_columns.into_iter().flatten() {
+            match headers.iter().position(|h| h == name) {
+                Some(idx) => flags[idx] = true,
+                None => warn_py(
+                    py,
+                    &format!("image_columns: unknown column '{name}', skipped"),
+                )?,
+            }
+        }
+        Ok(ImageColumns {
+            failed: vec![(0, 0); flags.len()],
+            flags,
+        })
+    }
+
+    pub fn is_image(&self, col: usize) -> bool {
+        self.flags.get(col).copied().unwrap_or(false)
+    }
+
+    fn embed(
+        &mut self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        source: Option<Source>,
+        fmt: Option<&Format>,
+    ) -> PyResult<()> {
+        let image = match source {
+            Some(Source::Path(p)) if p.is_empty() => None,
+            Some(source) => match source.load() {
+                Ok(image) => Some(image),
+                Err(_) => {
+                    let (count, first) = &mut self.failed[col as usize];
+                    if *count == 0 {
+                        *first = row;
+                    }
+                    *count += 1;
+                    None
+                }
+            },
+            None => None,
+        };
+        match (image, fmt) {
+            (Some(image), Some(f)) => {
+                crate::parallel::check_registered(f);
+                worksheet
+                    .embed_image_with_format(row, col, &image, f)
+                    .map_err(xlsx_err)?;
+            }
+            (Some(image), None) => {
+                worksheet.embed_image(row, col, &image).map_err(xlsx_err)?;
+            }
+            (None, _) => write_string_opt(worksheet, row, col, "", fmt)?,
+        }
+        Ok(())
+    }
+
+    /// Embed one Python value. Anything that is not bytes or a path counts as
+    /// an unreadable image.
+    pub fn write_py(
+        &mut self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        value: &Bound<'_, PyAny>,
+        fmt: Option<&Format>,
+    ) -> PyResult<()> {
+        if value.is_none() {
+            return write_string_opt(worksheet, row, col, "", fmt);
+        }
+        // An unusable type takes the same path as unreadable bytes.
+        let source = Source::from_py(value)?.or(Some(Source::Bytes(Vec::new())));
+        self.embed(worksheet, row, col, source, fmt)
+    }
+
+    /// Embed one non-null Arrow cell: binary columns hold the image bytes,
+    /// string columns its path.
+    pub fn write_arrow(
+        &mut self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        column: &ArrayRef,
+        idx: usize,
+        fmt: Option<&Format>,
+    ) -> PyResult<()> {
+        let source = match column.data_type() {
+            DataType::Binary => Source::Bytes(column.as_binary::<i32>().value(idx).to_vec()),
+            DataType::LargeBinary => Source::Bytes(column.as_binary::<i64>().value(idx).to_vec()),
+            DataType::BinaryView => Source::Bytes(column.as_binary_view().value(idx).to_vec()),
+            DataType::FixedSizeBinary(_) => {
+                Source::Bytes(column.as_fixed_size_binary().value(idx).to_vec())
+            }
+            DataType::Utf8 => Source::Path(column.as_string::<i32>().value(idx).to_string()),
+            DataType::LargeUtf8 => Source::Path(column.as_string::<i64>().value(idx).to_string()),
+            DataType::Utf8View => Source::Path(column.as_string_view().value(idx).to_string()),
+            _ => Source::Bytes(Vec::new()),
+        };
+        self.embed(worksheet, row, col, Some(source), fmt)
+    }
+
+    /// Warn once per column that had cells left blank.
+    pub fn warn_failures(&self, headers: &[String], py: Python) -> PyResult<()> {
+        for (col, &(count, first)) in self.failed.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let name = headers.get(col).map(String::as_str).unwrap_or("?");
+            warn_py(
+                py,
+                &format!(
+                    "image_columns: {count} value(s) in column '{name}' are not readable \
+images and were left blank (first at row {})",
+                    first + 1
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,29 @@
 mod arrow_ffi;
 mod arrow_writer;
 mod cell;
+mod chart;
+mod columns;
+mod comment;
+mod conditional;
 mod csv_writer;
 mod data_types;
 mod format;
+mod frame_index;
+mod header_groups;
 mod helpers;
+mod image;
+mod missing;
+mod nested;
+mod outline;
+mod overflow;
+mod parallel;
+mod properties;
+mod records;
+mod sentinel;
+mod table;
+mod timezone;
 mod utils;
+mod validation;
 mod worksheet;
 
 use pyo3::prelude::*;
@@ -17,5 +35,7 @@ fn rustpy_xlsxwriter(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(utils::validate_sheet_name, m)?)?;
     m.add_function(wrap_pyfunction!(csv_writer::write_csv, m)?)?;
     m.add_class::<format::Format>()?;
+    m.add_class::<sentinel::ExcelError>()?;
+    m.add_class::<sentinel::Blank>()?;
     Ok(())
 }
@@ -0,0 +1,209 @@
+//! Missing and non-finite values (`na_rep`, `nan_rep`, `inf_rep`).
+//!
+//! Excel has no NaN and no infinity, and an empty cell is only one way to
+//! show a gap. `None`, Arrow nulls and pandas' `NaT` follow `na_rep`; a NaN
+//! follows `nan_rep` and ±infinity `inf_rep`. Each is a blank (the default),
+//! a text, an Excel error value such as `#N/A`, or `"raise"`. The worksheet
+//! and CSV writers on every input path resolve such a value to a [`Special`]
+//! and hand it here, so they all agree.
+
+use std::borrow::Cow;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDateAccess, PyDateTime};
+use rust_xlsxwriter::{Formula, Format, Worksheet};
+
+use crate::helpers::{value_err, write_csv_escaped_guarded, write_string_opt};
+use crate::worksheet::xlsx_err;
+
+/// The error values a cell can hold, as Excel spells them.
+const EXCEL_ERRORS: [&str; 7] = [
+    "#DIV/0!", "#N/A", "#NAME?", "#NULL!", "#NUM!", "#REF!", "#VALUE!",
+];
+
+/// The error value `text` spells, if it spells one.
+pub fn excel_error(text: &str) -> Option<&'static str> {
+    EXCEL_ERRORS.iter().find(|e| **e == text).copied()
+}
+
+/// How one kind of special value is written.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub enum Rep {
+    /// An empty cell, or an empty CSV field.
+    #[default]
+    Blank,
+    /// A text cell. Negative infinity gets a leading `-`.
+    Text(String),
+    /// An Excel error value such as `#N/A`; CSV gets its spelling.
+    Error(&'static str),
+    /// Raise `ValueError` naming the cell.
+    Raise,
+}
+
+impl Rep {
+    fn parse(value: Option<&str>) -> Self {
+        match value {
+            None | Some("") => Rep::Blank,
+            Some("raise") => Rep::Raise,
+            Some(text) => match excel_error(text) {
+                Some(error) => Rep::Error(error),
+                None => Rep::Text(text.to_string()),
+            },
+        }
+    }
+}
+
+/// A sheet's `na_rep`, `nan_rep` and `inf_rep`.
+#[derive(Clone, Default)]
+pub struct Reps {
+    na: Rep,
+    nan: Rep,
+    inf: Rep,
+}
+
+impl Reps {
+    pub fn parse(na_rep: Option<&str>, nan_rep: Option<&str>, inf_rep: Option<&str>) -> Self {
+        Reps {
+            na: Rep::parse(na_rep),
+            nan: Rep::parse(nan_rep),
+            inf: Rep::parse(inf_rep),
+        }
+    }
+
+    fn get(&self, special: Special) -> &Rep {
+        match special {
+            Special::Missing => &self.na,
+            Special::NaN => &self.nan,
+            Special::Inf | Special::NegInf => &self.inf,
+        }
+    }
+
+    /// The text `special` is written as, `None` for a blank. `place` names the
+    /// cell for the `"raise"` error.
+    fn text(
+        &self,
+        special: Special,
+        place: impl FnOnce() -> String,
+    ) -> PyResult<Option<Cow<'_, str>>> {
+        match self.get(special) {
+            Rep::Blank => Ok(None),
+            Rep::Text(text) if special == Special::NegInf => Ok(Some(format!("-{text}").into())),
+            Rep::Text(text) => Ok(Some(text.as_str().into())),
+            Rep::Error(error) => Ok(Some((*error).into())),
+            Rep::Raise => Err(value_err(format!(
+                "{} holds {}, which {}=\"raise\" refuses. Pass a text, an Excel error such as \
+\"#N/A\", or None for a blank",
+                place(),
+                special.what(),
+                special.option()
+            ))),
+        }
+    }
+}
+
+/// A value with no number Excel can hold.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Special {
+    /// `None`, an Arrow null or `NaT`.
+    Missing,
+    NaN,
+    Inf,
+    NegInf,
+}
+
+impl Special {
+    /// The special value `val` is, if it is not finite.
+    pub fn of(val: f64) -> Option<Special> {
+        if val.is_nan() {
+            Some(Special::NaN)
+        } else if val == f64::INFINITY {
+            Some(Special::Inf)
+        } else if val == f64::NEG_INFINITY {
+            Some(Special::NegInf)
+        } else {
+            None
+        }
+    }
+
+    fn what(self) -> &'static str {
+        match self {
+            Special::Missing => "a missing value",
+            Special::NaN => "NaN",
+            Special::Inf => "inf",
+            Special::NegInf => "-inf",
+        }
+    }
+
+    fn option(self) -> &'static str {
+        match self {
+            Special::Missing => "na_rep",
+            Special::NaN => "nan_rep",
+            Special::Inf | Special::NegInf => "inf_rep",
+        }
+    }
+}
+
+/// Write `special` as `reps` says. `fmt` is the format the cell would carry
+/// anyway, so a banded row has no unshaded hole.
+pub fn write_special(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    special: Special,
+    reps: &Reps,
+    fmt: Option<&Format>,
+) -> PyResult<()> {
+    if let Rep::Error(error) = reps.get(special) {
+        return write_error_cell(worksheet, row, col, error, fmt);
+    }
+    let place = || format!("cell {}", rust_xlsxwriter::utility::row_col_to_cell(row, col));
+    let text = reps.text(special, place)?;
+    write_string_opt(worksheet, row, col, text.as_deref().unwrap_or(""), fmt)
+}
+
+/// [`write_special`] for CSV. `row` counts data rows from 1 and `col` fields
+/// from 0.
+pub fn emit_special_csv(
+    output: &mut Vec<u8>,
+    special: Special,
+    reps: &Reps,
+    sanitize: bool,
+    row: usize,
+    col: usize,
+) -> PyResult<()> {
+    let place = || format!("data row {row}, field {}", col + 1);
+    if let Some(text) = reps.text(special, place)? {
+        write_csv_escaped_guarded(output, &text, sanitize);
+    }
+    Ok(())
+}
+
+/// Write an Excel error value such as `#N/A` as a cell. Excel stores one as
+/// a formula whose cached result is the error.
+pub fn write_error_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    error: &str,
+    fmt: Option<&Format>,
+) -> PyResult<()> {
+    let formula = Formula::new(error).set_result(error);
+    match fmt {
+        Some(fmt) => {
+            crate::parallel::check_registered(fmt);
+            worksheet.write_formula_with_format(row, col, formula, fmt)
+        }
+        None => worksheet.write_formula(row, col, formula),
+    }
+    .map_err(xlsx_err)?;
+    Ok(())
+}
+
+/// Whether `dt` is pandas' `NaT`, a `datetime` subclass standing for a missing
+/// value. Its fields read 0001-01-01, so the type name is only looked up then.
+pub fn is_nat(dt: &Bound<'_, PyDateTime>) -> bool {
+    dt.get_year() == 1
+        && dt.get_month() == 1
+        && dt.get_day() == 1
+        && dt.get_type().name().is_ok_and(|name| name == "NaTType")
+}
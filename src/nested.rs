@@ -0,0 +1,251 @@
+//! Nested values — Arrow `List`, `Struct` and `Map` columns, and Python
+//! `list`, `tuple` and `dict` values in records — and the `nested_columns`
+//! policy that decides how they become cells.
+//!
+//! JSON and joined text are rendered per cell: for Arrow in
+//! [`crate::arrow_writer`], for Python values here. `"explode"` instead
+//! reshapes the input before anything sees it, so headers, column formats and
+//! every other column-keyed option simply find the `parent.field` columns.
+
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::{ArrayRef, RecordBatch, RecordBatchOptions, RecordBatchReader};
+use arrow_schema::{ArrowError, DataType, FieldRef, Fields, Schema, SchemaRef};
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+
+use crate::helpers::value_err;
+
+/// Between the items of a list under `nested_columns="join"`.
+pub const JOIN_SEPARATOR: &str = ", ";
+
+/// How nested values are written (`nested_columns`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum NestedColumns {
+    /// A JSON string.
+    #[default]
+    Json,
+    /// List items joined with [`JOIN_SEPARATOR`]; structs and maps as JSON.
+    Join,
+    /// Struct fields — `dict` values in records — as `parent.field` columns;
+    /// lists and maps as JSON.
+    Explode,
+    /// Raise `ValueError`.
+    Error,
+}
+
+impl NestedColumns {
+    pub fn parse(value: Option<&str>) -> PyResult<Self> {
+        match value {
+            None | Some("json") => Ok(NestedColumns::Json),
+            Some("join") => Ok(NestedColumns::Join),
+            Some("explode") => Ok(NestedColumns::Explode),
+            Some("error") => Ok(NestedColumns::Error),
+            Some(other) => Err(value_err(format!(
+                "nested_columns must be 'json', 'join', 'explode' or 'error', got '{other}'"
+            ))),
+        }
+    }
+}
+
+/// The error for a nested value under `nested_columns="error"`; `what` names
+/// it, e.g. `column 'tags' holds List(Utf8) values`.
+pub fn nested_err(what: &str) -> PyErr {
+    value_err(format!(
+        "{what}. Pass nested_columns=\"json\", \"join\" or \"explode\" to write nested values."
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Python values
+// ---------------------------------------------------------------------------
+
+/// Whether a Python value is one `nested_columns` applies to.
+pub fn is_nested(value: &Bound<'_, PyAny>) -> bool {
+    value.is_instance_of::<PyList>()
+        || value.is_instance_of::<PyTuple>()
+        || value.is_instance_of::<PyDict>()
+}
+
+/// The text of a Python `list`, `tuple` or `dict` cell.
+pub fn py_nested_text(value: &Bound<'_, PyAny>, nested: NestedColumns) -> PyResult<String> {
+    match nested {
+        NestedColumns::Error => {
+            let type_name = value.get_type().name()?;
+            Err(nested_err(&format!("found a {type_name} value")))
+        }
+        NestedColumns::Join if !value.is_instance_of::<PyDict>() => {
+            let items = value
+                .try_iter()?
+                .map(|item| py_join_item(&item?))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(items.join(JOIN_SEPARATOR))
+        }
+        _ => py_json(value),
+    }
+}
+
+/// One list item under `"join"`: strings as they are, `None` as nothing, JSON
+/// values as JSON and anything else — dates, say — as `str()` shows it.
+fn py_join_item(item: &Bound<'_, PyAny>) -> PyResult<String> {
+    if item.is_none() {
+        return Ok(String::new());
+    }
+    if let Ok(s) = item.cast::<PyString>() {
+        return Ok(s.to_str()?.to_string());
+    }
+    if is_nested(item)
+        || item.is_instance_of::<PyBool>()
+        || item.is_instance_of::<PyInt>()
+        || item.is_instance_of::<PyFloat>()
+    {
+        return py_json(item);
+    }
+    Ok(item.str()?.to_string())
+}
+
+/// `json.dumps(value, ensure_ascii=False, default=str)`, with the encoder
+/// built once.
+fn py_json(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    static ENCODE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+    let py = value.py();
+    let encode = ENCODE.get_or_try_init(py, || -> PyResult<_> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("ensure_ascii", false)?;
+        kwargs.set_item("default", py.get_type::<PyString>())?;
+        let encoder = py
+            .import("json")?
+            .getattr("JSONEncoder")?
+            .call((), Some(&kwargs))?;
+        Ok(encoder.getattr("encode")?.unbind())
+    })?;
+    encode.bind(py).call1((value,))?.extract()
+}
+
+/// A record with every `dict` value spread into `parent.field` keys,
+/// recursively, for `nested_columns="explode"`.
+pub fn explode_record<'py>(row: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyDict>> {
+    let out = PyDict::new(row.py());
+    explode_into(&out, row, None)?;
+    Ok(out)
+}
+
+fn explode_into(
+    out: &Bound<'_, PyDict>,
+    dict: &Bound<'_, PyDict>,
+    prefix: Option<&str>,
+) -> PyResult<()> {
+    for (key, value) in dict.iter() {
+        let key = match prefix {
+            Some(prefix) => {
+                let name = format!("{prefix}.{}", key.str()?);
+                PyString::new(dict.py(), &name).into_any()
+            }
+            None => key,
+        };
+        match value.cast::<PyDict>() {
+            Ok(inner) => explode_into(out, inner, Some(&key.str()?.to_string()))?,
+            Err(_) => out.set_item(key, value)?,
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Arrow
+// ---------------------------------------------------------------------------
+
+/// `reader` with every struct column replaced by its fields under
+/// `nested_columns="explode"`; otherwise `reader` itself.
+pub fn explode_reader(
+    reader: Box<dyn RecordBatchReader + Send>,
+    nested: NestedColumns,
+) -> Box<dyn RecordBatchReader + Send> {
+    let schema = reader.schema();
+    let has_struct = schema
+        .fields()
+        .iter()
+        .any(|f| matches!(f.data_type(), DataType::Struct(_)));
+    if nested != NestedColumns::Explode || !has_struct {
+        return reader;
+    }
+    let mut fields = Vec::new();
+    explode_fields(schema.fields(), None, false, &mut fields);
+    let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
+    Box::new(Exploded {
+        inner: reader,
+        schema,
+    })
+}
+
+/// `fields` with each struct field replaced by its own fields, named
+/// `parent.field`. A field under a nullable struct is nullable too, since a
+/// null struct nulls every field of that row.
+fn explode_fields(
+    fields: &Fields,
+    prefix: Option<&str>,
+    parent_nullable: bool,
+    out: &mut Vec<FieldRef>,
+) {
+    for field in fields {
+        let name = match prefix {
+            Some(prefix) => format!("{prefix}.{}", field.name()),
+            None => field.name().clone(),
+        };
+        let nullable = parent_nullable || field.is_nullable();
+        match field.data_type() {
+            DataType::Struct(children) => explode_fields(children, Some(&name), nullable, out),
+            _ => out.push(Arc::new(
+                field.as_ref().clone().with_name(name).with_nullable(nullable),
+            )),
+        }
+    }
+}
+
+/// The arrays matching [`explode_fields`].
+fn explode_columns(fields: &Fields, columns: &[ArrayRef], out: &mut Vec<ArrayRef>) {
+    for (field, column) in fields.iter().zip(columns) {
+        match field.data_type() {
+            DataType::Struct(_) => {
+                // `flatten` folds the struct's nulls into each field.
+                let (children, arrays) = column.as_struct().flatten();
+                explode_columns(&children, &arrays, out);
+            }
+            _ => out.push(column.clone()),
+        }
+    }
+}
+
+struct Exploded {
+    inner: Box<dyn RecordBatchReader + Send>,
+    schema: SchemaRef,
+}
+
+impl Iterator for Exploded {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = match self.inner.next()? {
+            Ok(batch) => batch,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut columns = Vec::new();
+        explode_columns(batch.schema().fields(), batch.columns(), &mut columns);
+        // A struct with no fields leaves nothing behind, so the row count has
+        // to be given rather than read off the columns.
+        let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+        Some(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &options,
+        ))
+    }
+}
+
+impl RecordBatchReader for Exploded {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
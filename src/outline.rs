@@ -0,0 +1,272 @@
+//! Outline groups (`row_groups`, `column_groups`, `outline_column`,
+//! `outline_collapsed`): rows and columns Excel folds away under the `+`/`-`
+//! buttons beside the sheet.
+//!
+//! Levels are absolute. Under `row_groups=[(1, 9, 1), (3, 5, 2)]` rows 3 to 5
+//! are on level 2 and the rest of 1 to 9 on level 1; neighbouring rows on the
+//! same level make one group, as they do in Excel. `outline_column` gives each
+//! data row its level instead, from a column that is read but not written.
+//! `outline_collapsed` writes the sheet folded down to its ungrouped rows and
+//! columns.
+//!
+//! `rust_xlsxwriter` raises a row's level one group at a time, and marks a
+//! collapsed group on the row below it, so a row can only be settled once the
+//! next row's level says which groups end on it. [`RowOutline`] settles each
+//! row one row late, which lets `outline_column` levels arrive with the rows.
+//! Its constant-memory writer leaves a row's level and collapsed mark out,
+//! though, so a sheet with grouped rows is kept in memory until save. Column
+//! groups are written apart from the rows and need no such care.
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef};
+use arrow_schema::DataType;
+use pyo3::prelude::*;
+use rust_xlsxwriter::Worksheet;
+
+use crate::helpers::value_err;
+use crate::worksheet::xlsx_err;
+
+/// Excel's deepest outline level.
+const MAX_LEVEL: u8 = 7;
+const LAST_ROW: u32 = 1_048_575;
+const LAST_COL: u32 = 16_383;
+
+/// A sheet's outline options.
+#[derive(Default)]
+pub struct Outline {
+    /// `(first_row, last_row, level)`.
+    row_groups: Vec<(u32, u32, u8)>,
+    /// `(first_col, last_col, level)`, checked to fit a column number.
+    column_groups: Vec<(u32, u32, u8)>,
+    /// The `outline_column`, by its written header.
+    column: Option<String>,
+    collapsed: bool,
+}
+
+impl Outline {
+    pub fn parse(
+        row_groups: Option<&Bound<'_, PyAny>>,
+        column_groups: Option<&Bound<'_, PyAny>>,
+        column: Option<String>,
+        collapsed: bool,
+    ) -> PyResult<Self> {
+        let row_groups = groups(row_groups, "row_groups", "row", LAST_ROW)?;
+        let column_groups = groups(column_groups, "column_groups", "col", LAST_COL)?;
+        if !row_groups.is_empty() && column.is_some() {
+            return Err(value_err(
+                "row_groups and outline_column cannot be combined: both set the row levels; \
+pass one of them"
+                    .to_string(),
+            ));
+        }
+        Ok(Outline {
+            row_groups,
+            column_groups,
+            column,
+            collapsed,
+        })
+    }
+
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Whether any row may be grouped, which needs a buffered sheet.
+    pub fn groups_rows(&self) -> bool {
+        !self.row_groups.is_empty() || self.column.is_some()
+    }
+
+    /// A fresh [`RowOutline`] for this sheet's `outline_column` rows.
+    pub fn rows(&self) -> RowOutline {
+        RowOutline {
+            collapsed: self.collapsed,
+            pending: None,
+        }
+    }
+
+    /// Lay down `row_groups` and `column_groups`. Called with the rest of the
+    /// layout, before the first row is written.
+    pub fn apply(&self, worksheet: &mut Worksheet) -> PyResult<()> {
+        let mut rows = self.rows();
+        for (row, level) in levels(&self.row_groups) {
+            rows.push(worksheet, row, level)?;
+        }
+        rows.finish(worksheet)?;
+        let cols = levels(&self.column_groups);
+        for (i, &(col, level)) in cols.iter().enumerate() {
+            let next = cols.get(i + 1).map_or(0, |&(_, next)| next);
+            let col = col as u16;
+            for step in 1..=level {
+                if self.collapsed && step > next && u32::from(col) < LAST_COL {
+                    worksheet.group_columns_collapsed(col, col)
+                } else {
+                    worksheet.group_columns(col, col)
+                }
+                .map_err(xlsx_err)?;
+            }
+            if self.collapsed && level > 0 {
+                worksheet.set_column_hidden(col).map_err(xlsx_err)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read a list of `(first, last, level)` groups, where `first` and `last` are
+/// rows or columns up to `last_allowed`.
+fn groups(
+    spec: Option<&Bound<'_, PyAny>>,
+    option: &str,
+    unit: &str,
+    last_allowed: u32,
+) -> PyResult<Vec<(u32, u32, u8)>> {
+    let Some(spec) = spec else {
+        return Ok(Vec::new());
+    };
+    let groups: Vec<(u32, u32, i64)> = spec.extract().map_err(|_| {
+        value_err(format!(
+            "{option} must be a list of (first_{unit}, last_{unit}, level) tuples"
+        ))
+    })?;
+    groups
+        .into_iter()
+        .map(|(first, last, level)| {
+            let group = format!("({first}, {last}, {level})");
+            if last < first {
+                return Err(value_err(format!(
+                    "{option}: group {group} is inverted: last_{unit} must not precede first_{unit}"
+                )));
+            }
+            if last > last_allowed {
+                return Err(value_err(format!(
+                    "{option}: group {group} reaches {unit} {last}, past the last one, \
+{last_allowed}"
+                )));
+            }
+            match u8::try_from(level) {
+                Ok(level @ 1..=MAX_LEVEL) => Ok((first, last, level)),
+                _ => Err(value_err(format!(
+                    "{option}: group {group} has level {level}; levels run from 1 to {MAX_LEVEL}"
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Every row or column from the first group's start to the last group's end,
+/// with the deepest level of the groups covering it.
+fn levels(groups: &[(u32, u32, u8)]) -> Vec<(u32, u8)> {
+    let Some(start) = groups.iter().map(|g| g.0).min() else {
+        return Vec::new();
+    };
+    let end = groups.iter().map(|g| g.1).max().unwrap_or(start);
+    let mut out: Vec<(u32, u8)> = (start..=end).map(|i| (i, 0)).collect();
+    for &(first, last, level) in groups {
+        for slot in &mut out[(first - start) as usize..=(last - start) as usize] {
+            slot.1 = slot.1.max(level);
+        }
+    }
+    out
+}
+
+/// Row levels, each row set once the level of the row after it is known.
+pub struct RowOutline {
+    collapsed: bool,
+    /// The last row given and its level, not set yet.
+    pending: Option<(u32, u8)>,
+}
+
+impl RowOutline {
+    /// Give `row` its `level`, and set the row before it. Rows come one after
+    /// another, and before any cell of `row` is written.
+    pub fn push(&mut self, worksheet: &mut Worksheet, row: u32, level: u8) -> PyResult<()> {
+        if let Some((prev, prev_level)) = self.pending.replace((row, level)) {
+            self.set(worksheet, prev, prev_level, level)?;
+        }
+        Ok(())
+    }
+
+    /// Set the last row given, which ends every group still open.
+    pub fn finish(&mut self, worksheet: &mut Worksheet) -> PyResult<()> {
+        if let Some((row, level)) = self.pending.take() {
+            self.set(worksheet, row, level, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Raise `row` to `level`, one group at a time. When collapsed it is
+    /// hidden, and the groups ending on it — those deeper than `next`, the
+    /// level of the row after — are marked on that row.
+    fn set(&self, worksheet: &mut Worksheet, row: u32, level: u8, next: u8) -> PyResult<()> {
+        for step in 1..=level {
+            if self.collapsed && step > next && row < LAST_ROW {
+                worksheet.group_rows_collapsed(row, row)
+            } else {
+                worksheet.group_rows(row, row)
+            }
+            .map_err(xlsx_err)?;
+        }
+        if self.collapsed && level > 0 {
+            worksheet.set_row_hidden(row).map_err(xlsx_err)?;
+        }
+        Ok(())
+    }
+}
+
+/// `level` as an outline level, from 0 to 7, for the `outline_column` cell on
+/// sheet row `row`.
+fn check_level(level: Option<i128>, shown: impl std::fmt::Display, row: u32) -> PyResult<u8> {
+    match level.and_then(|l| u8::try_from(l).ok()) {
+        Some(level @ 0..=MAX_LEVEL) => Ok(level),
+        _ => Err(value_err(format!(
+            "outline_column: row {} has level {shown}; levels are whole numbers from 0 to \
+{MAX_LEVEL}",
+            row + 1
+        ))),
+    }
+}
+
+/// The level in a Python `outline_column` cell on sheet row `row`. `None` is
+/// level 0, ungrouped.
+pub fn level_py(value: &Bound<'_, PyAny>, row: u32) -> PyResult<u8> {
+    if value.is_none() {
+        return Ok(0);
+    }
+    check_level(value.extract::<i64>().ok().map(i128::from), value.repr()?, row)
+}
+
+/// The levels in an Arrow `outline_column` whose first value is on sheet row
+/// `start`. Nulls are level 0.
+pub fn levels_arrow(column: &ArrayRef, start: u32) -> PyResult<Vec<u8>> {
+    macro_rules! levels {
+        ($ty:ty) => {{
+            let values = column.as_primitive::<$ty>();
+            (0..values.len())
+                .map(|i| {
+                    if values.is_null(i) {
+                        return Ok(0);
+                    }
+                    let level = i128::from(values.value(i));
+                    check_level(Some(level), level, start + i as u32)
+                })
+                .collect()
+        }};
+    }
+    match column.data_type() {
+        DataType::Int8 => levels!(Int8Type),
+        DataType::Int16 => levels!(Int16Type),
+        DataType::Int32 => levels!(Int32Type),
+        DataType::Int64 => levels!(Int64Type),
+        DataType::UInt8 => levels!(UInt8Type),
+        DataType::UInt16 => levels!(UInt16Type),
+        DataType::UInt32 => levels!(UInt32Type),
+        DataType::UInt64 => levels!(UInt64Type),
+        DataType::Null => Ok(vec![0; column.len()]),
+        other => Err(value_err(format!(
+            "outline_column: levels must be integers, got a {other} column"
+        ))),
+    }
+}
@@ -0,0 +1,129 @@
+//! Sources longer than a sheet (`overflow`).
+//!
+//! Excel stops at 1,048,576 rows. Rather than failing on the first row past
+//! the end with a bare "Excel write error", each writer counts data rows
+//! against [`RowLimit::capacity`] and, when a row does not fit, either raises
+//! a clear `ValueError` (`"error"`, the default), drops the rest with a
+//! warning (`"truncate"`), or stops and hands back the unwritten remainder
+//! (`"split"`). The caller then opens a continuation sheet — `Data (2)`,
+//! `Data (3)`, … — and writes the [`Rest`] to it exactly as it wrote the
+//! first, so header, column formats, freeze panes and autofilter repeat.
+//! Nothing is buffered: the remainder is the live iterator or Arrow reader.
+
+use std::collections::HashSet;
+
+use arrow_array::{RecordBatch, RecordBatchReader};
+use pyo3::prelude::*;
+use pyo3::types::PyIterator;
+
+use crate::helpers::{value_err, warn_py, SheetLayout};
+
+/// Rows in an Excel sheet.
+const ROW_LIMIT: u32 = 1_048_576;
+
+/// Excel's sheet-name length limit.
+const NAME_LIMIT: usize = 31;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    #[default]
+    Error,
+    Split,
+    Truncate,
+}
+
+impl Overflow {
+    pub fn parse(value: Option<&str>) -> PyResult<Self> {
+        match value {
+            None | Some("error") => Ok(Overflow::Error),
+            Some("split") => Ok(Overflow::Split),
+            Some("truncate") => Ok(Overflow::Truncate),
+            Some(other) => Err(value_err(format!(
+                "overflow must be 'error', 'split' or 'truncate', got '{other}'"
+            ))),
+        }
+    }
+}
+
+/// How many data rows one sheet takes, and what to do with the rest.
+pub struct RowLimit {
+    pub mode: Overflow,
+    sheet: String,
+}
+
+impl RowLimit {
+    pub fn new(mode: Overflow, layout: &SheetLayout, sheet: &str) -> Self {
+        RowLimit {
+            // With no room below the header a split would never progress.
+            mode: if Self::capacity(layout) == 0 { Overflow::Error } else { mode },
+            sheet: sheet.to_string(),
+        }
+    }
+
+    /// Data rows that fit below the header, leaving room for a totals row.
+    /// Read once the headers are placed: `header_groups` can move the header
+    /// row down.
+    pub fn capacity(layout: &SheetLayout) -> u32 {
+        ROW_LIMIT.saturating_sub(layout.first_data_row() + u32::from(layout.has_totals()))
+    }
+
+    /// The `"error"`-mode failure, raised as soon as a row is known not to
+    /// fit — before any of it is written where the length is known up front.
+    pub fn too_many_rows(&self, layout: &SheetLayout) -> PyErr {
+        let capacity = Self::capacity(layout);
+        value_err(format!(
+            "sheet '{}' has more data rows than fit: Excel allows {ROW_LIMIT} rows per \
+sheet, leaving room for {} here. Pass overflow=\"split\" to continue on new sheets, or \
+overflow=\"truncate\" to keep the first {}",
+            self.sheet, capacity, capacity
+        ))
+    }
+
+    /// The `"truncate"`-mode warning once the dropped rows are counted.
+    pub fn warn_truncated(&self, layout: &SheetLayout, dropped: u64, py: Python) -> PyResult<()> {
+        warn_py(
+            py,
+            &format!(
+                "overflow='truncate': sheet '{}' keeps the first {} data rows; {dropped} \
+more row(s) were dropped",
+                self.sheet,
+                Self::capacity(layout)
+            ),
+        )
+    }
+}
+
+/// The unwritten remainder of a source once a sheet is full.
+pub enum Rest<'py> {
+    /// The records iterator, the row that did not fit, and the headers the
+    /// next sheet keeps.
+    Records {
+        rows: Bound<'py, PyIterator>,
+        next: Bound<'py, PyAny>,
+        headers: Vec<String>,
+    },
+    /// The Arrow reader, the part of the current batch that did not fit, and
+    /// how many rows the sheets before took.
+    Arrow {
+        reader: Box<dyn RecordBatchReader + Send>,
+        next: RecordBatch,
+        done: usize,
+    },
+    /// Index of the first unwritten row of a DataFrame.
+    Frame { start: usize },
+}
+
+/// Name for part `part` (2, 3, …) of `base`: `"Data (2)"`, shortening `base`
+/// to stay within 31 characters and skipping any name in `taken` (lower-cased,
+/// as Excel compares sheet names case-insensitively).
+pub fn continuation_name(base: &str, mut part: u32, taken: &HashSet<String>) -> String {
+    loop {
+        let suffix = format!(" ({part})");
+        let keep = NAME_LIMIT.saturating_sub(suffix.chars().count());
+        let name: String = base.chars().take(keep).collect::<String>() + &suffix;
+        if !taken.contains(&name.to_lowercase()) {
+            return name;
+        }
+        part += 1;
+    }
+}
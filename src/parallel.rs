@@ -0,0 +1,108 @@
+//! Arrow sheets written side by side (`parallel=True`).
+//!
+//! `write_worksheets` normally builds one sheet after another. With
+//! `parallel=True` each Arrow-backed sheet is still set up in sheet order with
+//! the GIL held — header row, column settings, the formats it will use
+//! registered with the workbook — and its stream read into `RecordBatch`es.
+//! The batch loops, which touch no Python object, then run here on a pool of
+//! threads with the GIL released, and the sheets are finished and added to the
+//! workbook in the order given. Registering the formats up front is what keeps
+//! the output identical to the serial path: a constant-memory sheet numbers
+//! formats workbook-wide on first use, which threads would otherwise race for.
+//!
+//! That list is kept by hand next to the batch writer, so debug builds check
+//! every cell format an Arrow sheet writes against it: see [`Registered`].
+
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use rust_xlsxwriter::Format;
+
+use crate::helpers::value_err;
+
+/// Threads to use for `jobs` sheets: `max_threads`, or one per core, but never
+/// more than there are sheets.
+pub fn thread_count(max_threads: Option<usize>, jobs: usize) -> PyResult<usize> {
+    let limit = match max_threads {
+        Some(0) => return Err(value_err("max_threads must be at least 1".to_string())),
+        Some(n) => n,
+        None => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    };
+    Ok(limit.min(jobs).max(1))
+}
+
+/// Run `work` over every task on `threads` scoped threads. Each thread takes
+/// the next task as it frees up, so one long sheet does not hold back the rest.
+pub fn run_all<T: Send>(tasks: Vec<T>, threads: usize, work: impl Fn(T) + Sync) {
+    let queue = Mutex::new(tasks.into_iter());
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let Some(task) = queue.lock().expect("task queue poisoned").next() else {
+                    break;
+                };
+                work(task);
+            });
+        }
+    });
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static REGISTERED: RefCell<Option<HashSet<Format>>> = const { RefCell::new(None) };
+}
+
+/// The formats a constant-memory Arrow sheet registered before its first
+/// batch. In debug builds, [`Registered::during`] makes every format written
+/// on this thread meanwhile assert that it is one of them; a format missing
+/// from the list would be numbered on first use and break `parallel=True`'s
+/// byte parity. Release builds keep nothing and check nothing.
+#[derive(Default)]
+pub struct Registered {
+    #[cfg(debug_assertions)]
+    formats: Option<HashSet<Format>>,
+}
+
+impl Registered {
+    pub fn new(formats: &[Format]) -> Self {
+        #[cfg(not(debug_assertions))]
+        let _ = formats;
+        Registered {
+            #[cfg(debug_assertions)]
+            formats: Some(formats.iter().cloned().collect()),
+        }
+    }
+
+    /// Run `work` with the check on. A `Registered::default()`, for a sheet
+    /// that numbers its formats at save, leaves it off.
+    pub fn during<R>(&self, work: impl FnOnce() -> R) -> R {
+        #[cfg(debug_assertions)]
+        let outer = REGISTERED.with(|r| r.replace(self.formats.clone()));
+        let result = work();
+        #[cfg(debug_assertions)]
+        REGISTERED.with(|r| r.replace(outer));
+        result
+    }
+}
+
+/// Assert, in debug builds, that `fmt` was registered up front if the sheet
+/// being written on this thread registers its formats.
+#[inline]
+pub fn check_registered(fmt: &Format) {
+    #[cfg(debug_assertions)]
+    REGISTERED.with(|r| {
+        if let Some(formats) = r.borrow().as_ref() {
+            debug_assert!(
+                formats.contains(fmt),
+                "cell format {fmt:?} is missing from reachable_formats"
+            );
+        }
+    });
+    #[cfg(not(debug_assertions))]
+    let _ = fmt;
+}
@@ -0,0 +1,181 @@
+//! Workbook document properties (`properties`): the File > Info metadata —
+//! title, author, company and so on — plus typed custom properties.
+//!
+//! `rust_xlsxwriter` stamps every workbook with the current time as its
+//! creation date, so two otherwise identical exports differ byte for byte.
+//! A fixed `created` date removes that difference, and so does
+//! `reproducible=True` or `SOURCE_DATE_EPOCH`. That timestamp is the only
+//! time-varying part of the file: zip entries already carry a fixed date.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDate, PyDateTime, PyDict, PyFloat, PyInt, PyString};
+use rust_xlsxwriter::{DocProperties, ExcelDateTime};
+
+use crate::helpers::{value_err, OptionDict};
+use crate::worksheet::xlsx_err;
+
+const TEXT_FIELDS: [&str; 10] = [
+    "title",
+    "subject",
+    "author",
+    "manager",
+    "company",
+    "category",
+    "keywords",
+    "comment",
+    "status",
+    "hyperlink_base",
+];
+
+/// A `datetime` or `date` as a UTC `ExcelDateTime`. Aware datetimes are
+/// converted to UTC; naive ones are taken to be UTC already.
+fn utc_datetime(value: &Bound<'_, PyAny>, what: &str) -> PyResult<Option<ExcelDateTime>> {
+    let invalid = |e: rust_xlsxwriter::XlsxError| value_err(format!("{what}: {e}"));
+    if let Ok(dt) = value.cast::<PyDateTime>() {
+        let dt = if dt.getattr("tzinfo")?.is_none() {
+            dt.clone()
+        } else {
+            let utc = value
+                .py()
+                .import("datetime")?
+                .getattr("timezone")?
+                .getattr("utc")?;
+            dt.call_method1("astimezone", (utc,))?
+                .cast_into::<PyDateTime>()?
+        };
+        let (year, month, day): (u16, u8, u8) = (
+            dt.getattr("year")?.extract()?,
+            dt.getattr("month")?.extract()?,
+            dt.getattr("day")?.extract()?,
+        );
+        let (hour, minute, second): (u16, u8, u8) = (
+            dt.getattr("hour")?.extract()?,
+            dt.getattr("minute")?.extract()?,
+            dt.getattr("second")?.extract()?,
+        );
+        return ExcelDateTime::from_ymd(year, month, day)
+            .and_then(|d| d.and_hms(hour, minute, second))
+            .map(Some)
+            .map_err(invalid);
+    }
+    if let Ok(d) = value.cast::<PyDate>() {
+        return ExcelDateTime::from_ymd(
+            d.getattr("year")?.extract()?,
+            d.getattr("month")?.extract()?,
+            d.getattr("day")?.extract()?,
+        )
+        .map(Some)
+        .map_err(invalid);
+    }
+    Ok(None)
+}
+
+fn custom_property(
+    props: DocProperties,
+    name: &str,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<DocProperties> {
+    let label = format!("properties['custom']['{name}']");
+    // `bool` before `int` (it is a subclass) and `datetime` before `date`.
+    if value.is_instance_of::<PyBool>() {
+        return Ok(props.set_custom_property(name, value.extract::<bool>()?));
+    }
+    if value.is_instance_of::<PyInt>() {
+        // Excel stores whole numbers as 32-bit; anything wider as a real.
+        return Ok(match value.extract::<i32>() {
+            Ok(n) => props.set_custom_property(name, n),
+            Err(_) => props.set_custom_property(name, value.extract::<f64>()?),
+        });
+    }
+    if value.is_instance_of::<PyFloat>() {
+        return Ok(props.set_custom_property(name, value.extract::<f64>()?));
+    }
+    if let Ok(s) = value.cast::<PyString>() {
+        return Ok(props.set_custom_property(name, s.to_str()?));
+    }
+    if let Some(dt) = utc_datetime(value, &label)? {
+        return Ok(props.set_custom_property(name, &dt));
+    }
+    Err(value_err(format!(
+        "{label}: must be a str, int, float, bool, date or datetime"
+    )))
+}
+
+/// The creation date to use when `created` is not given: `SOURCE_DATE_EPOCH`
+/// whenever it is set, following the reproducible-builds convention, else
+/// 1980-01-01 — the date on the zip entries — under `reproducible=True`.
+fn pinned_creation(reproducible: bool) -> PyResult<Option<ExcelDateTime>> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(raw) if !raw.trim().is_empty() => {
+            let bad = || {
+                value_err(format!(
+                    "SOURCE_DATE_EPOCH must be a Unix timestamp between 1900 and 9999, got '{raw}'"
+                ))
+            };
+            let secs: i64 = raw.trim().parse().map_err(|_| bad())?;
+            ExcelDateTime::from_timestamp(secs)
+                .map(Some)
+                .map_err(|_| bad())
+        }
+        _ if reproducible => ExcelDateTime::from_ymd(1980, 1, 1)
+            .map(Some)
+            .map_err(xlsx_err),
+        _ => Ok(None),
+    }
+}
+
+/// Read `properties` into a `DocProperties`, or `None` when there is nothing
+/// to set. Every problem raises `ValueError` before anything is written.
+pub fn resolve_properties(
+    spec: Option<&Bound<'_, PyAny>>,
+    reproducible: bool,
+) -> PyResult<Option<DocProperties>> {
+    let pinned = pinned_creation(reproducible)?;
+    let Some(spec) = spec else {
+        return Ok(pinned.map(|created| DocProperties::new().set_creation_datetime(&created)));
+    };
+    let spec = OptionDict::new(spec, "properties".into())?;
+    let mut allowed = TEXT_FIELDS.to_vec();
+    allowed.extend(["created", "custom"]);
+    spec.check_keys(&allowed)?;
+
+    let mut props = DocProperties::new();
+    for field in TEXT_FIELDS {
+        let Some(text) = spec.str(field)? else {
+            continue;
+        };
+        props = match field {
+            "title" => props.set_title(text),
+            "subject" => props.set_subject(text),
+            "author" => props.set_author(text),
+            "manager" => props.set_manager(text),
+            "company" => props.set_company(text),
+            "category" => props.set_category(text),
+            "keywords" => props.set_keywords(text),
+            "comment" => props.set_comment(text),
+            "status" => props.set_status(text),
+            _ => props.set_hyperlink_base(text),
+        };
+    }
+    if let Some(created) = spec.get("created")? {
+        let created = utc_datetime(&created, "properties: 'created'")?
+            .ok_or_else(|| value_err("properties: 'created' must be a date or datetime".into()))?;
+        props = props.set_creation_datetime(&created);
+    } else if let Some(created) = pinned {
+        props = props.set_creation_datetime(&created);
+    }
+    if let Some(custom) = spec.get("custom")? {
+        let custom = custom.cast::<PyDict>().map_err(|_| {
+            value_err("properties: 'custom' must be a dict of {name: value}".into())
+        })?;
+        for (name, value) in custom.iter() {
+            let name: String = name.extract().map_err(|_| {
+                value_err(format!(
+                    "properties['custom']: name {name} must be a string"
+                ))
+            })?;
+            props = custom_property(props, &name, &value)?;
+        }
+    }
+    Ok(Some(props))
+}
@@ -0,0 +1,239 @@
+//! Lining record dicts up with the header row (`missing`, `extra_keys`,
+//! `scan_rows`).
+//!
+//! The headers come from `columns` (see [`crate::columns`]) if given, else
+//! from the first record's
+//! keys; under `extra_keys="append"` keys first seen in the next few records
+//! are added too, read ahead before the header row is written. Each record
+//! is then matched to the headers by key, not by position, so a record with
+//! its keys in another order, or with one missing, still lands in the right
+//! columns.
+
+use std::collections::{HashMap, HashSet};
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyIterator, PyList, PyString, PyTuple};
+
+use crate::helpers::value_err;
+use crate::nested::NestedColumns;
+
+/// Records read ahead for `extra_keys="append"` when the input is not a list
+/// or tuple and `scan_rows` is not given.
+const DEFAULT_SCAN_ROWS: usize = 100;
+
+/// What to do when a record lacks one of the columns (`missing`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingKeys {
+    /// Write it as `None` would be — blank unless `na_rep` says otherwise.
+    #[default]
+    Blank,
+    /// Raise `ValueError` naming the row and the column.
+    Error,
+}
+
+/// What to do with a key that is not one of the columns (`extra_keys`).
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtraKeys {
+    /// Leave the value out.
+    #[default]
+    Ignore,
+    /// Raise `ValueError` naming the row and the key.
+    Error,
+    /// Add the key as a column, if it is seen while scanning for headers.
+    Append,
+}
+
+/// A sheet's `missing`, `extra_keys` and `scan_rows`.
+#[derive(Clone, Default)]
+pub struct RecordKeys {
+    missing: MissingKeys,
+    extra: ExtraKeys,
+    scan_rows: Option<usize>,
+}
+
+impl RecordKeys {
+    pub fn parse(
+        missing: Option<&str>,
+        extra_keys: Option<&str>,
+        scan_rows: Option<usize>,
+    ) -> PyResult<Self> {
+        let missing = match missing {
+            None | Some("blank") => MissingKeys::Blank,
+            Some("error") => MissingKeys::Error,
+            Some(other) => {
+                return Err(value_err(format!(
+                    "missing must be 'blank' or 'error', got '{other}'"
+                )))
+            }
+        };
+        let extra = match extra_keys {
+            None | Some("ignore") => ExtraKeys::Ignore,
+            Some("error") => ExtraKeys::Error,
+            Some("append") => ExtraKeys::Append,
+            Some(other) => {
+                return Err(value_err(format!(
+                    "extra_keys must be 'ignore', 'error' or 'append', got '{other}'"
+                )))
+            }
+        };
+        if scan_rows == Some(0) {
+            return Err(value_err("scan_rows must be at least 1".to_string()));
+        }
+        Ok(RecordKeys {
+            missing,
+            extra,
+            scan_rows,
+        })
+    }
+
+    /// How many records to read ahead for headers: one unless new keys are
+    /// appended, then `scan_rows`, all of a list or tuple, or 100.
+    fn scan_limit(&self, records: &Bound<'_, PyAny>) -> usize {
+        match (self.extra, self.scan_rows) {
+            (ExtraKeys::Append, Some(n)) => n,
+            (ExtraKeys::Append, None)
+                if records.is_instance_of::<PyList>() || records.is_instance_of::<PyTuple>() =>
+            {
+                usize::MAX
+            }
+            (ExtraKeys::Append, None) => DEFAULT_SCAN_ROWS,
+            _ => 1,
+        }
+    }
+
+    /// The source headers for `records`, whose rows `rows` yields, and the
+    /// rows again with those read ahead put back in front. `columns` are the
+    /// headers, if the sheet names them.
+    pub fn headers<'py>(
+        &self,
+        columns: Option<&[String]>,
+        records: &Bound<'py, PyAny>,
+        rows: Bound<'py, PyIterator>,
+        nested: NestedColumns,
+    ) -> PyResult<(Vec<String>, Bound<'py, PyIterator>)> {
+        let py = records.py();
+        let mut headers = columns.map(<[String]>::to_vec).unwrap_or_default();
+        if columns.is_some() && self.extra != ExtraKeys::Append {
+            return Ok((headers, rows));
+        }
+        let limit = self.scan_limit(records);
+        let mut seen: HashSet<String> = headers.iter().cloned().collect();
+        let mut read = Vec::new();
+        for row in rows.clone() {
+            let row = row?;
+            if read.is_empty() || self.extra == ExtraKeys::Append {
+                for key in as_record(&row, nested)?.keys().iter() {
+                    let key = key.extract::<String>()?;
+                    if seen.insert(key.clone()) {
+                        headers.push(key);
+                    }
+                }
+            }
+            read.push(row);
+            if read.len() == limit {
+                break;
+            }
+        }
+        if read.is_empty() {
+            return Ok((headers, rows));
+        }
+        let chain = py.import("itertools")?.getattr("chain")?;
+        let rows = chain.call1((PyList::new(py, read)?, rows))?.try_iter()?;
+        Ok((headers, rows))
+    }
+}
+
+/// `row` as a record: a dict, spread out under `nested_columns="explode"`.
+pub fn as_record<'py>(
+    row: &Bound<'py, PyAny>,
+    nested: NestedColumns,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = row.cast::<PyDict>().map_err(|_| {
+        PyErr::new::<pyo3::exceptions::PyTypeError, _>("Items in records must be dictionaries")
+    })?;
+    if nested == NestedColumns::Explode {
+        crate::nested::explode_record(dict)
+    } else {
+        Ok(dict.clone())
+    }
+}
+
+/// The headers of one sheet, to look record keys up in.
+pub struct KeyMap {
+    headers: Vec<String>,
+    index: HashMap<String, usize>,
+    missing: MissingKeys,
+    extra: ExtraKeys,
+    scanned: Option<usize>,
+}
+
+impl KeyMap {
+    pub fn new(keys: &RecordKeys, headers: &[String]) -> Self {
+        KeyMap {
+            headers: headers.to_vec(),
+            index: headers
+                .iter()
+                .enumerate()
+                .map(|(i, h)| (h.clone(), i))
+                .collect(),
+            missing: keys.missing,
+            extra: keys.extra,
+            scanned: keys.scan_rows,
+        }
+    }
+
+    /// Fill `slots` with `row`'s values, one per header, `None` where `row`
+    /// has no such key. `place` names the row for an error.
+    pub fn align<'py>(
+        &self,
+        row: &Bound<'py, PyDict>,
+        slots: &mut Vec<Option<Bound<'py, PyAny>>>,
+        place: impl Fn() -> String,
+    ) -> PyResult<()> {
+        slots.clear();
+        slots.resize(self.headers.len(), None);
+        for (pos, (key, value)) in row.iter().enumerate() {
+            let key = key.cast_into::<PyString>().map_err(|_| {
+                PyErr::new::<pyo3::exceptions::PyTypeError, _>("Record keys must be strings")
+            })?;
+            let key = key.to_str()?;
+            // Records mostly share one key order, so try the same position
+            // before hashing.
+            let col = match self.headers.get(pos) {
+                Some(header) if header == key => Some(pos),
+                _ => self.index.get(key).copied(),
+            };
+            match col {
+                Some(col) => slots[col] = Some(value),
+                None => self.extra_key(key, &place)?,
+            }
+        }
+        if self.missing == MissingKeys::Error {
+            if let Some(col) = slots.iter().position(Option::is_none) {
+                return Err(value_err(format!(
+                    "{}: no value for column '{}'. Pass missing=\"blank\" to leave it empty",
+                    place(),
+                    self.headers[col]
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn extra_key(&self, key: &str, place: impl Fn() -> String) -> PyResult<()> {
+        match self.extra {
+            ExtraKeys::Ignore => Ok(()),
+            ExtraKeys::Error => Err(value_err(format!(
+                "{}: key '{key}' is not a column. Pass extra_keys=\"ignore\" to drop it, or \
+\"append\" to add it as a column",
+                place()
+            ))),
+            ExtraKeys::Append => Err(value_err(format!(
+                "{}: key '{key}' is not a column, and extra_keys=\"append\" only adds keys seen \
+in the first {} records. Raise scan_rows, or name it in columns",
+                place(),
+                self.scanned.unwrap_or(DEFAULT_SCAN_ROWS)
+            ))),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Cell values with no Python counterpart: Excel error values and blanks.
+//!
+//! `ExcelError.NA` and friends are written as real error cells, so lookups
+//! and `ISNA()` treat them as Excel would; `Blank(format)` is an empty cell
+//! that still carries a format, which `None` (subject to `na_rep`) is not.
+//! The type cascade in [`crate::cell`] recognises both and hands them to
+//! [`crate::cell::CellWriter::write_sentinel`].
+
+use pyo3::prelude::*;
+
+use crate::format::Format;
+
+/// An Excel error value.
+#[pyclass(eq, eq_int, frozen, from_py_object)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ExcelError {
+    NA,
+    DIV0,
+    NAME,
+    NULL,
+    NUM,
+    REF,
+    VALUE,
+}
+
+impl ExcelError {
+    /// The error as Excel spells it, e.g. `#N/A`.
+    pub fn spelling(self) -> &'static str {
+        match self {
+            ExcelError::NA => "#N/A",
+            ExcelError::DIV0 => "#DIV/0!",
+            ExcelError::NAME => "#NAME?",
+            ExcelError::NULL => "#NULL!",
+            ExcelError::NUM => "#NUM!",
+            ExcelError::REF => "#REF!",
+            ExcelError::VALUE => "#VALUE!",
+        }
+    }
+}
+
+#[pymethods]
+impl ExcelError {
+    fn __str__(&self) -> &'static str {
+        self.spelling()
+    }
+}
+
+/// An empty cell, formatted with `format` if one is given.
+#[pyclass(frozen)]
+pub struct Blank {
+    pub format: Option<rust_xlsxwriter::Format>,
+}
+
+#[pymethods]
+impl Blank {
+    #[new]
+    #[pyo3(signature = (format = None))]
+    fn new(format: Option<PyRef<'_, Format>>) -> Self {
+        Blank {
+            format: format.map(|f| f.inner.clone()),
+        }
+    }
+
+    fn __repr__(&self) -> &'static str {
+        match self.format {
+            Some(_) => "Blank(Format)",
+            None => "Blank()",
+        }
+    }
+}
+
+/// A sentinel value, as handed to a [`crate::cell::CellWriter`].
+pub enum Sentinel<'a> {
+    /// An error value, spelled as Excel spells it.
+    Error(&'static str),
+    /// A blank cell, with its own format if it has one.
+    Blank(Option<&'a rust_xlsxwriter::Format>),
+}
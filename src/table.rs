@@ -0,0 +1,382 @@
+//! Native Excel tables (`ListObject`) over the header and data rows.
+//!
+//! `autofilter`, `banded_rows` and `totals_row` emulate a table cell by cell;
+//! `table` emits the real thing, so Excel offers slicers, structured
+//! references and the totals dropdown. The table range needs the final row
+//! count, and `add_table` rewrites the header (and any calculated column) in
+//! place, which constant-memory mode cannot do once those rows are flushed —
+//! so a sheet with a table is buffered in memory, like one with
+//! `dedupe_strings`.
+
+use pyo3::prelude::*;
+use pyo3::types::PyBool;
+use rust_xlsxwriter::{Format, Formula, Table, TableColumn, TableFunction, TableStyle, Worksheet};
+
+use crate::helpers::{value_err, warn_py, FormulaColumn, OptionDict, SheetLayout, TotalsCell};
+use crate::worksheet::xlsx_err;
+
+/// A resolved `table` option: the name (also used to qualify `[@Column]`
+/// references) and a `Table` carrying the style flags, still without columns.
+pub struct TableSpec {
+    pub name: String,
+    table: Table,
+}
+
+#[rustfmt::skip]
+const STYLES: [TableStyle; 61] = {
+    use TableStyle::*;
+    [
+        None,
+        Light1, Light2, Light3, Light4, Light5, Light6, Light7, Light8, Light9, Light10,
+        Light11, Light12, Light13, Light14, Light15, Light16, Light17, Light18, Light19,
+        Light20, Light21,
+        Medium1, Medium2, Medium3, Medium4, Medium5, Medium6, Medium7, Medium8, Medium9,
+        Medium10, Medium11, Medium12, Medium13, Medium14, Medium15, Medium16, Medium17,
+        Medium18, Medium19, Medium20, Medium21, Medium22, Medium23, Medium24, Medium25,
+        Medium26, Medium27, Medium28,
+        Dark1, Dark2, Dark3, Dark4, Dark5, Dark6, Dark7, Dark8, Dark9, Dark10, Dark11,
+    ]
+};
+
+/// Accept the name as Excel shows it ("Table Style Medium 9"), as stored in
+/// the file ("TableStyleMedium9"), or just "medium9".
+fn parse_style(name: &str, label: &str) -> PyResult<TableStyle> {
+    let key = |s: &str| {
+        let s: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        s.strip_prefix("tablestyle")
+            .map(str::to_string)
+            .unwrap_or(s)
+    };
+    let wanted = key(name);
+    STYLES
+        .iter()
+        .find(|style| key(&style.to_string()) == wanted)
+        .copied()
+        .ok_or_else(|| {
+            value_err(format!(
+                "{label}: unknown style '{name}' (valid: \"Table Style Light 1\"-\"21\", \
+\"Table Style Medium 1\"-\"28\", \"Table Style Dark 1\"-\"11\", or \"None\")"
+            ))
+        })
+}
+
+/// Excel's rules for a table name: a letter, `_` or `\` first, then letters,
+/// digits, `_`, `.` or `\`; and nothing that reads as a cell reference.
+fn name_problem(name: &str) -> Option<&'static str> {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return Some("it is empty");
+    };
+    if name.chars().count() > 255 {
+        return Some("it is longer than 255 characters");
+    }
+    if !(first.is_alphabetic() || first == '_' || first == '\\') {
+        return Some("it must start with a letter, '_' or '\\'");
+    }
+    if !chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '\\')) {
+        return Some("only letters, digits, '_', '.' and '\\' are allowed");
+    }
+    let upper = name.to_ascii_uppercase();
+    let letters = upper.trim_end_matches(|c: char| c.is_ascii_digit());
+    let a1 = letters.len() <= 3
+        && letters.len() < upper.len()
+        && letters.chars().all(|c| c.is_ascii_uppercase());
+    let r1c1 = upper
+        .strip_prefix('R')
+        .map(|rest| rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+        .and_then(|rest| rest.strip_prefix('C'))
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()));
+    if a1 || r1c1 || upper == "R" || upper == "C" {
+        return Some("it reads as a cell reference");
+    }
+    None
+}
+
+/// Read `table` — `True` for a default table, or a dict of options. `False`
+/// and `None` mean no table. The table replaces the hand-built filter and
+/// banding, so asking for both raises rather than stacking two of each.
+pub fn resolve_table(
+    spec: Option<&Bound<'_, PyAny>>,
+    default_name: &str,
+    layout: &SheetLayout,
+) -> PyResult<Option<TableSpec>> {
+    let Some(spec) = spec.filter(|s| !s.is_none()) else {
+        return Ok(None);
+    };
+    let options = if let Ok(b) = spec.cast::<PyBool>() {
+        if !b.is_true() {
+            return Ok(None);
+        }
+        None
+    } else {
+        let options = OptionDict::new(spec, "table".to_string())
+            .map_err(|_| value_err("table must be True or a dict of table options".into()))?;
+        options.check_keys(&[
+            "name",
+            "style",
+            "banded_rows",
+            "banded_columns",
+            "first_column",
+            "last_column",
+            "autofilter",
+        ])?;
+        Some(options)
+    };
+
+    if layout.autofilter {
+        return Err(value_err(
+            "autofilter and table both add filter buttons to the header row; drop autofilter \
+(a table has them by default, table={'autofilter': False} hides them)"
+                .into(),
+        ));
+    }
+    if layout.band_color.is_some() {
+        return Err(value_err(
+            "banded_rows and table both shade alternate rows; drop banded_rows and pick a \
+table style instead"
+                .into(),
+        ));
+    }
+    if layout.header_groups.is_some() {
+        return Err(value_err(
+            "header_groups and table cannot be combined: a table's header cells must hold its \
+full column names, which must be unique; drop one of them"
+                .into(),
+        ));
+    }
+
+    let mut table = Table::new();
+    let mut name = default_name.to_string();
+    if let Some(options) = options {
+        if let Some(given) = options.str("name")? {
+            if let Some(problem) = name_problem(&given) {
+                return Err(value_err(format!(
+                    "table: invalid name '{given}': {problem}"
+                )));
+            }
+            name = given;
+        }
+        if let Some(style) = options.str("style")? {
+            table = table.set_style(parse_style(&style, &options.label)?);
+        }
+        if let Some(on) = options.bool("banded_rows")? {
+            table = table.set_banded_rows(on);
+        }
+        if let Some(on) = options.bool("banded_columns")? {
+            table = table.set_banded_columns(on);
+        }
+        if let Some(on) = options.bool("first_column")? {
+            table = table.set_first_column(on);
+        }
+        if let Some(on) = options.bool("last_column")? {
+            table = table.set_last_column(on);
+        }
+        if let Some(on) = options.bool("autofilter")? {
+            table = table.set_autofilter(on);
+        }
+    }
+    Ok(Some(TableSpec { name, table }))
+}
+
+/// Rewrite Excel's `[@Column]` shorthand into the form stored in the file,
+/// `Name[[#This Row],[Column]]`. `[@[A]:[B]]` and an already qualified
+/// `Other[@Column]` are handled too; text inside string literals is left
+/// alone.
+fn expand_this_row(template: &str, table: &str) -> String {
+    let chars: Vec<char> = template.chars().collect();
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut in_string = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            in_string = !in_string;
+        }
+        if in_string || c != '[' || chars.get(i + 1) != Some(&'@') {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        let qualified = out
+            .chars()
+            .last()
+            .is_some_and(|p| p.is_alphanumeric() || matches!(p, '_' | '.' | '\\'));
+        let prefix = if qualified { "" } else { table };
+        if chars.get(i + 2) == Some(&'[') {
+            // `[@[A]:[B]]` — the bracketed column list follows as is.
+            out.push_str(&format!("{prefix}[[#This Row],"));
+            i += 2;
+        } else if let Some(end) = chars[i + 2..].iter().position(|&c| c == ']') {
+            let column: String = chars[i + 2..i + 2 + end].iter().collect();
+            out.push_str(&format!("{prefix}[[#This Row],[{column}]]"));
+            i += end + 3;
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Qualify `[@Column]` references in `formula_columns` with the table name.
+/// Without a table they have nothing to refer to, so they raise.
+pub fn resolve_structured_refs(
+    mut columns: Vec<FormulaColumn>,
+    table: Option<&TableSpec>,
+) -> PyResult<Vec<FormulaColumn>> {
+    for column in &mut columns {
+        if !column.template.contains("[@") {
+            continue;
+        }
+        let Some(table) = table else {
+            return Err(value_err(format!(
+                "formula_columns['{}'] uses a [@Column] reference, which only resolves inside \
+a table; pass table=True to write the sheet as an Excel table",
+                column.header
+            )));
+        };
+        column.template = expand_this_row(&column.template, &table.name);
+    }
+    Ok(columns)
+}
+
+/// Escape a header for use inside `[...]`, as Excel does.
+fn structured_name(header: &str) -> String {
+    header
+        .replace('\'', "''")
+        .replace('#', "'#")
+        .replace(']', "']")
+        .replace('[', "'[")
+}
+
+/// The totals-row function for one `totals_row` entry, as a `SUBTOTAL` over
+/// the column so the totals dropdown works and filtered rows drop out.
+fn total_function(
+    cell: &TotalsCell,
+    header: &str,
+    col: u16,
+    first: u32,
+    last: u32,
+) -> TableFunction {
+    match cell {
+        TotalsCell::Aggregate("SUM") => TableFunction::Sum,
+        TotalsCell::Aggregate("AVERAGE") => TableFunction::Average,
+        TotalsCell::Aggregate("COUNT") => TableFunction::CountNumbers,
+        TotalsCell::Aggregate("MIN") => TableFunction::Min,
+        TotalsCell::Aggregate("MAX") => TableFunction::Max,
+        TotalsCell::Aggregate("STDEV") => TableFunction::StdDev,
+        // No dropdown entry for PRODUCT; SUBTOTAL still covers it.
+        TotalsCell::Aggregate("PRODUCT") => TableFunction::Custom(Formula::new(format!(
+            "SUBTOTAL(106,[{}])",
+            structured_name(header)
+        ))),
+        // `excel_function` names every aggregate a totals cell can hold.
+        TotalsCell::Aggregate(other) => unreachable!("no table function for {other}"),
+        formula @ TotalsCell::Formula(_) => {
+            let letter = rust_xlsxwriter::utility::column_number_to_name(col);
+            TableFunction::Custom(Formula::new(formula.render(&letter, first, last)))
+        }
+    }
+}
+
+/// Add the table over the header, the data and — when `totals_row` or
+/// `totals_label` is set and there is data — the totals row, which the table
+/// writes itself in place of [`SheetLayout::apply_totals`].
+///
+/// With no data rows the table still gets one blank row, which is how Excel
+/// represents an empty table.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_table(
+    worksheet: &mut Worksheet,
+    spec: &TableSpec,
+    headers: &[String],
+    formula_cols: &[FormulaColumn],
+    layout: &SheetLayout,
+    data_rows: u32,
+    header_fmt: Option<&Format>,
+    py: Python,
+) -> PyResult<()> {
+    if headers.is_empty() {
+        return Ok(());
+    }
+    let has_totals = data_rows > 0 && layout.has_totals();
+    let first = layout.first_data_row();
+    let last_data = first + data_rows.max(1) - 1;
+    let totals_row = last_data + 1;
+
+    let n_data_cols = headers.len() - formula_cols.len();
+    let mut columns: Vec<TableColumn> = headers
+        .iter()
+        .map(|h| {
+            let column = TableColumn::new().set_header(h);
+            match header_fmt {
+                Some(fmt) => column.set_header_format(fmt),
+                None => column,
+            }
+        })
+        .collect();
+
+    // A formula without row placeholders is the same on every row, which is
+    // what Excel calls a calculated column: it fills in as rows are added.
+    for (offset, fc) in formula_cols.iter().enumerate() {
+        if !fc.template.contains("{row}") && !fc.template.contains("{first}") {
+            let col = n_data_cols + offset;
+            columns[col] = columns[col].clone().set_formula(fc.template.as_str());
+        }
+    }
+
+    let mut total_cols: Vec<u16> = Vec::new();
+    if has_totals {
+        for (name, cell) in &layout.totals {
+            let Some(col) = headers.iter().position(|h| h == name) else {
+                warn_py(py, &format!("totals_row: unknown column '{name}', skipped"))?;
+                continue;
+            };
+            // A1 notation is 1-based.
+            let function = total_function(cell, name, col as u16, first + 1, last_data + 1);
+            columns[col] = columns[col].clone().set_total_function(function);
+            total_cols.push(col as u16);
+        }
+        if let Some(label) = &layout.totals_label {
+            if total_cols.contains(&0) {
+                return Err(value_err(
+                    "totals_label would overwrite the totals formula in the first column; \
+drop one of them or move the aggregate to another column"
+                        .into(),
+                ));
+            }
+            columns[0] = columns[0].clone().set_total_label(label);
+            total_cols.push(0);
+        }
+    }
+
+    let table = spec
+        .table
+        .clone()
+        .set_name(&spec.name)
+        .set_columns(&columns)
+        .set_total_row(has_totals);
+    let last_row = if has_totals { totals_row } else { last_data };
+    worksheet
+        .add_table(
+            layout.header_row(),
+            0,
+            last_row,
+            (headers.len() - 1) as u16,
+            &table,
+        )
+        .map_err(|e| value_err(format!("table: {e}")))?;
+
+    if let Some(fmt) = layout.totals_format.as_ref().filter(|_| has_totals) {
+        for col in total_cols {
+            worksheet
+                .set_cell_format(totals_row, col, fmt)
+                .map_err(xlsx_err)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,738 @@
+//! Time zones — the `timezone` option and the zone rules behind it.
+//!
+//! Aware Python datetimes are converted by Python itself
+//! (`datetime.astimezone`). Arrow timestamps are converted here, from the
+//! instant they store to the wall time of a [`Zone`]: a fixed offset, or the
+//! rules of a compiled tz database file (TZif, RFC 8536) — the one Python's
+//! `zoneinfo` would load for that name. The rules are read once, while the GIL
+//! is held, so the batch loop needs no Python.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use arrow_schema::{DataType, Schema};
+use pyo3::prelude::*;
+use pyo3::types::{PyDateTime, PyDelta, PyDeltaAccess};
+
+use crate::helpers::value_err;
+
+/// The `timezone` option: the zone aware datetimes are shown in.
+pub struct Timezone {
+    /// As given, for messages.
+    name: String,
+    /// `None` under `"keep"`.
+    target: Option<Target>,
+}
+
+struct Target {
+    /// Handed to `datetime.astimezone`; `None` for the local zone.
+    tzinfo: Option<Py<PyAny>>,
+    zone: Arc<Zone>,
+}
+
+impl Timezone {
+    /// `"keep"` (the default), `"utc"`, `"local"`, an IANA name such as
+    /// `"Europe/Berlin"`, or a fixed offset such as `"+05:30"`.
+    pub fn parse(py: Python<'_>, value: Option<&str>) -> PyResult<Self> {
+        let name = value.unwrap_or("keep");
+        let target = match name {
+            "keep" => None,
+            "local" => Some(Target {
+                tzinfo: None,
+                zone: Arc::new(Zone::local(py)?),
+            }),
+            _ => {
+                let zone = Zone::resolve(py, name).map_err(|_| {
+                    value_err(format!(
+                        "timezone must be 'keep', 'utc', 'local', an IANA name such as \
+                         'Europe/Berlin' or an offset such as '+05:30', got '{name}'"
+                    ))
+                })?;
+                let tzinfo = match &zone {
+                    Zone::Fixed(seconds) => {
+                        let datetime = py.import("datetime")?;
+                        let delta = PyDelta::new(py, 0, *seconds, 0, true)?;
+                        datetime.getattr("timezone")?.call1((delta,))?
+                    }
+                    Zone::Rules(_) => py.import("zoneinfo")?.getattr("ZoneInfo")?.call1((name,))?,
+                };
+                Some(Target {
+                    tzinfo: Some(tzinfo.unbind()),
+                    zone: Arc::new(zone),
+                })
+            }
+        };
+        Ok(Timezone {
+            name: name.to_string(),
+            target,
+        })
+    }
+
+    /// `dt` as the wall time to write: an aware datetime converted to the
+    /// target zone, a naive one — or any under `"keep"` — as it is.
+    pub fn wall<'py>(&self, dt: &Bound<'py, PyDateTime>) -> PyResult<Bound<'py, PyDateTime>> {
+        let Some(target) = &self.target else {
+            return Ok(dt.clone());
+        };
+        if !is_aware(dt)? {
+            return Ok(dt.clone());
+        }
+        let converted = match &target.tzinfo {
+            Some(tzinfo) => dt.call_method1("astimezone", (tzinfo.bind(dt.py()),))?,
+            None => dt.call_method0("astimezone")?,
+        };
+        Ok(converted.cast_into::<PyDateTime>()?)
+    }
+
+    /// For each field of `schema`, the zone its timestamps are shown in:
+    /// the target zone, or under `"keep"` the column's own. `None` for
+    /// naive timestamps and every other type, which are written as stored.
+    pub fn arrow_zones(&self, py: Python<'_>, schema: &Schema) -> PyResult<Vec<Option<Arc<Zone>>>> {
+        let mut own: HashMap<String, Arc<Zone>> = HashMap::new();
+        let mut zones = Vec::with_capacity(schema.fields().len());
+        for field in schema.fields() {
+            let zone = match timestamp_tz(field.data_type()) {
+                None => None,
+                Some(_) if self.target.is_some() => {
+                    self.target.as_ref().map(|target| target.zone.clone())
+                }
+                Some(tz) => {
+                    if !own.contains_key(tz) {
+                        let zone = Zone::resolve(py, tz).map_err(|_| {
+                            value_err(format!(
+                                "column '{}' has an unknown time zone '{tz}'",
+                                field.name()
+                            ))
+                        })?;
+                        own.insert(tz.to_string(), Arc::new(zone));
+                    }
+                    own.get(tz).cloned()
+                }
+            };
+            zones.push(zone);
+        }
+        Ok(zones)
+    }
+}
+
+/// Whether a Python datetime carries a UTC offset.
+pub fn is_aware(dt: &Bound<'_, PyDateTime>) -> PyResult<bool> {
+    Ok(!dt.call_method0("utcoffset")?.is_none())
+}
+
+/// `+HH:MM` for an aware datetime (with `:SS` when the offset has seconds),
+/// or `None` for a naive one.
+pub fn py_utc_offset(dt: &Bound<'_, PyDateTime>) -> PyResult<Option<String>> {
+    let offset = dt.call_method0("utcoffset")?;
+    if offset.is_none() {
+        return Ok(None);
+    }
+    let delta = offset.cast_into::<PyDelta>()?;
+    let seconds = i64::from(delta.get_days()) * 86_400 + i64::from(delta.get_seconds());
+    Ok(Some(format_offset(seconds as i32)))
+}
+
+/// An offset from UTC in seconds as ISO 8601 `+HH:MM[:SS]`.
+pub fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let abs = seconds.unsigned_abs();
+    let (h, m, s) = (abs / 3600, abs / 60 % 60, abs % 60);
+    if s == 0 {
+        format!("{sign}{h:02}:{m:02}")
+    } else {
+        format!("{sign}{h:02}:{m:02}:{s:02}")
+    }
+}
+
+/// The time zone of an Arrow timestamp type, looking through dictionaries.
+fn timestamp_tz(data_type: &DataType) -> Option<&str> {
+    match data_type {
+        DataType::Timestamp(_, tz) => tz.as_deref(),
+        DataType::Dictionary(_, values) => timestamp_tz(values),
+        _ => None,
+    }
+}
+
+/// Columns that have held both naive and aware datetimes, found while the
+/// rows are written and reported once the sheet is done.
+#[derive(Default)]
+pub struct TzMix {
+    seen: HashMap<usize, bool>,
+    mixed: BTreeSet<usize>,
+}
+
+impl TzMix {
+    /// Note that column `col` held a datetime.
+    pub fn see(&mut self, col: usize, dt: &Bound<'_, PyDateTime>) -> PyResult<()> {
+        let aware = is_aware(dt)?;
+        if *self.seen.entry(col).or_insert(aware) != aware {
+            self.mixed.insert(col);
+        }
+        Ok(())
+    }
+
+    /// Under `"keep"` a mixed column is only a warning, since both kinds are
+    /// written as the wall time they hold. Converting has no answer for the
+    /// naive values, so it raises.
+    pub fn check(&self, headers: &[String], timezone: &Timezone, py: Python<'_>) -> PyResult<()> {
+        let Some(&col) = self.mixed.first() else {
+            return Ok(());
+        };
+        let name = headers.get(col).map_or_else(|| col.to_string(), |h| h.clone());
+        if timezone.target.is_some() {
+            return Err(value_err(format!(
+                "column '{name}' mixes naive and timezone-aware datetimes, and naive values \
+                 cannot be converted to '{}'. Make them aware, or pass timezone=\"keep\".",
+                timezone.name
+            )));
+        }
+        let message = format!(
+            "column '{name}' mixes naive and timezone-aware datetimes; each is written as \
+             the wall time it holds"
+        );
+        let warnings = py.import("warnings")?;
+        let category = py.get_type::<pyo3::exceptions::PyUserWarning>();
+        warnings.call_method1("warn", (message, category, 2))?;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Zone rules
+// ---------------------------------------------------------------------------
+
+/// A time zone: how far its wall clock is from UTC at any instant.
+pub enum Zone {
+    /// Seconds east of UTC.
+    Fixed(i32),
+    Rules(Box<Rules>),
+}
+
+pub struct Rules {
+    /// Instants (Unix seconds) at which the offset changes, ascending.
+    transitions: Vec<i64>,
+    /// The offset from each transition on.
+    offsets: Vec<i32>,
+    /// The offset before the first transition.
+    initial: i32,
+    /// The rule past the last transition.
+    footer: Option<Posix>,
+}
+
+impl Zone {
+    /// Seconds east of UTC at Unix time `seconds`.
+    pub fn offset_at(&self, seconds: i64) -> i32 {
+        match self {
+            Zone::Fixed(offset) => *offset,
+            Zone::Rules(rules) => rules.offset_at(seconds),
+        }
+    }
+
+    /// The zone an Arrow timezone string or the `timezone` option names: a
+    /// UTC alias, an offset, or a key of the tz database.
+    fn resolve(py: Python<'_>, name: &str) -> PyResult<Zone> {
+        if matches!(name, "utc" | "UTC" | "Z" | "GMT" | "Etc/UTC" | "Etc/GMT") {
+            return Ok(Zone::Fixed(0));
+        }
+        if let Some(seconds) = parse_offset(name) {
+            return Ok(Zone::Fixed(seconds));
+        }
+        let data = tzif_bytes(py, name)?;
+        Rules::parse(&data)
+            .map(|rules| Zone::Rules(Box::new(rules)))
+            .ok_or_else(|| value_err(format!("unreadable time zone data for '{name}'")))
+    }
+
+    /// The zone of this machine: `TZ` if set, else `/etc/localtime`, else the
+    /// current offset as a fixed one.
+    fn local(py: Python<'_>) -> PyResult<Zone> {
+        if let Ok(tz) = std::env::var("TZ") {
+            let key = tz.strip_prefix(':').unwrap_or(&tz);
+            if key.starts_with('/') {
+                if let Some(rules) = std::fs::read(key).ok().and_then(|d| Rules::parse(&d)) {
+                    return Ok(Zone::Rules(Box::new(rules)));
+                }
+            } else if let Ok(zone) = Zone::resolve(py, key) {
+                return Ok(zone);
+            } else if let Some(rule) = Posix::parse(key) {
+                return Ok(Zone::Rules(Box::new(Rules::from_posix(rule))));
+            }
+        }
+        if let Some(rules) = std::fs::read("/etc/localtime")
+            .ok()
+            .and_then(|data| Rules::parse(&data))
+        {
+            return Ok(Zone::Rules(Box::new(rules)));
+        }
+        let offset: i32 = py
+            .import("time")?
+            .call_method0("localtime")?
+            .getattr("tm_gmtoff")?
+            .extract()?;
+        Ok(Zone::Fixed(offset))
+    }
+}
+
+/// `+05:30`, `-0800` or `+05` as seconds east of UTC.
+fn parse_offset(text: &str) -> Option<i32> {
+    let sign = match text.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits: String = text[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    (hours < 24 && minutes < 60).then_some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// The compiled tz database file for `key`, from the directories
+/// `zoneinfo.TZPATH` lists or else the `tzdata` package — where
+/// `zoneinfo.ZoneInfo` looks. `ZoneInfo` also vets the key, so a name cannot
+/// reach outside those directories.
+fn tzif_bytes(py: Python<'_>, key: &str) -> PyResult<Vec<u8>> {
+    let zoneinfo = py.import("zoneinfo")?;
+    zoneinfo.getattr("ZoneInfo")?.call1((key,))?;
+    let tzpath: Vec<String> = zoneinfo.getattr("TZPATH")?.extract()?;
+    for dir in tzpath {
+        let path = std::path::Path::new(&dir).join(key);
+        if let Ok(data) = std::fs::read(path) {
+            return Ok(data);
+        }
+    }
+    let mut resource = py
+        .import("importlib.resources")?
+        .call_method1("files", ("tzdata.zoneinfo",))?;
+    for part in key.split('/') {
+        resource = resource.call_method1("joinpath", (part,))?;
+    }
+    resource.call_method0("read_bytes")?.extract()
+}
+
+impl Rules {
+    /// A `TZ` rule alone, with no transitions before it.
+    fn from_posix(rule: Posix) -> Rules {
+        Rules {
+            transitions: Vec::new(),
+            offsets: Vec::new(),
+            initial: rule.std,
+            footer: Some(rule),
+        }
+    }
+
+    fn offset_at(&self, seconds: i64) -> i32 {
+        let after = self.transitions.partition_point(|&t| t <= seconds);
+        // The footer takes over past the last transition — from the start
+        // when there are none.
+        if after == self.transitions.len() {
+            if let Some(rule) = &self.footer {
+                return rule.offset_at(seconds);
+            }
+        }
+        if after == 0 {
+            return self.initial;
+        }
+        self.offsets[after - 1]
+    }
+
+    /// A TZif file: the 64-bit block of a version 2+ file and its footer, or
+    /// the 32-bit block of a version 1 file.
+    fn parse(data: &[u8]) -> Option<Rules> {
+        let (header, body) = tzif_header(data)?;
+        let version = data[4];
+        if version == 0 {
+            return tzif_block(body, header, 4).map(|(rules, _)| rules);
+        }
+        let v1_len = header.block_len(4);
+        let rest = body.get(v1_len..)?;
+        let (header, body) = tzif_header(rest)?;
+        let (mut rules, tail) = tzif_block(body, header, 8)?;
+        // The footer is a POSIX TZ string between newlines.
+        let footer = tail.strip_prefix(b"\n")?;
+        let end = footer.iter().position(|&b| b == b'\n')?;
+        rules.footer = std::str::from_utf8(&footer[..end]).ok().and_then(Posix::parse);
+        Some(rules)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct TzifHeader {
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl TzifHeader {
+    fn block_len(&self, time_size: usize) -> usize {
+        self.timecnt * time_size
+            + self.timecnt
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+fn tzif_header(data: &[u8]) -> Option<(TzifHeader, &[u8])> {
+    if data.len() < 44 || &data[..4] != b"TZif" {
+        return None;
+    }
+    let count = |i: usize| {
+        let at = 20 + i * 4;
+        u32::from_be_bytes(data[at..at + 4].try_into().unwrap()) as usize
+    };
+    let header = TzifHeader {
+        isutcnt: count(0),
+        isstdcnt: count(1),
+        leapcnt: count(2),
+        timecnt: count(3),
+        typecnt: count(4),
+        charcnt: count(5),
+    };
+    Some((header, &data[44..]))
+}
+
+/// One data block; returns the rules and whatever follows the block.
+fn tzif_block(body: &[u8], header: TzifHeader, time_size: usize) -> Option<(Rules, &[u8])> {
+    let len = header.block_len(time_size);
+    let block = body.get(..len)?;
+    let times = &block[..header.timecnt * time_size];
+    let indices = &block[times.len()..times.len() + header.timecnt];
+    let types = &block[times.len() + indices.len()..][..header.typecnt * 6];
+    let utoff = |i: usize| -> Option<i32> {
+        let at = i * 6;
+        Some(i32::from_be_bytes(types.get(at..at + 4)?.try_into().ok()?))
+    };
+    let transitions = times
+        .chunks_exact(time_size)
+        .map(|t| match time_size {
+            4 => i64::from(i32::from_be_bytes(t.try_into().unwrap())),
+            _ => i64::from_be_bytes(t.try_into().unwrap()),
+        })
+        .collect();
+    let offsets = indices
+        .iter()
+        .map(|&i| utoff(usize::from(i)))
+        .collect::<Option<Vec<_>>>()?;
+    let rules = Rules {
+        transitions,
+        offsets,
+        // RFC 8536: local time type 0 applies before the first transition.
+        initial: utoff(0)?,
+        footer: None,
+    };
+    Some((rules, &body[len..]))
+}
+
+// ---------------------------------------------------------------------------
+// POSIX TZ strings — `CET-1CEST,M3.5.0,M10.5.0/3`
+// ---------------------------------------------------------------------------
+
+/// A POSIX TZ rule: a standard offset, and optionally a daylight one with the
+/// dates it starts and ends each year.
+struct Posix {
+    /// Seconds east of UTC — the sign POSIX writes is the other way round.
+    std: i32,
+    dst: Option<(i32, RuleDay, i32, RuleDay, i32)>,
+}
+
+#[derive(Clone, Copy)]
+enum RuleDay {
+    /// `Jn`: day 1–365, never counting February 29.
+    Julian1(i64),
+    /// `n`: day 0–365, counting February 29.
+    Julian0(i64),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    MonthWeekDay(u32, u32, u32),
+}
+
+impl Posix {
+    fn parse(text: &str) -> Option<Posix> {
+        let mut rest = text;
+        skip_name(&mut rest)?;
+        let std = -parse_hms(&mut rest)?;
+        if rest.is_empty() {
+            return Some(Posix { std, dst: None });
+        }
+        skip_name(&mut rest)?;
+        let dst = if rest.starts_with(',') || rest.is_empty() {
+            std + 3600
+        } else {
+            -parse_hms(&mut rest)?
+        };
+        // Without dates, the US rules POSIX falls back on.
+        let rules = rest.strip_prefix(',').unwrap_or("M3.2.0,M11.1.0");
+        let (start, end) = rules.split_once(',')?;
+        let (start, start_time) = parse_rule(start)?;
+        let (end, end_time) = parse_rule(end)?;
+        Some(Posix {
+            std,
+            dst: Some((dst, start, start_time, end, end_time)),
+        })
+    }
+
+    fn offset_at(&self, seconds: i64) -> i32 {
+        let Some((dst, start, start_time, end, end_time)) = self.dst else {
+            return self.std;
+        };
+        let year = year_of(seconds + i64::from(self.std));
+        // The start is given in standard time, the end in daylight time.
+        let begins = start.days(year) * 86_400 + i64::from(start_time - self.std);
+        let ends = end.days(year) * 86_400 + i64::from(end_time - dst);
+        let in_dst = if begins < ends {
+            begins <= seconds && seconds < ends
+        } else {
+            !(ends <= seconds && seconds < begins)
+        };
+        if in_dst {
+            dst
+        } else {
+            self.std
+        }
+    }
+}
+
+/// Skip a zone abbreviation — letters, or anything between `<` and `>`.
+fn skip_name(rest: &mut &str) -> Option<()> {
+    let len = if let Some(quoted) = rest.strip_prefix('<') {
+        quoted.find('>')? + 2
+    } else {
+        rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len())
+    };
+    if len < 3 {
+        return None;
+    }
+    *rest = &rest[len..];
+    Some(())
+}
+
+/// `[+-]hh[:mm[:ss]]` as seconds.
+fn parse_hms(rest: &mut &str) -> Option<i32> {
+    let sign = match rest.as_bytes().first() {
+        Some(b'-') => -1,
+        Some(b'+') => 1,
+        _ => 0,
+    };
+    if sign != 0 {
+        *rest = &rest[1..];
+    }
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ':')
+        .unwrap_or(rest.len());
+    let mut seconds = 0;
+    let mut parts = 0;
+    for (unit, part) in [3600, 60, 1].iter().zip(rest[..end].split(':')) {
+        seconds += unit * part.parse::<i32>().ok()?;
+        parts += 1;
+    }
+    if parts == 0 {
+        return None;
+    }
+    *rest = &rest[end..];
+    Some(if sign < 0 { -seconds } else { seconds })
+}
+
+/// A start or end date and its time of day — `M3.5.0/3`, 02:00 if no time.
+fn parse_rule(text: &str) -> Option<(RuleDay, i32)> {
+    let (day, time) = match text.split_once('/') {
+        Some((day, mut time)) => {
+            let seconds = parse_hms(&mut time)?;
+            (day, seconds)
+        }
+        None => (text, 7200),
+    };
+    let day = if let Some(n) = day.strip_prefix('J') {
+        RuleDay::Julian1(n.parse().ok()?)
+    } else if let Some(mwd) = day.strip_prefix('M') {
+        let mut parts = mwd.split('.').map(|p| p.parse::<u32>().ok());
+        let (m, w, d) = (parts.next()??, parts.next()??, parts.next()??);
+        if !(1..=12).contains(&m) || !(1..=5).contains(&w) || d > 6 {
+            return None;
+        }
+        RuleDay::MonthWeekDay(m, w, d)
+    } else {
+        RuleDay::Julian0(day.parse().ok()?)
+    };
+    Some((day, time))
+}
+
+impl RuleDay {
+    /// Days since the Unix epoch of this date in `year`.
+    fn days(self, year: i64) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        match self {
+            RuleDay::Julian1(n) => jan1 + n - 1 + i64::from(leap && n >= 60),
+            RuleDay::Julian0(n) => jan1 + n,
+            RuleDay::MonthWeekDay(m, w, d) => {
+                let first = days_from_civil(year, m, 1);
+                let next = if m == 12 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, m + 1, 1)
+                };
+                // 1970-01-01 was a Thursday.
+                let weekday = (first + 4).rem_euclid(7);
+                let first_match = first + (i64::from(d) - weekday).rem_euclid(7);
+                let mut day = first_match + 7 * (i64::from(w) - 1);
+                while day >= next {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+/// Howard Hinnant's days_from_civil — (y, m, d) → days since the Unix epoch.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The calendar year Unix time `seconds` falls in.
+fn year_of(seconds: i64) -> i64 {
+    let z = seconds.div_euclid(86_400) + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    yoe + era * 400 + i64::from(mp >= 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unix time of a UTC wall time.
+    fn utc(year: i64, month: u32, day: u32, hour: i64) -> i64 {
+        days_from_civil(year, month, day) * 86_400 + hour * 3600
+    }
+
+    /// A TZif data block of `time_size`-byte times: one transition to each
+    /// of `offsets[1..]` at `times`, with `offsets[0]` before the first.
+    fn block(time_size: usize, times: &[i64], offsets: &[i32]) -> (Vec<u8>, Vec<u8>) {
+        let mut header = Vec::new();
+        for count in [0, 0, 0, times.len(), offsets.len(), 4] {
+            header.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+        let mut data = Vec::new();
+        for &t in times {
+            match time_size {
+                4 => data.extend_from_slice(&(t as i32).to_be_bytes()),
+                _ => data.extend_from_slice(&t.to_be_bytes()),
+            }
+        }
+        data.extend((1..=times.len()).map(|i| i as u8));
+        for &offset in offsets {
+            data.extend_from_slice(&offset.to_be_bytes());
+            data.extend_from_slice(&[u8::from(offset != offsets[0]), 0]);
+        }
+        data.extend_from_slice(b"UTC\0");
+        (header, data)
+    }
+
+    fn tzif(version: u8, header: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut out = b"TZif".to_vec();
+        out.push(version);
+        out.extend_from_slice(&[0; 15]);
+        out.extend_from_slice(header);
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn posix_parses_offsets_and_rules() {
+        let cet = Posix::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(cet.std, 3600);
+        assert_eq!(cet.dst.map(|dst| dst.0), Some(7200));
+        let india = Posix::parse("<+0530>-5:30").unwrap();
+        assert_eq!((india.std, india.dst.is_none()), (19_800, true));
+        // Without dates, the US rules.
+        let us = Posix::parse("XST8XDT").unwrap();
+        assert_eq!(us.offset_at(utc(2024, 7, 1, 0)), -7 * 3600);
+        for bad in ["", "AB5", "CET", "CET-1CEST,M3.5.0", "CET-1CEST,M13.1.0,M10.5.0"] {
+            assert!(Posix::parse(bad).is_none(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn posix_switches_at_the_given_local_times() {
+        // Europe: 02:00 CET in March, 03:00 CEST in October — both 01:00 UTC.
+        let cet = Posix::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let spring = utc(2024, 3, 31, 1);
+        let autumn = utc(2024, 10, 27, 1);
+        assert_eq!(cet.offset_at(spring - 1), 3600);
+        assert_eq!(cet.offset_at(spring), 7200);
+        assert_eq!(cet.offset_at(autumn - 1), 7200);
+        assert_eq!(cet.offset_at(autumn), 3600);
+        // Southern hemisphere: daylight time spans the new year.
+        let sydney = Posix::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(sydney.offset_at(utc(2024, 1, 15, 0)), 11 * 3600);
+        assert_eq!(sydney.offset_at(utc(2024, 7, 1, 0)), 10 * 3600);
+    }
+
+    #[test]
+    fn rule_days() {
+        let day = |rule: &str, year| parse_rule(rule).unwrap().0.days(year);
+        assert_eq!(day("M3.5.0", 2024), days_from_civil(2024, 3, 31));
+        assert_eq!(day("M3.2.0", 2024), days_from_civil(2024, 3, 10));
+        assert_eq!(day("M11.1.0", 2024), days_from_civil(2024, 11, 3));
+        // Week 5 is the last such weekday, even in a month with only four.
+        assert_eq!(day("M2.5.4", 2024), days_from_civil(2024, 2, 29));
+        assert_eq!(day("M2.5.4", 2023), days_from_civil(2023, 2, 23));
+        // `Jn` never counts February 29; `n` does, from 0.
+        assert_eq!(day("J60", 2024), days_from_civil(2024, 3, 1));
+        assert_eq!(day("J60", 2023), days_from_civil(2023, 3, 1));
+        assert_eq!(day("59", 2024), days_from_civil(2024, 2, 29));
+        assert_eq!(parse_rule("M3.2.0").unwrap().1, 7200);
+        assert_eq!(parse_rule("M3.2.0/-1").unwrap().1, -3600);
+    }
+
+    #[test]
+    fn footer_only_rules_apply_daylight_time() {
+        let rules = Rules::from_posix(Posix::parse("XST8XDT,M3.2.0,M11.1.0").unwrap());
+        assert_eq!(rules.offset_at(utc(2024, 1, 1, 0)), -8 * 3600);
+        assert_eq!(rules.offset_at(utc(2024, 7, 1, 0)), -7 * 3600);
+    }
+
+    #[test]
+    fn version_1_file() {
+        let (header, data) = block(4, &[1000], &[0, 3600]);
+        let rules = Rules::parse(&tzif(0, &header, &data)).unwrap();
+        assert!(rules.footer.is_none());
+        assert_eq!(rules.offset_at(999), 0);
+        assert_eq!(rules.offset_at(1000), 3600);
+        assert_eq!(rules.offset_at(i64::from(i32::MAX)), 3600);
+    }
+
+    #[test]
+    fn version_2_file_hands_over_to_its_footer() {
+        let (v1_header, v1_data) = block(4, &[], &[0]);
+        let (header, data) = block(8, &[utc(2000, 1, 1, 0)], &[0, 3600]);
+        let mut file = tzif(2, &v1_header, &v1_data);
+        file.extend(tzif(2, &header, &data));
+        file.extend_from_slice(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n");
+        let rules = Rules::parse(&file).unwrap();
+        assert_eq!(rules.offset_at(utc(1999, 7, 1, 0)), 0);
+        assert_eq!(rules.offset_at(utc(2024, 1, 1, 0)), 3600);
+        assert_eq!(rules.offset_at(utc(2024, 7, 1, 0)), 7200);
+        assert!(Rules::parse(b"TZif2").is_none());
+    }
+}
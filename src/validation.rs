@@ -0,0 +1,361 @@
+//! Data validation (dropdowns, ranges, length limits), keyed by column header.
+//!
+//! Validations are stored in the worksheet footer like conditional formats,
+//! so they are added after the data with the range taken from the final row
+//! count. Specs are parsed before any cell is written; a bad one raises
+//! `ValueError` rather than producing a file Excel silently repairs.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDate, PyDateTime, PyDict, PyList, PyString, PyTime, PyTimeAccess};
+use rust_xlsxwriter::{
+    DataValidation, DataValidationErrorStyle, DataValidationRule, ExcelDateTime, Formula, Worksheet,
+};
+
+use crate::helpers::{formula_problem, value_err, warn_py, OptionDict, SheetLayout};
+use crate::worksheet::xlsx_err;
+
+/// A parsed validation plus the header name of the column it covers. A
+/// `custom` validation keeps its formula template, because `{col}`, `{first}`
+/// and `{last}` depend on where the column lands and how many rows the sheet
+/// takes.
+pub struct ColumnValidation {
+    column: String,
+    validation: DataValidation,
+    custom: Option<String>,
+}
+
+/// Which values a validation accepts (xlsxwriter's `validate` key).
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Whole,
+    Decimal,
+    Date,
+    Time,
+    Length,
+}
+
+impl Kind {
+    fn expects(self) -> &'static str {
+        match self {
+            Kind::Whole => "an int",
+            Kind::Decimal => "a number",
+            Kind::Date => "a date or datetime",
+            Kind::Time => "a time",
+            Kind::Length => "a non-negative int",
+        }
+    }
+}
+
+fn excel_err(label: &str, e: rust_xlsxwriter::XlsxError) -> PyErr {
+    value_err(format!("{label}: {e}"))
+}
+
+/// Read one bound as the string Excel stores in the validation. A string
+/// starting with `=` is a cell reference or formula (`"=$H$1"`) and is allowed
+/// for every kind; anything else must match `kind`.
+fn bound(spec: &OptionDict, key: &str, kind: Kind) -> PyResult<Formula> {
+    let v = spec.require(key)?;
+    let wrong = || {
+        value_err(format!(
+            "{}: '{key}' must be {} or a formula string starting with '='",
+            spec.label,
+            kind.expects()
+        ))
+    };
+    if let Ok(s) = v.cast::<PyString>() {
+        let s = s.to_str()?;
+        if !s.starts_with('=') {
+            return Err(wrong());
+        }
+        return Ok(Formula::new(s));
+    }
+    if v.cast::<PyBool>().is_ok() {
+        return Err(wrong());
+    }
+    let value = match kind {
+        Kind::Whole => {
+            let n: i64 = v.extract().map_err(|_| wrong())?;
+            i32::try_from(n)
+                .map_err(|_| {
+                    value_err(format!(
+                        "{}: '{key}' is outside Excel's whole-number range",
+                        spec.label
+                    ))
+                })?
+                .to_string()
+        }
+        Kind::Decimal => v.extract::<f64>().map_err(|_| wrong())?.to_string(),
+        Kind::Length => v.extract::<u32>().map_err(|_| wrong())?.to_string(),
+        Kind::Date => {
+            let excel = if let Ok(dt) = v.cast::<PyDateTime>() {
+                crate::helpers::py_datetime_to_excel(dt)?
+            } else if let Ok(d) = v.cast::<PyDate>() {
+                crate::helpers::py_date_to_excel(d)?
+            } else {
+                return Err(wrong());
+            };
+            excel.to_excel().to_string()
+        }
+        Kind::Time => {
+            let t = v.cast::<PyTime>().map_err(|_| wrong())?;
+            let secs = f64::from(t.get_second()) + f64::from(t.get_microsecond()) / 1e6;
+            ExcelDateTime::from_hms(u16::from(t.get_hour()), t.get_minute(), secs)
+                .map_err(|e| excel_err(&spec.label, e))?
+                .to_excel()
+                .to_string()
+        }
+    };
+    Ok(Formula::new(value))
+}
+
+fn rule(spec: &OptionDict, kind: Kind) -> PyResult<DataValidationRule<Formula>> {
+    let criteria = spec.str("criteria")?.ok_or_else(|| {
+        value_err(format!(
+            "{}: missing required option 'criteria'",
+            spec.label
+        ))
+    })?;
+    use DataValidationRule as R;
+    Ok(match criteria.to_ascii_lowercase().as_str() {
+        "==" | "=" | "equal_to" => R::EqualTo(bound(spec, "value", kind)?),
+        "!=" | "<>" | "not_equal_to" => R::NotEqualTo(bound(spec, "value", kind)?),
+        ">" | "greater_than" => R::GreaterThan(bound(spec, "value", kind)?),
+        ">=" | "greater_than_or_equal_to" => R::GreaterThanOrEqualTo(bound(spec, "value", kind)?),
+        "<" | "less_than" => R::LessThan(bound(spec, "value", kind)?),
+        "<=" | "less_than_or_equal_to" => R::LessThanOrEqualTo(bound(spec, "value", kind)?),
+        "between" => R::Between(bound(spec, "minimum", kind)?, bound(spec, "maximum", kind)?),
+        "not_between" => {
+            R::NotBetween(bound(spec, "minimum", kind)?, bound(spec, "maximum", kind)?)
+        }
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown criteria '{other}' (valid: ==, !=, >, >=, <, <=, between, \
+not_between)",
+                spec.label
+            )))
+        }
+    })
+}
+
+/// `source` for a list validation: a list of strings for a literal dropdown,
+/// or a `"=..."` range reference.
+fn list_source(spec: &OptionDict, dv: DataValidation) -> PyResult<DataValidation> {
+    let source = spec.require("source")?;
+    if let Ok(s) = source.cast::<PyString>() {
+        let s = s.to_str()?;
+        if !s.starts_with('=') {
+            return Err(value_err(format!(
+                "{}: 'source' must be a list of strings or a range reference starting with '='",
+                spec.label
+            )));
+        }
+        return Ok(dv.allow_list_formula(Formula::new(s)));
+    }
+    let items: Vec<String> = source
+        .cast::<PyList>()
+        .ok()
+        .and_then(|l| l.extract().ok())
+        .ok_or_else(|| {
+            value_err(format!(
+                "{}: 'source' must be a list of strings or a range reference starting with '='",
+                spec.label
+            ))
+        })?;
+    if items.is_empty() {
+        return Err(value_err(format!("{}: 'source' is empty", spec.label)));
+    }
+    dv.allow_list_strings(&items)
+        .map_err(|e| excel_err(&spec.label, e))
+}
+
+const COMMON_KEYS: [&str; 9] = [
+    "ignore_blank",
+    "input_title",
+    "input_message",
+    "show_input",
+    "error_title",
+    "error_message",
+    "error_type",
+    "show_error",
+    "validate",
+];
+
+fn check_keys(spec: &OptionDict, extra: &[&str]) -> PyResult<()> {
+    let mut allowed: Vec<&str> = COMMON_KEYS.to_vec();
+    allowed.extend_from_slice(extra);
+    spec.check_keys(&allowed)
+}
+
+/// Input prompt, error alert and blank handling — shared by every kind.
+fn messages(spec: &OptionDict, mut dv: DataValidation) -> PyResult<DataValidation> {
+    let label = &spec.label;
+    if let Some(ignore) = spec.bool("ignore_blank")? {
+        dv = dv.ignore_blank(ignore);
+    }
+    if let Some(title) = spec.str("input_title")? {
+        dv = dv.set_input_title(title).map_err(|e| excel_err(label, e))?;
+    }
+    if let Some(msg) = spec.str("input_message")? {
+        dv = dv.set_input_message(msg).map_err(|e| excel_err(label, e))?;
+    }
+    if let Some(show) = spec.bool("show_input")? {
+        dv = dv.show_input_message(show);
+    }
+    if let Some(title) = spec.str("error_title")? {
+        dv = dv.set_error_title(title).map_err(|e| excel_err(label, e))?;
+    }
+    if let Some(msg) = spec.str("error_message")? {
+        dv = dv.set_error_message(msg).map_err(|e| excel_err(label, e))?;
+    }
+    if let Some(style) = spec.str("error_type")? {
+        dv = dv.set_error_style(match style.to_ascii_lowercase().as_str() {
+            "stop" => DataValidationErrorStyle::Stop,
+            "warning" => DataValidationErrorStyle::Warning,
+            "information" => DataValidationErrorStyle::Information,
+            other => {
+                return Err(value_err(format!(
+                    "{label}: unknown error_type '{other}' (valid: stop, warning, information)"
+                )))
+            }
+        });
+    }
+    if let Some(show) = spec.bool("show_error")? {
+        dv = dv.show_error_message(show);
+    }
+    Ok(dv)
+}
+
+fn parse_validation(spec: &OptionDict) -> PyResult<(DataValidation, Option<String>)> {
+    let validate = spec.str("validate")?.ok_or_else(|| {
+        value_err(format!(
+            "{}: missing required option 'validate'",
+            spec.label
+        ))
+    })?;
+    let dv = DataValidation::new();
+    let mut custom = None;
+    let dv = match validate.to_ascii_lowercase().as_str() {
+        "list" => {
+            check_keys(spec, &["source", "dropdown"])?;
+            let dv = list_source(spec, dv)?;
+            // `dropdown=False` keeps the check but hides the in-cell arrow.
+            dv.show_dropdown(spec.bool("dropdown")?.unwrap_or(true))
+        }
+        kind @ ("whole" | "integer" | "decimal" | "date" | "time" | "length") => {
+            check_keys(spec, &["criteria", "value", "minimum", "maximum"])?;
+            match kind {
+                "whole" | "integer" => dv.allow_whole_number_formula(rule(spec, Kind::Whole)?),
+                "decimal" => dv.allow_decimal_number_formula(rule(spec, Kind::Decimal)?),
+                "date" => dv.allow_date_formula(rule(spec, Kind::Date)?),
+                "time" => dv.allow_time_formula(rule(spec, Kind::Time)?),
+                _ => dv.allow_text_length_formula(rule(spec, Kind::Length)?),
+            }
+        }
+        "custom" => {
+            check_keys(spec, &["value"])?;
+            let template = spec.str("value")?.ok_or_else(|| {
+                value_err(format!(
+                    "{}: missing required option 'value' (the formula)",
+                    spec.label
+                ))
+            })?;
+            if let Some(problem) = formula_problem(&template) {
+                return Err(value_err(format!(
+                    "{}: formula looks malformed: {problem}. Formula: {template}",
+                    spec.label
+                )));
+            }
+            custom = Some(template);
+            dv
+        }
+        "any" => {
+            check_keys(spec, &[])?;
+            dv.allow_any_value()
+        }
+        other => {
+            return Err(value_err(format!(
+                "{}: unknown validate '{other}' (valid: list, whole, decimal, date, time, \
+length, custom, any)",
+                spec.label
+            )))
+        }
+    };
+    Ok((messages(spec, dv)?, custom))
+}
+
+/// Read `data_validation` — `{column name: spec}`, each spec a dict with a
+/// `validate` key.
+pub fn resolve_data_validation(spec: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<ColumnValidation>> {
+    let Some(spec) = spec else {
+        return Ok(Vec::new());
+    };
+    let dict = spec.cast::<PyDict>().map_err(|_| {
+        value_err("data_validation must be a dict of {column name: validation}".into())
+    })?;
+    let mut out = Vec::with_capacity(dict.len());
+    for (key, val) in dict.iter() {
+        let column: String = key
+            .extract()
+            .map_err(|_| value_err("data_validation keys must be column names".into()))?;
+        let label = format!("data_validation['{column}']");
+        let (validation, custom) = parse_validation(&OptionDict::new(&val, label)?)?;
+        out.push(ColumnValidation {
+            column,
+            validation,
+            custom,
+        });
+    }
+    Ok(out)
+}
+
+/// Add every validation over its column's data rows. A sheet with headers but
+/// no rows, such as a template to fill in, gets it on the first data row; one
+/// with no columns at all gets none. Unknown columns warn and are skipped,
+/// matching `conditional_formats`.
+pub fn apply_data_validation(
+    worksheet: &mut Worksheet,
+    validations: &[ColumnValidation],
+    headers: &[String],
+    layout: &SheetLayout,
+    data_rows: u32,
+    py: Python,
+) -> PyResult<()> {
+    let first = layout.first_data_row();
+    // A header on the sheet's last row leaves no data row to validate.
+    if validations.is_empty() || headers.is_empty() || first > 1_048_575 {
+        return Ok(());
+    }
+    let last = first + data_rows.max(1) - 1;
+
+    for ColumnValidation {
+        column,
+        validation,
+        custom,
+    } in validations
+    {
+        let Some(col) = headers.iter().position(|h| h == column) else {
+            warn_py(
+                py,
+                &format!("data_validation: unknown column '{column}', skipped"),
+            )?;
+            continue;
+        };
+        let col = col as u16;
+        match custom {
+            // Like a formula conditional format, the formula is relative to
+            // the top-left cell of the range.
+            Some(template) => {
+                let letter = rust_xlsxwriter::utility::column_number_to_name(col);
+                let formula = template
+                    .replace("{col}", &letter)
+                    .replace("{first}", &(first + 1).to_string())
+                    .replace("{last}", &(last + 1).to_string());
+                let dv = validation.clone().allow_custom(Formula::new(formula));
+                worksheet.add_data_validation(first, col, last, col, &dv)
+            }
+            None => worksheet.add_data_validation(first, col, last, col, validation),
+        }
+        .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
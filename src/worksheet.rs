@@ -1,7 +1,10 @@
+use arrow_array::{RecordBatch, RecordBatchReader};
+use indexmap::IndexMap;
 use pyo3::prelude::*;
-use pyo3::types::{PyDate, PyDateTime, PyInt};
+use pyo3::types::{PyDate, PyDateTime, PyDelta, PyInt, PyTime};
 use pyo3::{Py, PyAny, Python};
 use rust_xlsxwriter::{Format, Workbook};
+use std::cell::Cell;
 use std::collections::HashSet;
 
 use crate::cell::{classify_and_write, try_cached, CellWriter};
@@ -11,6 +14,9 @@ use crate::helpers::{
     write_datetime_opt, write_num, write_number_opt, write_string_opt, write_url_or_text,
     ColType,
 };
+use crate::missing::{excel_error, is_nat, write_error_cell, write_special, Special};
+use crate::overflow::{continuation_name, Overflow, Rest, RowLimit};
+use crate::sentinel::Sentinel;
 use crate::utils::ensure_valid_sheet_name;
 
 pub fn xlsx_err(e: impl std::fmt::Display) -> PyErr {
@@ -32,6 +38,16 @@ struct ExcelCell<'a> {
     text_fmt: Option<&'a Format>,
     float_fmt: Option<&'a Format>,
     datetime_fmt: &'a Format,
+    time_fmt: &'a Format,
+    duration_fmt: &'a Format,
+    durations: crate::helpers::Durations,
+    nested: crate::nested::NestedColumns,
+    timezone: &'a crate::timezone::Timezone,
+    tz_mix: &'a mut crate::timezone::TzMix,
+    int_overflow: crate::helpers::IntOverflow,
+    /// The sheet's headers, to name a column in an error.
+    headers: &'a [String],
+    reps: &'a crate::missing::Reps,
     datetime_cols_set: &'a mut HashSet<u16>,
     col_override: Option<&'a Format>,
     /// When banding is on, datetimes are formatted per cell instead of via a
@@ -40,6 +56,9 @@ struct ExcelCell<'a> {
     per_cell_datetime: bool,
     /// This column was listed in `url_columns`, so strings become links.
     is_url: bool,
+    /// This column was listed in `error_columns`, so strings spelling an
+    /// Excel error become error cells.
+    is_error: bool,
 }
 
 impl ExcelCell<'_> {
@@ -89,10 +108,20 @@ impl ExcelCell<'_> {
 
 impl CellWriter for ExcelCell<'_> {
     fn write_none(&mut self) -> PyResult<()> {
-        self.put_string("")
+        write_special(
+            self.worksheet,
+            self.row,
+            self.col,
+            Special::Missing,
+            self.reps,
+            self.text_fmt,
+        )
     }
 
     fn write_str(&mut self, s: &str) -> PyResult<()> {
+        if let Some(error) = self.is_error.then(|| excel_error(s)).flatten() {
+            return self.write_sentinel(Sentinel::Error(error));
+        }
         self.put_string(s)
     }
 
@@ -117,24 +146,27 @@ impl CellWriter for ExcelCell<'_> {
             self.col,
             f,
             self.col_override.or(self.float_fmt),
+            self.reps,
         )
     }
 
     fn write_int(&mut self, i: &Bound<'_, PyInt>) -> PyResult<()> {
-        let val: f64 = i.extract()?;
         // On a band row `text_fmt` carries the fill, so an unformatted integer
         // column still gets shaded.
-        write_number_opt(
+        crate::helpers::write_py_int(
             self.worksheet,
             self.row,
             self.col,
-            val,
+            i,
+            self.int_overflow,
             self.col_override.or(self.text_fmt),
+            self.headers.get(self.col as usize).map_or("", String::as_str),
         )
     }
 
     fn write_datetime(&mut self, dt: &Bound<'_, PyDateTime>) -> PyResult<()> {
-        let excel_dt = py_datetime_to_excel(dt)?;
+        self.tz_mix.see(self.col as usize, dt)?;
+        let excel_dt = py_datetime_to_excel(&self.timezone.wall(dt)?)?;
         self.put_datetime(&excel_dt)
     }
 
@@ -142,6 +174,46 @@ impl CellWriter for ExcelCell<'_> {
         let excel_dt = py_date_to_excel(d)?;
         self.put_datetime(&excel_dt)
     }
+
+    fn write_time(&mut self, t: &Bound<'_, PyTime>) -> PyResult<()> {
+        write_number_opt(
+            self.worksheet,
+            self.row,
+            self.col,
+            crate::helpers::py_time_to_excel(t),
+            Some(self.col_override.unwrap_or(self.time_fmt)),
+        )
+    }
+
+    fn write_timedelta(&mut self, d: &Bound<'_, PyDelta>) -> PyResult<()> {
+        crate::helpers::write_duration(
+            self.worksheet,
+            self.row,
+            self.col,
+            crate::helpers::py_timedelta_micros(d) as f64 / 1e6,
+            self.durations,
+            self.col_override.unwrap_or(self.duration_fmt),
+            self.col_override.or(self.text_fmt),
+        )
+    }
+
+    fn write_nested(&mut self, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let text = crate::nested::py_nested_text(value, self.nested)?;
+        self.put_string(&text)
+    }
+
+    fn write_sentinel(&mut self, sentinel: Sentinel<'_>) -> PyResult<()> {
+        let (row, col) = (self.row, self.col);
+        let fmt = self.col_override.or(self.text_fmt);
+        match sentinel {
+            Sentinel::Error(error) => write_error_cell(self.worksheet, row, col, error, fmt),
+            Sentinel::Blank(Some(own)) => {
+                self.worksheet.write_blank(row, col, own).map_err(xlsx_err)?;
+                Ok(())
+            }
+            Sentinel::Blank(None) => write_string_opt(self.worksheet, row, col, "", fmt),
+        }
+    }
 }
 
 /// Per-column scalar classification shared by the Pandas and Polars writers.
@@ -165,62 +237,213 @@ fn polars_kind(dtype_str: &str) -> ScalarKind {
         ScalarKind::Float
     } else if dtype_str == "Boolean" {
         ScalarKind::Bool
-    } else if dtype_str.starts_with("Date")
-        || dtype_str.starts_with("Datetime")
-        || dtype_str.starts_with("Time")
-        || dtype_str.starts_with("Duration")
-    {
+    } else if dtype_str.starts_with("Date") || dtype_str.starts_with("Datetime") {
+        // Time and Duration values are `time`/`timedelta`, which the cascade
+        // writes with their own formats — a datetime column format would
+        // show a duration in seconds as a date.
         ScalarKind::Temporal
     } else {
         ScalarKind::Other
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn write_worksheet_content(
-    worksheet: &mut rust_xlsxwriter::Worksheet,
-    records: &WorksheetData,
-    password: Option<&String>,
+/// One sheet's options, resolved from the call's arguments — and, for
+/// `write_worksheets`, from its per-sheet dicts.
+struct SheetOptions<'py> {
+    password: Option<String>,
     freeze_row: Option<u32>,
     freeze_col: Option<u16>,
-    float_format: Option<&String>,
-    datetime_format: Option<&String>,
-    index_columns: Option<&Vec<String>>,
+    float_format: Option<String>,
+    datetime_format: Option<String>,
+    index_columns: Option<Vec<String>>,
     autofit: bool,
     bold_headers: bool,
     column_width: Option<f64>,
-    column_widths: Option<&Bound<'_, PyAny>>,
-    column_formats: Option<&Bound<'_, PyAny>>,
-    header_format: Option<&crate::format::Format>,
-    layout: &crate::helpers::SheetLayout,
-    url_columns: Option<&Vec<String>>,
-    formula_columns: Option<&Bound<'_, PyAny>>,
-    py: Python,
-) -> PyResult<()> {
-    let float_fmt = float_format.map(|s| Format::new().set_num_format(s));
-    let dt_fmt_str = datetime_format
-        .map(|s| s.as_str())
-        .unwrap_or("yyyy-mm-ddThh:mm:ss");
-    let datetime_fmt = Format::new().set_num_format(dt_fmt_str);
-    let bold_fmt = Format::new().set_bold();
-    let mut datetime_cols_set: HashSet<u16> = HashSet::new();
-    let mut final_headers: Vec<String> = Vec::new();
-    // Number of data rows written, needed for the autofilter range.
-    let mut data_rows: u32 = 0;
+    column_widths: Option<Bound<'py, PyAny>>,
+    column_formats: Option<Bound<'py, PyAny>>,
+    header_format: Option<crate::format::Format>,
+    layout: crate::helpers::SheetLayout,
+    url_columns: Option<Vec<String>>,
+    id_columns: Option<Vec<String>>,
+    error_columns: Option<Vec<String>>,
+    image_columns: Option<Vec<String>>,
+    formula_columns: Option<Bound<'py, PyAny>>,
+    conditional_formats: Option<Bound<'py, PyAny>>,
+    data_validation: Option<Bound<'py, PyAny>>,
+    table: Option<crate::table::TableSpec>,
+    charts: Option<Bound<'py, PyAny>>,
+    images: Option<Bound<'py, PyAny>>,
+    comments: Option<Bound<'py, PyAny>>,
+    wide_decimals: crate::arrow_writer::WideDecimals,
+    durations: crate::helpers::Durations,
+    nested: crate::nested::NestedColumns,
+    timezone: crate::timezone::Timezone,
+    int_overflow: crate::helpers::IntOverflow,
+    reps: crate::missing::Reps,
+    columns: crate::columns::ColumnSelection,
+    record_keys: crate::records::RecordKeys,
+    /// The columns a pandas index became under `index=True`.
+    index: crate::frame_index::IndexColumns,
+    limit: RowLimit,
+    /// Kept in memory until save rather than flushed row by row.
+    buffered: bool,
+}
+
+impl SheetOptions<'_> {
+    /// Whether an Arrow source on this sheet can have its batches written on
+    /// the thread pool. A split needs the next sheet as soon as this one is
+    /// full, and a row format under the data makes the crate register merged
+    /// row and column formats cell by cell, in whatever order the rows land.
+    fn can_write_in_parallel(&self) -> bool {
+        let first_data_row = self.layout.first_data_row();
+        self.limit.mode != Overflow::Split
+            && !self
+                .layout
+                .row_formats
+                .iter()
+                .any(|(row, _)| *row >= first_data_row)
+    }
+
+    /// Write the header row — and any `header_groups` rows above it — then
+    /// merge the index cells under it, now that the first data row is known.
+    fn write_headers(
+        &self,
+        worksheet: &mut rust_xlsxwriter::Worksheet,
+        start: &SheetStart,
+        headers: &[String],
+    ) -> PyResult<()> {
+        write_all_headers(
+            worksheet,
+            &self.layout,
+            headers,
+            self.bold_headers,
+            &start.bold_fmt,
+            self.index_columns.as_ref(),
+            self.header_format.as_ref().map(|h| &h.inner),
+        )?;
+        let capacity = RowLimit::capacity(&self.layout);
+        start
+            .index_merges
+            .set(self.index.apply(worksheet, &self.layout, start.first, capacity)?);
+        Ok(())
+    }
+}
+
+/// What a sheet resolves before its first row and keeps until its last step.
+struct SheetStart {
+    float_fmt: Option<Format>,
+    datetime_fmt: Format,
+    bold_fmt: Format,
+    formula_cols: Vec<crate::helpers::FormulaColumn>,
+    cond_formats: Vec<crate::conditional::ConditionalRule>,
+    validations: Vec<crate::validation::ColumnValidation>,
+    chart_specs: Vec<crate::chart::ChartSpec>,
+    floating_images: Vec<crate::image::FloatingImage>,
+    comments: crate::comment::Comments,
+    /// Source row of the sheet's first data row, for merging index cells.
+    first: usize,
+    /// Merged index cells, laid under the headers and blanked below their
+    /// label once the data is in.
+    index_merges: Cell<Vec<(u16, u32, u32)>>,
+}
 
+/// What the data pass leaves for [`finish_sheet`].
+struct SheetData {
+    headers: Vec<String>,
+    rows: u32,
+    image_cols: crate::image::ImageColumns,
+}
+
+/// Resolve the sheet's specs and lay down everything that has to precede the
+/// headers. The first data row holds source row `first`.
+fn begin_sheet(
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    opts: &SheetOptions,
+    first: usize,
+) -> PyResult<SheetStart> {
+    let dt_fmt_str = opts
+        .datetime_format
+        .as_deref()
+        .unwrap_or("yyyy-mm-ddThh:mm:ss");
     // Merges, row heights and row formats must all land before the first data
     // cell — constant-memory mode cannot revisit a flushed row.
-    layout.apply(worksheet)?;
-    let formula_cols = crate::helpers::resolve_formula_columns(formula_columns)?;
+    opts.layout.apply(worksheet)?;
+    let start = SheetStart {
+        float_fmt: opts
+            .float_format
+            .as_ref()
+            .map(|s| Format::new().set_num_format(s)),
+        datetime_fmt: Format::new().set_num_format(dt_fmt_str),
+        bold_fmt: Format::new().set_bold(),
+        formula_cols: crate::table::resolve_structured_refs(
+            crate::helpers::resolve_formula_columns(opts.formula_columns.as_ref())?,
+            opts.table.as_ref(),
+        )?,
+        cond_formats: crate::conditional::resolve_conditional_formats(
+            opts.conditional_formats.as_ref(),
+        )?,
+        validations: crate::validation::resolve_data_validation(opts.data_validation.as_ref())?,
+        chart_specs: crate::chart::resolve_charts(opts.charts.as_ref())?,
+        floating_images: crate::image::resolve_images(opts.images.as_ref())?,
+        comments: crate::comment::resolve_comments(opts.comments.as_ref())?,
+        first,
+        index_merges: Cell::default(),
+    };
     // Applies to every formula this sheet writes, so it must be set before any
     // of them. The crate's default cached result is 0, which readers that trust
     // the cache take at face value — `pandas.read_excel` reports 0, and
     // LibreOffice displays 0 instead of recalculating. An empty result reads
     // back as "not computed" and forces a recalculation on open.
     worksheet.set_formula_result_default("");
-    let first_data_row = layout.first_data_row();
-    let header_row = layout.header_row;
+    Ok(start)
+}
+
+/// Write one sheet: headers, data, then everything that depends on the final
+/// row count. `resume` continues a source that overflowed the previous sheet;
+/// the returned [`Rest`] is whatever did not fit on this one.
+fn write_worksheet_content<'py>(
+    workbook: &mut Workbook,
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    records: &WorksheetData,
+    resume: Option<Rest<'py>>,
+    opts: &SheetOptions<'py>,
+    py: Python<'py>,
+) -> PyResult<(Vec<crate::chart::PlacedChart>, Option<Rest<'py>>)> {
+    // Source row of this sheet's first data row, for merging index cells.
+    let first = match &resume {
+        Some(Rest::Frame { start }) => *start,
+        Some(Rest::Arrow { done, .. }) => *done,
+        _ => 0,
+    };
+    let mut start = begin_sheet(worksheet, opts, first)?;
+    // Charts and fixed-cell notes belong to the sheet as a whole, so only the
+    // first part of a split sheet gets them.
+    if resume.is_some() {
+        start.chart_specs.clear();
+        start.comments.drop_cells();
+    }
+    let SheetStart {
+        float_fmt,
+        datetime_fmt,
+        formula_cols,
+        comments,
+        ..
+    } = &start;
+    let layout = &opts.layout;
+    let limit = &opts.limit;
+    let column_formats = opts.column_formats.as_ref();
+    let url_columns = opts.url_columns.as_ref();
+    let id_columns = opts.id_columns.as_ref();
+    let error_columns = opts.error_columns.as_ref();
+    let image_columns = opts.image_columns.as_ref();
+    let mut datetime_cols_set: HashSet<u16> = HashSet::new();
+    let mut final_headers: Vec<String> = Vec::new();
+    // Number of data rows written, needed for the autofilter range.
+    let mut data_rows: u32 = 0;
+    // Resolved with the headers on each path below.
+    let mut image_cols = crate::image::ImageColumns::default();
     let banding = layout.band_color.is_some();
+    let mut rest: Option<Rest<'py>> = None;
 
     match records {
         WorksheetData::ArrowDataFrame(stream_obj) => {
@@ -229,111 +452,95 @@ fn write_worksheet_content(
             // not installed, and an all-Null empty frame has no Arrow type. The
             // reader is therefore built up front: while nothing has been
             // written yet, falling back to the column-by-column writer is safe.
-            // Once batches start landing a failure has to propagate, because
-            // re-writing from the top would duplicate rows.
-            let reader = crate::arrow_ffi::stream_to_reader(stream_obj, py);
-            let arrow_ok = match reader {
-                Err(stream_err) => Err(stream_err),
-                Ok(reader) => (|| -> PyResult<()> {
-                let schema = reader.schema();
-                final_headers = schema
-                    .fields()
-                    .iter()
-                    .map(|f| f.name().to_string())
-                    .collect();
-                for fc in &formula_cols {
-                    final_headers.push(fc.header.clone());
+            // Anything that fails once the stream is open propagates: the
+            // header and earlier batches may already be flushed, and an error
+            // such as `wide_decimals="error"` or `nested_columns="error"` must
+            // not be routed round through a writer that loses what it guards.
+            let (arrow, frame_start) = match resume {
+                // The stream already fell back to the column-by-column writer.
+                Some(Rest::Frame { start }) => (None, start),
+                Some(Rest::Arrow { reader, next, .. }) => (Some((Ok(reader), Some(next))), 0),
+                _ => {
+                    let reader = opts
+                        .columns
+                        .narrow(stream_obj, py)
+                        .and_then(|obj| crate::arrow_ffi::stream_to_reader(&obj, py))
+                        .map(|reader| crate::nested::explode_reader(reader, opts.nested))
+                        .and_then(|reader| opts.columns.project(reader, py));
+                    (Some((reader, None)), 0)
                 }
-                let n_data_cols = final_headers.len() - formula_cols.len();
-                write_all_headers(
-                    worksheet,
-                    header_row,
-                    &final_headers,
-                    bold_headers,
-                    &bold_fmt,
-                    index_columns,
-                    header_format.map(|h| &h.inner),
-                )?;
-
-                let mut current_row: u32 = layout.first_data_row();
-                let mut formats_set = false;
-
-                // Resolve per-column formats ONCE (after headers are known).
-                // Apply set_column_format BEFORE the first batch — constant memory mode requires
-                // column formats to be set before their data rows.
-                let col_formats: Vec<Option<crate::format::Format>> =
-                    crate::format::resolve_column_formats(column_formats, &final_headers, py)?;
-                let (plain, banded) = crate::format::build_palettes(
-                    &col_formats,
-                    float_fmt.as_ref(),
-                    &datetime_fmt,
-                    layout.band_color.as_deref(),
-                )?;
-                let url_cols =
-                    crate::helpers::resolve_url_columns(url_columns, &final_headers, py)?;
-
-                for batch_result in reader {
-                    let batch = batch_result.map_err(crate::arrow_ffi::batch_read_err)?;
-
-                    if !formats_set {
-                        // Auto datetime column formats first…
-                        crate::arrow_writer::set_datetime_column_formats(
+            };
+            let arrow_ok = match arrow {
+                None => Err(None),
+                Some((Err(stream_err), _)) => Err(Some(stream_err)),
+                Some((Ok(mut reader), pending)) => Ok((|| -> PyResult<Option<Rest<'py>>> {
+                    let mut arrow =
+                        prepare_arrow(workbook, worksheet, &reader.schema(), opts, &start, py)?;
+                    // From here to the last batch is pure Rust over Arrow
+                    // buffers, so the GIL is released and other Python threads
+                    // keep running. Anything that needs Python — the
+                    // truncation warning, image warnings — waits until it is
+                    // re-acquired.
+                    let streamed = py.detach(|| {
+                        arrow.write(
                             worksheet,
-                            &batch,
-                            &datetime_fmt,
-                        )?;
-                        // …then explicit column_formats override them (and any other cols).
-                        crate::format::apply_column_formats(worksheet, &col_formats)?;
-                        formats_set = true;
+                            &mut reader,
+                            pending,
+                            limit,
+                            layout,
+                            datetime_fmt,
+                            formula_cols,
+                        )
+                    })?;
+                    final_headers = arrow.headers;
+                    image_cols = arrow.image_cols;
+                    data_rows = streamed.rows;
+                    if let Some(count) = streamed.dropped {
+                        limit.warn_truncated(layout, count, py)?;
                     }
-
-                    crate::arrow_writer::write_arrow_batch(
-                        worksheet,
-                        &batch,
-                        current_row,
-                        &plain,
-                        banded.as_ref(),
-                        layout,
-                        &url_cols,
-                        &formula_cols,
-                        n_data_cols,
-                    )?;
-
-                    current_row += batch.num_rows() as u32;
-                }
-                data_rows = current_row - layout.first_data_row();
-                Ok(())
-                })(),
+                    let done = first + data_rows as usize;
+                    Ok(streamed.next.map(|next| Rest::Arrow { reader, next, done }))
+                })()?),
             };
 
-            if let Err(stream_err) = arrow_ok {
-                let df = stream_obj.bind(py);
-                // Polars exposes `get_column`; pandas indexes with `[]`.
-                let polars = df.hasattr("get_column")?;
-                if !df.hasattr("columns")? || !df.hasattr("dtypes")? {
-                    return Err(stream_err);
-                }
-                if polars {
-                    write_dataframe(
-                        worksheet, py, stream_obj, &mut final_headers, &mut data_rows,
-                        column_formats, float_fmt.as_ref(), &datetime_fmt,
-                        &mut datetime_cols_set, bold_headers, &bold_fmt, index_columns,
-                        header_format, layout, url_columns, &formula_cols,
-                        "get_column", "to_list",
-                        |dtype| Ok(polars_kind(&dtype.to_string())),
-                    )?;
-                } else {
-                    write_dataframe(
-                        worksheet, py, stream_obj, &mut final_headers, &mut data_rows,
-                        column_formats, float_fmt.as_ref(), &datetime_fmt,
-                        &mut datetime_cols_set, bold_headers, &bold_fmt, index_columns,
-                        header_format, layout, url_columns, &formula_cols,
-                        "__getitem__", "tolist",
-                        |dtype| {
-                            let kind: String = dtype.getattr("kind")?.extract()?;
-                            Ok(map_pandas_kind(kind.chars().next().unwrap_or('O')))
-                        },
-                    )?;
+            match arrow_ok {
+                Ok(arrow_rest) => rest = arrow_rest,
+                Err(stream_err) => {
+                    let df = stream_obj.bind(py);
+                    // Polars exposes `get_column`; pandas indexes with `[]`.
+                    let polars = df.hasattr("get_column")?;
+                    if !df.hasattr("columns")? || !df.hasattr("dtypes")? {
+                        return Err(stream_err.expect("a resumed frame has columns and dtypes"));
+                    }
+                    let next = if polars {
+                        write_dataframe(
+                            worksheet, py, stream_obj, frame_start, limit, &mut final_headers,
+                            &mut data_rows, column_formats, float_fmt.as_ref(), datetime_fmt,
+                            &mut datetime_cols_set, opts, &start, layout, url_columns, image_columns,
+                            &mut image_cols, comments, formula_cols, opts.durations, opts.nested,
+                            &opts.timezone, opts.int_overflow, id_columns, error_columns, &opts.reps,
+                            &opts.columns,
+                            "get_column",
+                            "to_list",
+                            |dtype| Ok(polars_kind(&dtype.to_string())),
+                        )?
+                    } else {
+                        write_dataframe(
+                            worksheet, py, stream_obj, frame_start, limit, &mut final_headers,
+                            &mut data_rows, column_formats, float_fmt.as_ref(), datetime_fmt,
+                            &mut datetime_cols_set, opts, &start, layout, url_columns, image_columns,
+                            &mut image_cols, comments, formula_cols, opts.durations, opts.nested,
+                            &opts.timezone, opts.int_overflow, id_columns, error_columns, &opts.reps,
+                            &opts.columns,
+                            "__getitem__",
+                            "tolist",
+                            |dtype| {
+                                let kind: String = dtype.getattr("kind")?.extract()?;
+                                Ok(map_pandas_kind(kind.chars().next().unwrap_or('O')))
+                            },
+                        )?
+                    };
+                    rest = next.map(|start| Rest::Frame { start });
                 }
             }
         }
@@ -341,12 +548,35 @@ fn write_worksheet_content(
         WorksheetData::Records(records_list) => {
             // Propagate rather than skip: a non-iterable input used to
             // produce an empty sheet and report success.
-            let rows = records_list.bind(py).try_iter().map_err(|_| {
-                PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                    "records must be an iterable of dicts, a DataFrame, or an Arrow stream",
-                )
-            })?;
-            let mut headers: Vec<String> = Vec::new();
+            // A continuation sheet keeps the headers of the sheet before it.
+            let (rows, first, source_headers) = match resume {
+                Some(Rest::Records {
+                    rows,
+                    next,
+                    headers,
+                }) => (rows, Some(next), headers),
+                _ => {
+                    let rows = records_list.bind(py).try_iter().map_err(|_| {
+                        PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                            "records must be an iterable of dicts, a DataFrame, or an Arrow stream",
+                        )
+                    })?;
+                    let (headers, rows) = opts.record_keys.headers(
+                        opts.columns.columns(),
+                        records_list.bind(py),
+                        rows,
+                        opts.nested,
+                    )?;
+                    (rows, None, headers)
+                }
+            };
+            let keys = crate::records::KeyMap::new(&opts.record_keys, &source_headers);
+            let labels = opts.columns.labels(&source_headers, &source_headers, py)?;
+            let mut slots = Vec::new();
+            let none = py.None().into_bound(py);
+            // A second handle on the same iterator, for whatever is left if
+            // this sheet fills up.
+            let remaining = rows.clone();
             let mut headers_written = false;
             let mut col_types: Vec<ColType> = Vec::new();
             // Resolved once when headers are first seen; kept alive for the
@@ -357,32 +587,45 @@ fn write_worksheet_content(
                 Option<crate::format::RowPalette>,
             )> = None;
             let mut url_cols: Vec<bool> = Vec::new();
+            let mut id_cols: Vec<bool> = Vec::new();
+            let mut error_cols: Vec<bool> = Vec::new();
+            let mut note_plan = crate::comment::CommentPlan::default();
+            let mut outline = layout.outline.rows();
             let mut n_data_cols: usize = 0;
+            let mut tz_mix = crate::timezone::TzMix::default();
 
-            for (row_idx, row_res) in rows.enumerate() {
+            for (row_idx, row_res) in first.map(Ok).into_iter().chain(rows).enumerate() {
                 let row_obj = row_res?;
-                let row_dict = row_obj.cast::<pyo3::types::PyDict>().map_err(|_| {
-                    PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                        "Items in records must be dictionaries",
-                    )
-                })?;
+                if row_idx as u32 == RowLimit::capacity(layout) {
+                    match limit.mode {
+                        Overflow::Error => return Err(limit.too_many_rows(layout)),
+                        Overflow::Split => {
+                            rest = Some(Rest::Records {
+                                rows: remaining,
+                                next: row_obj,
+                                headers: source_headers,
+                            });
+                        }
+                        Overflow::Truncate => {
+                            let mut dropped: u64 = 1;
+                            for later in remaining {
+                                later?;
+                                dropped += 1;
+                            }
+                            limit.warn_truncated(layout, dropped, py)?;
+                        }
+                    }
+                    break;
+                }
+                let row_dict = crate::records::as_record(&row_obj, opts.nested)?;
 
                 if !headers_written {
-                    for key in row_dict.keys().iter() {
-                        headers.push(key.extract::<String>()?);
-                    }
-                    for fc in &formula_cols {
+                    note_plan = comments.plan(&labels, layout.outline.column(), py)?;
+                    let mut headers = note_plan.output_headers(&labels);
+                    for fc in formula_cols {
                         headers.push(fc.header.clone());
                     }
-                    write_all_headers(
-                        worksheet,
-                        header_row,
-                        &headers,
-                        bold_headers,
-                        &bold_fmt,
-                        index_columns,
-                        header_format.map(|h| &h.inner),
-                    )?;
+                    opts.write_headers(worksheet, &start, &headers)?;
                     col_types.resize(headers.len(), ColType::Unknown);
                     final_headers = headers.clone();
                     n_data_cols = headers.len() - formula_cols.len();
@@ -394,11 +637,29 @@ fn write_worksheet_content(
                     palettes = Some(crate::format::build_palettes(
                         &col_formats,
                         float_fmt.as_ref(),
-                        &datetime_fmt,
+                        datetime_fmt,
                         layout.band_color.as_deref(),
                     )?);
-                    url_cols =
-                        crate::helpers::resolve_url_columns(url_columns, &final_headers, py)?;
+                    url_cols = crate::helpers::resolve_column_flags(
+                        "url_columns",
+                        url_columns,
+                        &final_headers,
+                        py,
+                    )?;
+                    id_cols = crate::helpers::resolve_column_flags(
+                        "id_columns",
+                        id_columns,
+                        &final_headers,
+                        py,
+                    )?;
+                    error_cols = crate::helpers::resolve_column_flags(
+                        "error_columns",
+                        error_columns,
+                        &final_headers,
+                        py,
+                    )?;
+                    image_cols =
+                        crate::image::ImageColumns::resolve(image_columns, &final_headers, py)?;
                     headers_written = true;
                 }
 
@@ -421,15 +682,40 @@ fn write_worksheet_content(
                     text_fmt: pal.text.as_ref(),
                     float_fmt: pal.float.as_ref(),
                     datetime_fmt: &pal.datetime,
+                    time_fmt: &pal.time,
+                    duration_fmt: &pal.duration,
+                    durations: opts.durations,
+                    nested: opts.nested,
+                    timezone: &opts.timezone,
+                    tz_mix: &mut tz_mix,
+                    int_overflow: opts.int_overflow,
+                    headers: &final_headers,
+                    reps: &opts.reps,
                     datetime_cols_set: &mut datetime_cols_set,
                     col_override: None,
                     per_cell_datetime: banding,
                     is_url: false,
+                    is_error: false,
                 };
 
-                // Iterate the dict directly (insertion order == header order)
-                // to avoid allocating a fresh `values()` list per row.
-                for (col, (_key, value)) in row_dict.iter().enumerate() {
+                // Values are matched to the headers by key; a key the row
+                // lacks is written as `None`.
+                keys.align(&row_dict, &mut slots, || format!("row {}", row_u32 + 1))?;
+                // This row's level settles the row above; see `RowOutline`.
+                if let Some(src) = note_plan.outline_col() {
+                    let level = match &slots[src] {
+                        Some(value) => crate::outline::level_py(value, row_u32)?,
+                        None => 0,
+                    };
+                    outline.push(&mut *sink.worksheet, row_u32, level)?;
+                }
+                for (src, value) in slots.drain(..).enumerate() {
+                    let value = value.unwrap_or_else(|| none.clone());
+                    let Some(col) = note_plan.output_col(src) else {
+                        note_plan.note_py(&mut *sink.worksheet, row_u32, src, &value)?;
+                        continue;
+                    };
+                    let col = col as usize;
                     let cached = col_types
                         .get(col)
                         .copied()
@@ -438,7 +724,29 @@ fn write_worksheet_content(
                     sink.col = col as u16;
                     // Column format override: wins over float_fmt / datetime_fmt.
                     sink.col_override = pal.col(col);
+                    if image_cols.is_image(col) {
+                        image_cols.write_py(
+                            &mut *sink.worksheet,
+                            row_u32,
+                            col as u16,
+                            &value,
+                            sink.col_override.or(sink.text_fmt),
+                        )?;
+                        continue;
+                    }
+                    if id_cols.get(col).copied().unwrap_or(false) {
+                        crate::helpers::write_py_id(
+                            &mut *sink.worksheet,
+                            row_u32,
+                            col as u16,
+                            &value,
+                            sink.col_override.or(sink.text_fmt),
+                            &opts.reps,
+                        )?;
+                        continue;
+                    }
                     sink.is_url = url_cols.get(col).copied().unwrap_or(false);
+                    sink.is_error = error_cols.get(col).copied().unwrap_or(false);
 
                     if !try_cached(&value, cached, &mut sink)? {
                         let detected = classify_and_write(&value, &mut sink)?;
@@ -450,36 +758,52 @@ fn write_worksheet_content(
                 if !formula_cols.is_empty() {
                     crate::helpers::write_formula_row(
                         worksheet,
-                        &formula_cols,
+                        formula_cols,
                         n_data_cols as u16,
                         row_u32,
-                        first_data_row,
+                        layout.first_data_row(),
                         None,
                     )?;
                 }
                 data_rows = row_idx as u32 + 1;
             }
-
+            outline.finish(worksheet)?;
+            tz_mix.check(&final_headers, &opts.timezone, py)?;
         }
 
         WorksheetData::PandasDataFrame(df) => {
-            write_dataframe(
+            let frame_start = match resume {
+                Some(Rest::Frame { start }) => start,
+                _ => 0,
+            };
+            let next = write_dataframe(
                 worksheet,
                 py,
                 df,
+                frame_start,
+                limit,
                 &mut final_headers,
                 &mut data_rows,
                 column_formats,
                 float_fmt.as_ref(),
-                &datetime_fmt,
+                datetime_fmt,
                 &mut datetime_cols_set,
-                bold_headers,
-                &bold_fmt,
-                index_columns,
-                header_format,
+                opts,
+                &start,
                 layout,
                 url_columns,
-                &formula_cols,
+                image_columns,
+                &mut image_cols,
+                comments,
+                formula_cols,
+                opts.durations,
+                opts.nested,
+                &opts.timezone,
+                opts.int_overflow,
+                id_columns,
+                error_columns,
+                &opts.reps,
+                &opts.columns,
                 "__getitem__",
                 "tolist",
                 |dtype| {
@@ -487,58 +811,433 @@ fn write_worksheet_content(
                     Ok(map_pandas_kind(kind.chars().next().unwrap_or('O')))
                 },
             )?;
+            rest = next.map(|start| Rest::Frame { start });
         }
 
         WorksheetData::PolarsDataFrame(df) => {
-            write_dataframe(
+            let frame_start = match resume {
+                Some(Rest::Frame { start }) => start,
+                _ => 0,
+            };
+            let next = write_dataframe(
                 worksheet,
                 py,
                 df,
+                frame_start,
+                limit,
                 &mut final_headers,
                 &mut data_rows,
                 column_formats,
                 float_fmt.as_ref(),
-                &datetime_fmt,
+                datetime_fmt,
                 &mut datetime_cols_set,
-                bold_headers,
-                &bold_fmt,
-                index_columns,
-                header_format,
+                opts,
+                &start,
                 layout,
                 url_columns,
-                &formula_cols,
+                image_columns,
+                &mut image_cols,
+                comments,
+                formula_cols,
+                opts.durations,
+                opts.nested,
+                &opts.timezone,
+                opts.int_overflow,
+                id_columns,
+                error_columns,
+                &opts.reps,
+                &opts.columns,
                 "get_column",
                 "to_list",
                 |dtype| Ok(polars_kind(&dtype.to_string())),
             )?;
+            rest = next.map(|start| Rest::Frame { start });
         }
     }
 
+    let data = SheetData {
+        headers: final_headers,
+        rows: data_rows,
+        image_cols,
+    };
+    let charts = finish_sheet(worksheet, opts, &start, data, py)?;
+    Ok((charts, rest))
+}
+
+/// Everything that depends on the final row count, and the sheet-wide
+/// settings that close a sheet.
+fn finish_sheet(
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    opts: &SheetOptions,
+    start: &SheetStart,
+    data: SheetData,
+    py: Python,
+) -> PyResult<Vec<crate::chart::PlacedChart>> {
+    let SheetData {
+        headers: final_headers,
+        rows: data_rows,
+        image_cols,
+    } = data;
+    let layout = &opts.layout;
+    image_cols.warn_failures(&final_headers, py)?;
+    crate::frame_index::clear_merged(worksheet, &start.index_merges.take())?;
+
     layout.apply_autofilter(worksheet, data_rows, final_headers.len())?;
-    layout.apply_totals(worksheet, &final_headers, data_rows, py)?;
+    match &opts.table {
+        // The table writes the totals row itself, as structured references.
+        Some(spec) => crate::table::apply_table(
+            worksheet,
+            spec,
+            &final_headers,
+            &start.formula_cols,
+            layout,
+            data_rows,
+            opts.header_format
+                .as_ref()
+                .map(|h| &h.inner)
+                .or(opts.bold_headers.then_some(&start.bold_fmt)),
+            py,
+        )?,
+        None => layout.apply_totals(worksheet, &final_headers, data_rows, py)?,
+    }
+    crate::conditional::apply_conditional_formats(
+        worksheet,
+        &start.cond_formats,
+        &final_headers,
+        layout,
+        data_rows,
+        py,
+    )?;
+    crate::validation::apply_data_validation(
+        worksheet,
+        &start.validations,
+        &final_headers,
+        layout,
+        data_rows,
+        py,
+    )?;
+    crate::image::apply_images(worksheet, &start.floating_images)?;
+    crate::helpers::ignore_id_warnings(
+        worksheet,
+        opts.id_columns.as_ref(),
+        &final_headers,
+        layout,
+        data_rows,
+    )?;
+    start.comments.apply_cells(worksheet)?;
+    let charts = crate::chart::build_charts(
+        &start.chart_specs,
+        worksheet.name().as_str(),
+        &final_headers,
+        layout,
+        data_rows,
+        py,
+    )?;
 
-    if freeze_row.is_some() || freeze_col.is_some() {
+    if opts.freeze_row.is_some() || opts.freeze_col.is_some() {
         worksheet
-            .set_freeze_panes(freeze_row.unwrap_or(0), freeze_col.unwrap_or(0))
+            .set_freeze_panes(
+                layout.freeze_row(opts.freeze_row.unwrap_or(0)),
+                opts.freeze_col.unwrap_or(0),
+            )
             .map_err(xlsx_err)?;
     }
 
-    if autofit {
+    if opts.autofit {
         worksheet.autofit();
     }
     crate::helpers::apply_column_widths(
         worksheet,
         &final_headers,
-        column_width,
-        column_widths,
+        opts.column_width,
+        opts.column_widths.as_ref(),
         py,
     )?;
 
-    if let Some(password) = password {
+    if let Some(password) = &opts.password {
         worksheet.protect_with_password(password);
     }
 
-    Ok(())
+    Ok(charts)
+}
+
+/// An Arrow sheet between its header row and its first batch: what
+/// [`ArrowSheet::write`] needs, resolved while the GIL is held.
+struct ArrowSheet {
+    headers: Vec<String>,
+    note_plan: crate::comment::CommentPlan,
+    /// Source columns that hold data rather than notes or row levels.
+    kept: Vec<usize>,
+    outline: crate::outline::RowOutline,
+    col_formats: Vec<Option<crate::format::Format>>,
+    plain: crate::format::RowPalette,
+    banded: Option<crate::format::RowPalette>,
+    url_cols: Vec<bool>,
+    id_cols: Vec<bool>,
+    error_cols: Vec<bool>,
+    image_cols: crate::image::ImageColumns,
+    n_data_cols: usize,
+    wide_decimals: crate::arrow_writer::WideDecimals,
+    durations: crate::helpers::Durations,
+    nested: crate::nested::NestedColumns,
+    /// Per data column, the zone its timestamps are shown in.
+    zones: Vec<Option<std::sync::Arc<crate::timezone::Zone>>>,
+    int_overflow: crate::helpers::IntOverflow,
+    reps: crate::missing::Reps,
+    /// The formats registered up front, checked in debug builds.
+    registered: crate::parallel::Registered,
+}
+
+/// Write the header row of an Arrow sheet and resolve its column settings.
+///
+/// A constant-memory sheet also registers every format its batches and
+/// totals row can use, so the workbook's format numbering is fixed before
+/// any data is written — see [`crate::arrow_writer::reachable_formats`].
+fn prepare_arrow(
+    workbook: &mut Workbook,
+    worksheet: &mut rust_xlsxwriter::Worksheet,
+    schema: &arrow_schema::Schema,
+    opts: &SheetOptions,
+    start: &SheetStart,
+    py: Python,
+) -> PyResult<ArrowSheet> {
+    crate::arrow_writer::check_nested(schema, opts.nested)?;
+    let source_headers: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect();
+    let note_plan = start
+        .comments
+        .plan(&source_headers, opts.layout.outline.column(), py)?;
+    let kept = note_plan.kept(source_headers.len());
+    let mut headers = note_plan.output_headers(&source_headers);
+    for fc in &start.formula_cols {
+        headers.push(fc.header.clone());
+    }
+    let n_data_cols = headers.len() - start.formula_cols.len();
+    opts.write_headers(worksheet, start, &headers)?;
+
+    // Resolve per-column formats ONCE (after headers are known).
+    // They are applied before the first batch, in `ArrowSheet::write`.
+    let col_formats: Vec<Option<crate::format::Format>> =
+        crate::format::resolve_column_formats(opts.column_formats.as_ref(), &headers, py)?;
+    let (plain, banded) = crate::format::build_palettes(
+        &col_formats,
+        start.float_fmt.as_ref(),
+        &start.datetime_fmt,
+        opts.layout.band_color.as_deref(),
+    )?;
+    let url_cols = crate::helpers::resolve_column_flags(
+        "url_columns",
+        opts.url_columns.as_ref(),
+        &headers,
+        py,
+    )?;
+    let id_cols =
+        crate::helpers::resolve_column_flags("id_columns", opts.id_columns.as_ref(), &headers, py)?;
+    let error_cols = crate::helpers::resolve_column_flags(
+        "error_columns",
+        opts.error_columns.as_ref(),
+        &headers,
+        py,
+    )?;
+    let image_cols =
+        crate::image::ImageColumns::resolve(opts.image_columns.as_ref(), &headers, py)?;
+
+    let data_schema = schema
+        .project(&kept)
+        .map_err(crate::arrow_ffi::batch_read_err)?;
+    let zones = opts.timezone.arrow_zones(py, &data_schema)?;
+
+    // A buffered sheet numbers its formats itself, in order, at save.
+    let mut registered = crate::parallel::Registered::default();
+    if !opts.buffered {
+        let formats = crate::arrow_writer::reachable_formats(
+            &data_schema,
+            &start.datetime_fmt,
+            &col_formats,
+            &plain,
+            banded.as_ref(),
+            &url_cols,
+            &id_cols,
+            opts.durations,
+        );
+        for fmt in formats.iter().chain(&opts.layout.totals_format) {
+            workbook.register_format(fmt);
+        }
+        registered = crate::parallel::Registered::new(&formats);
+    }
+
+    Ok(ArrowSheet {
+        headers,
+        note_plan,
+        kept,
+        outline: opts.layout.outline.rows(),
+        col_formats,
+        plain,
+        banded,
+        url_cols,
+        id_cols,
+        error_cols,
+        image_cols,
+        n_data_cols,
+        wide_decimals: opts.wide_decimals,
+        durations: opts.durations,
+        nested: opts.nested,
+        zones,
+        int_overflow: opts.int_overflow,
+        reps: opts.reps.clone(),
+        registered,
+    })
+}
+
+/// What [`ArrowSheet::write`] got through before the stream ended or the sheet
+/// filled up.
+struct Streamed {
+    /// Data rows written.
+    rows: u32,
+    /// The part of a batch that did not fit, under `overflow="split"`.
+    next: Option<RecordBatch>,
+    /// Rows dropped under `overflow="truncate"`.
+    dropped: Option<u64>,
+}
+
+impl ArrowSheet {
+    /// The Arrow batch loop. Touches no Python object, so the caller runs it
+    /// with the GIL released; `pending` is a batch left over from the previous
+    /// sheet.
+    #[allow(clippy::too_many_arguments)]
+    fn write(
+        &mut self,
+        worksheet: &mut rust_xlsxwriter::Worksheet,
+        batches: &mut impl Iterator<Item = Result<RecordBatch, arrow_schema::ArrowError>>,
+        pending: Option<RecordBatch>,
+        limit: &RowLimit,
+        layout: &crate::helpers::SheetLayout,
+        datetime_fmt: &Format,
+        formula_cols: &[crate::helpers::FormulaColumn],
+    ) -> PyResult<Streamed> {
+        let registered = std::mem::take(&mut self.registered);
+        let streamed = registered.during(|| {
+            self.write_batches(
+                worksheet,
+                batches,
+                pending,
+                limit,
+                layout,
+                datetime_fmt,
+                formula_cols,
+            )
+        });
+        self.registered = registered;
+        streamed
+    }
+
+    /// [`ArrowSheet::write`] with the registered formats checked.
+    #[allow(clippy::too_many_arguments)]
+    fn write_batches(
+        &mut self,
+        worksheet: &mut rust_xlsxwriter::Worksheet,
+        batches: &mut impl Iterator<Item = Result<RecordBatch, arrow_schema::ArrowError>>,
+        mut pending: Option<RecordBatch>,
+        limit: &RowLimit,
+        layout: &crate::helpers::SheetLayout,
+        datetime_fmt: &Format,
+        formula_cols: &[crate::helpers::FormulaColumn],
+    ) -> PyResult<Streamed> {
+        let mut current_row: u32 = layout.first_data_row();
+        let mut formats_set = false;
+        let mut overflow_batch = None;
+        let mut dropped: Option<u64> = None;
+
+        // Not a `for` loop: the reader has to stay free to move into the
+        // `Rest` when the sheet fills mid-batch.
+        loop {
+            let Some(batch_result) = pending.take().map(Ok).or_else(|| batches.next()) else {
+                break;
+            };
+            let mut batch = batch_result.map_err(crate::arrow_ffi::batch_read_err)?;
+            let capacity = RowLimit::capacity(layout);
+            let room = (capacity - (current_row - layout.first_data_row())) as usize;
+            if batch.num_rows() > room {
+                let over = batch.num_rows() - room;
+                match limit.mode {
+                    Overflow::Error => return Err(limit.too_many_rows(layout)),
+                    Overflow::Split => overflow_batch = Some(batch.slice(room, over)),
+                    Overflow::Truncate => {
+                        let mut count = over as u64;
+                        for later in batches.by_ref() {
+                            count += later.map_err(crate::arrow_ffi::batch_read_err)?.num_rows() as u64;
+                        }
+                        dropped = Some(count);
+                    }
+                }
+                batch = batch.slice(0, room);
+            }
+            // Note columns become notes and the outline column row levels,
+            // then both are projected away so the writer only ever sees data
+            // columns.
+            let batch = if self.note_plan.drops_columns() {
+                for &(src, target) in self.note_plan.note_columns() {
+                    self.note_plan.note_arrow(worksheet, current_row, target, batch.column(src))?;
+                }
+                if let Some(src) = self.note_plan.outline_col() {
+                    let levels = crate::outline::levels_arrow(batch.column(src), current_row)?;
+                    for (i, level) in levels.into_iter().enumerate() {
+                        self.outline.push(worksheet, current_row + i as u32, level)?;
+                    }
+                }
+                batch
+                    .project(&self.kept)
+                    .map_err(crate::arrow_ffi::batch_read_err)?
+            } else {
+                batch
+            };
+
+            // Constant memory mode requires column formats to be set before their
+            // data rows.
+            if !formats_set {
+                // Auto datetime column formats first…
+                crate::arrow_writer::set_datetime_column_formats(worksheet, &batch, datetime_fmt)?;
+                // …then explicit column_formats override them (and any other cols).
+                crate::format::apply_column_formats(worksheet, &self.col_formats)?;
+                formats_set = true;
+            }
+
+            crate::arrow_writer::write_arrow_batch(
+                worksheet,
+                &batch,
+                current_row,
+                &self.plain,
+                self.banded.as_ref(),
+                layout,
+                &self.url_cols,
+                &mut self.image_cols,
+                formula_cols,
+                self.n_data_cols,
+                self.wide_decimals,
+                self.durations,
+                self.nested,
+                &self.zones,
+                self.int_overflow,
+                &self.id_cols,
+                &self.error_cols,
+                &self.reps,
+            )?;
+
+            current_row += batch.num_rows() as u32;
+            if overflow_batch.is_some() || dropped.is_some() {
+                break;
+            }
+        }
+        self.outline.finish(worksheet)?;
+        Ok(Streamed {
+            rows: current_row - layout.first_data_row(),
+            next: overflow_batch,
+            dropped,
+        })
+    }
 }
 
 fn map_pandas_kind(kind: char) -> ScalarKind {
@@ -580,7 +1279,7 @@ impl<'py> ColAccess<'py> {
 fn write_df_rows<F>(
     worksheet: &mut rust_xlsxwriter::Worksheet,
     py: Python,
-    nrows: usize,
+    rows: std::ops::Range<usize>,
     col_lists: &[Py<PyAny>],
     kind_at: F,
     datetime_cols_set: &mut HashSet<u16>,
@@ -588,8 +1287,18 @@ fn write_df_rows<F>(
     banded: Option<&crate::format::RowPalette>,
     layout: &crate::helpers::SheetLayout,
     url_cols: &[bool],
+    image_cols: &mut crate::image::ImageColumns,
     formula_cols: &[crate::helpers::FormulaColumn],
     n_data_cols: usize,
+    durations: crate::helpers::Durations,
+    nested: crate::nested::NestedColumns,
+    timezone: &crate::timezone::Timezone,
+    tz_mix: &mut crate::timezone::TzMix,
+    int_overflow: crate::helpers::IntOverflow,
+    id_cols: &[bool],
+    error_cols: &[bool],
+    headers: &[String],
+    reps: &crate::missing::Reps,
 ) -> PyResult<()>
 where
     F: Fn(usize) -> ScalarKind,
@@ -615,8 +1324,9 @@ where
     };
     let banding = layout.band_color.is_some();
 
-    for row in 0..nrows {
-        let row_u32 = layout.first_data_row() + row as u32;
+    let start = rows.start;
+    for row in rows {
+        let row_u32 = layout.first_data_row() + (row - start) as u32;
         let use_band = layout.is_banded(row_u32);
         let pal = if use_band { banded.unwrap_or(plain) } else { plain };
         let overrides = if use_band && banded.is_some() {
@@ -632,19 +1342,41 @@ where
             let col_override = overrides[col_idx];
 
             if item.is_none() {
-                write_string_opt(worksheet, row_u32, col_u16, "", text_fmt)?;
+                write_special(worksheet, row_u32, col_u16, Special::Missing, reps, text_fmt)?;
+                continue;
+            }
+            if image_cols.is_image(col_idx) {
+                image_cols.write_py(
+                    worksheet,
+                    row_u32,
+                    col_u16,
+                    &item,
+                    col_override.or(text_fmt),
+                )?;
+                continue;
+            }
+            if id_cols.get(col_idx).copied().unwrap_or(false) {
+                crate::helpers::write_py_id(
+                    worksheet,
+                    row_u32,
+                    col_u16,
+                    &item,
+                    col_override.or(text_fmt),
+                    reps,
+                )?;
                 continue;
             }
 
             match kind_at(col_idx) {
                 ScalarKind::Int => {
-                    let val: f64 = item.extract()?;
-                    write_number_opt(
+                    crate::helpers::write_py_int(
                         worksheet,
                         row_u32,
                         col_u16,
-                        val,
+                        &item,
+                        int_overflow,
                         col_override.or(text_fmt),
+                        headers.get(col_idx).map_or("", String::as_str),
                     )?;
                 }
                 ScalarKind::Float => {
@@ -655,6 +1387,7 @@ where
                         col_u16,
                         val,
                         col_override.or(pal.float.as_ref()),
+                        reps,
                     )?;
                 }
                 ScalarKind::Bool => {
@@ -666,10 +1399,16 @@ where
                     // column format cannot alternate between rows.
                     let dt_fmt = banding.then(|| col_override.unwrap_or(&pal.datetime));
                     if let Ok(dt) = item.cast::<PyDateTime>() {
-                        let excel_dt = py_datetime_to_excel(&dt)?;
-                        write_datetime_opt(worksheet, row_u32, col_u16, &excel_dt, dt_fmt)?;
+                        if is_nat(dt) {
+                            let missing = Special::Missing;
+                            write_special(worksheet, row_u32, col_u16, missing, reps, text_fmt)?;
+                        } else {
+                            tz_mix.see(col_idx, dt)?;
+                            let excel_dt = py_datetime_to_excel(&timezone.wall(dt)?)?;
+                            write_datetime_opt(worksheet, row_u32, col_u16, &excel_dt, dt_fmt)?;
+                        }
                     } else if let Ok(d) = item.cast::<PyDate>() {
-                        let excel_dt = py_date_to_excel(&d)?;
+                        let excel_dt = py_date_to_excel(d)?;
                         write_datetime_opt(worksheet, row_u32, col_u16, &excel_dt, dt_fmt)?;
                     } else {
                         write_string_opt(
@@ -689,10 +1428,20 @@ where
                         text_fmt,
                         float_fmt: pal.float.as_ref(),
                         datetime_fmt: &pal.datetime,
+                        time_fmt: &pal.time,
+                        duration_fmt: &pal.duration,
+                        durations,
+                        nested,
+                        timezone,
+                        tz_mix: &mut *tz_mix,
+                        int_overflow,
+                        headers,
+                        reps,
                         datetime_cols_set: &mut *datetime_cols_set,
                         col_override,
                         per_cell_datetime: banding,
                         is_url: url_cols.get(col_idx).copied().unwrap_or(false),
+                        is_error: error_cols.get(col_idx).copied().unwrap_or(false),
                     };
                     classify_and_write(&item, &mut sink)?;
                 }
@@ -720,67 +1469,122 @@ where
 /// - `get_column_method`: `"__getitem__"` (Pandas) or `"get_column"` (Polars)
 /// - `to_list_method`: `"tolist"` (Pandas) or `"to_list"` (Polars)
 /// - `classify_dtype`: maps one dtype object to a `ScalarKind`
+///
+/// Writes rows from `start` on, as many as `limit` allows, and returns the
+/// first row left over when the frame continues on another sheet.
 #[allow(clippy::too_many_arguments)]
 fn write_dataframe<C>(
     worksheet: &mut rust_xlsxwriter::Worksheet,
     py: Python,
     df: &Py<PyAny>,
+    start: usize,
+    limit: &RowLimit,
     final_headers: &mut Vec<String>,
     data_rows: &mut u32,
     column_formats: Option<&Bound<'_, PyAny>>,
     float_fmt: Option<&Format>,
     datetime_fmt: &Format,
     datetime_cols_set: &mut HashSet<u16>,
-    bold_headers: bool,
-    bold_fmt: &Format,
-    index_columns: Option<&Vec<String>>,
-    header_format: Option<&crate::format::Format>,
+    opts: &SheetOptions,
+    sheet_start: &SheetStart,
     layout: &crate::helpers::SheetLayout,
     url_columns: Option<&Vec<String>>,
+    image_columns: Option<&Vec<String>>,
+    image_cols: &mut crate::image::ImageColumns,
+    comments: &crate::comment::Comments,
     formula_cols: &[crate::helpers::FormulaColumn],
+    durations: crate::helpers::Durations,
+    nested: crate::nested::NestedColumns,
+    timezone: &crate::timezone::Timezone,
+    int_overflow: crate::helpers::IntOverflow,
+    id_columns: Option<&Vec<String>>,
+    error_columns: Option<&Vec<String>>,
+    reps: &crate::missing::Reps,
+    selection: &crate::columns::ColumnSelection,
     get_column_method: &str,
     to_list_method: &str,
     classify_dtype: C,
-) -> PyResult<()>
+) -> PyResult<Option<usize>>
 where
     C: Fn(&Bound<'_, PyAny>) -> PyResult<ScalarKind>,
 {
-    let headers: Vec<String> = df.getattr(py, "columns")?.extract(py)?;
+    let source_headers: Vec<String> = df.getattr(py, "columns")?.extract(py)?;
+    let (picked, labels) = selection.pick(&source_headers, py)?;
+    let note_plan = comments.plan(&labels, layout.outline.column(), py)?;
+    let headers = note_plan.output_headers(&labels);
     let n_data_cols = headers.len();
     *final_headers = headers.clone();
     for fc in formula_cols {
         final_headers.push(fc.header.clone());
     }
+    // Header groups can move the header row, and with it the room left.
+    layout.place_header(final_headers)?;
+
+    // The length is known up front, so an overflow is settled before any
+    // cell is written.
+    let nrows: usize = df.call_method0(py, "__len__")?.extract(py)?;
+    let remaining = nrows.saturating_sub(start);
+    let count = remaining.min(RowLimit::capacity(layout) as usize);
+    let next = if remaining > count {
+        match limit.mode {
+            Overflow::Error => return Err(limit.too_many_rows(layout)),
+            Overflow::Split => Some(start + count),
+            Overflow::Truncate => {
+                limit.warn_truncated(layout, (remaining - count) as u64, py)?;
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let rows = start..start + count;
+
     let dtypes = df.getattr(py, "dtypes")?;
     let dtypes_list: Vec<Bound<'_, PyAny>> =
         dtypes.bind(py).try_iter()?.collect::<Result<Vec<_>, _>>()?;
 
-    write_all_headers(
-        worksheet,
-        layout.header_row,
-        final_headers,
-        bold_headers,
-        bold_fmt,
-        index_columns,
-        header_format.map(|h| &h.inner),
-    )?;
+    opts.write_headers(worksheet, sheet_start, final_headers)?;
 
     let mut col_kinds: Vec<ScalarKind> = Vec::with_capacity(headers.len());
     let mut col_lists: Vec<Py<PyAny>> = Vec::with_capacity(headers.len());
+    let mut note_lists: Vec<(usize, Py<PyAny>)> = Vec::new();
 
     // NOTE: `to_list`/`tolist` materializes each column as a full Python list,
     // so peak memory here is O(rows) per column — this path is NOT constant
     // memory. It is only reached when the Arrow zero-copy path is unavailable
     // (old pandas without `__arrow_c_stream__`, or exotic dtypes). Modern
     // pandas ≥2 and Polars hit the Arrow path in `data_types.rs` instead.
-    for (col_idx, header) in headers.iter().enumerate() {
-        col_kinds.push(classify_dtype(&dtypes_list[col_idx])?);
-        let col_series = df.call_method1(py, get_column_method, (header.as_str(),))?;
-        col_lists.push(col_series.call_method0(py, to_list_method)?);
+    // Only the picked columns are fetched and converted.
+    for (col_idx, &src) in picked.iter().enumerate() {
+        let header = source_headers[src].as_str();
+        let col_series = df.call_method1(py, get_column_method, (header,))?;
+        let values = col_series.call_method0(py, to_list_method)?;
+        if note_plan.output_col(col_idx).is_some() {
+            col_kinds.push(classify_dtype(&dtypes_list[src])?);
+            col_lists.push(values);
+        } else {
+            note_lists.push((col_idx, values));
+        }
     }
 
-    let nrows: usize = df.call_method0(py, "__len__")?.extract(py)?;
-    *data_rows = nrows as u32;
+    *data_rows = count as u32;
+    let mut outline = layout.outline.rows();
+    for (col_idx, values) in &note_lists {
+        let values = values.bind(py);
+        let levels = note_plan.outline_col() == Some(*col_idx);
+        for row in rows.clone() {
+            let row_u32 = layout.first_data_row() + (row - start) as u32;
+            let value = values.get_item(row)?;
+            if levels {
+                let level = crate::outline::level_py(&value, row_u32)?;
+                outline.push(worksheet, row_u32, level)?;
+            } else {
+                note_plan.note_py(worksheet, row_u32, *col_idx, &value)?;
+            }
+        }
+    }
+    // Every row is still to be written, so the last level can go in now.
+    outline.finish(worksheet)?;
 
     // Auto datetime column formats first, then explicit column_formats
     // override (constant memory: BEFORE writing data rows).
@@ -801,12 +1605,19 @@ where
         layout.band_color.as_deref(),
     )?;
 
-    let url_cols = crate::helpers::resolve_url_columns(url_columns, final_headers, py)?;
+    let url_cols =
+        crate::helpers::resolve_column_flags("url_columns", url_columns, final_headers, py)?;
+    let id_cols =
+        crate::helpers::resolve_column_flags("id_columns", id_columns, final_headers, py)?;
+    let error_cols =
+        crate::helpers::resolve_column_flags("error_columns", error_columns, final_headers, py)?;
+    *image_cols = crate::image::ImageColumns::resolve(image_columns, final_headers, py)?;
 
+    let mut tz_mix = crate::timezone::TzMix::default();
     write_df_rows(
         worksheet,
         py,
-        nrows,
+        rows,
         &col_lists,
         |col_idx| col_kinds[col_idx],
         datetime_cols_set,
@@ -814,9 +1625,21 @@ where
         banded.as_ref(),
         layout,
         &url_cols,
+        image_cols,
         formula_cols,
         n_data_cols,
-    )
+        durations,
+        nested,
+        timezone,
+        &mut tz_mix,
+        int_overflow,
+        &id_cols,
+        &error_cols,
+        final_headers,
+        reps,
+    )?;
+    tz_mix.check(final_headers, timezone, py)?;
+    Ok(next)
 }
 
 /// Resolve a per-sheet value from a dict keyed by sheet name, falling back
@@ -860,9 +1683,83 @@ fn keyed_get<'py>(
     dict.get_item("general")
 }
 
+/// A sheet in constant-memory mode unless something on it must stay editable
+/// until save. Standalone, so several can be written at once; the caller
+/// pushes each onto the workbook in order.
+fn new_sheet(workbook: &mut Workbook, buffered: bool) -> rust_xlsxwriter::Worksheet {
+    if buffered {
+        rust_xlsxwriter::Worksheet::new()
+    } else {
+        workbook.new_worksheet_with_constant_memory()
+    }
+}
+
+/// A table is a single range on a single sheet, so it cannot be continued.
+fn ensure_splittable(mode: Overflow, has_table: bool) -> PyResult<()> {
+    if mode == Overflow::Split && has_table {
+        return Err(crate::helpers::value_err(
+            "overflow=\"split\" cannot be combined with table: a table cannot span sheets"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A sheet of `write_worksheets`, kept in the order given.
+enum Slot<'py> {
+    /// Written in full: the sheet, any continuation sheets, and its charts.
+    Written(Vec<rust_xlsxwriter::Worksheet>, Vec<crate::chart::PlacedChart>),
+    /// An Arrow sheet whose batches are left for the thread pool.
+    Queued(Box<QueuedSheet<'py>>),
+}
+
+struct QueuedSheet<'py> {
+    opts: SheetOptions<'py>,
+    start: SheetStart,
+    job: BatchJob,
+}
+
+/// The part of a queued sheet a pool thread works on.
+struct BatchJob {
+    worksheet: rust_xlsxwriter::Worksheet,
+    arrow: ArrowSheet,
+    batches: Vec<RecordBatch>,
+    streamed: Option<PyResult<Streamed>>,
+}
+
+/// Set up an Arrow sheet for `parallel=True`: everything up to its first
+/// batch, then the whole stream read into memory with the GIL still held, so
+/// the pool only ever touches Rust data.
+fn queue_sheet<'py>(
+    workbook: &mut Workbook,
+    sheet_name: &str,
+    mut reader: Box<dyn RecordBatchReader + Send>,
+    opts: SheetOptions<'py>,
+    py: Python<'py>,
+) -> PyResult<QueuedSheet<'py>> {
+    let mut worksheet = new_sheet(workbook, opts.buffered);
+    worksheet.set_name(sheet_name).map_err(xlsx_err)?;
+    let start = begin_sheet(&mut worksheet, &opts, 0)?;
+    let arrow = prepare_arrow(workbook, &mut worksheet, &reader.schema(), &opts, &start, py)?;
+    let batches = reader
+        .by_ref()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(crate::arrow_ffi::batch_read_err)?;
+    Ok(QueuedSheet {
+        opts,
+        start,
+        job: BatchJob {
+            worksheet,
+            arrow,
+            batches,
+            streamed: None,
+        },
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 #[pyfunction]
-#[pyo3(signature = (records_with_sheet_name, file_name, password = None, freeze_panes = None, float_format = None, datetime_format = None, index_columns = None, autofit = true, bold_headers = false, column_width = None, column_widths = None, column_formats = None, header_format = None, dedupe_strings = None, header_row = None, merge_ranges = None, row_heights = None, row_formats = None, banded_rows = None, autofilter = None, url_columns = None, totals_row = None, totals_label = None, totals_format = None, formula_columns = None))]
+#[pyo3(signature = (records_with_sheet_name, file_name, password = None, freeze_panes = None, float_format = None, datetime_format = None, index_columns = None, autofit = true, bold_headers = false, column_width = None, column_widths = None, column_formats = None, header_format = None, dedupe_strings = None, header_row = None, merge_ranges = None, row_heights = None, row_formats = None, banded_rows = None, autofilter = None, url_columns = None, totals_row = None, totals_label = None, totals_format = None, formula_columns = None, conditional_formats = None, data_validation = None, table = None, charts = None, image_columns = None, images = None, comments = None, properties = None, reproducible = false, overflow = None, parallel = false, max_threads = None, wide_decimals = None, durations = None, nested_columns = None, timezone = None, int_overflow = None, id_columns = None, na_rep = None, nan_rep = None, inf_rep = None, error_columns = None, columns = None, missing = None, extra_keys = None, scan_rows = None, rename = None, header_groups = None, index = None, merge_index_cells = None, row_groups = None, column_groups = None, outline_column = None, outline_collapsed = None))]
 pub fn write_worksheets(
     py: Python,
     records_with_sheet_name: Vec<(String, WorksheetData)>,
@@ -890,20 +1787,159 @@ pub fn write_worksheets(
     totals_label: Option<Bound<'_, pyo3::types::PyDict>>,
     totals_format: Option<Bound<'_, pyo3::types::PyDict>>,
     formula_columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    conditional_formats: Option<Bound<'_, pyo3::types::PyDict>>,
+    data_validation: Option<Bound<'_, pyo3::types::PyDict>>,
+    table: Option<Bound<'_, pyo3::types::PyDict>>,
+    charts: Option<Bound<'_, pyo3::types::PyDict>>,
+    image_columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    images: Option<Bound<'_, pyo3::types::PyDict>>,
+    comments: Option<Bound<'_, pyo3::types::PyDict>>,
+    properties: Option<Bound<'_, PyAny>>,
+    reproducible: bool,
+    overflow: Option<Bound<'_, pyo3::types::PyDict>>,
+    parallel: bool,
+    max_threads: Option<usize>,
+    wide_decimals: Option<Bound<'_, pyo3::types::PyDict>>,
+    durations: Option<Bound<'_, pyo3::types::PyDict>>,
+    nested_columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    timezone: Option<Bound<'_, pyo3::types::PyDict>>,
+    int_overflow: Option<Bound<'_, pyo3::types::PyDict>>,
+    id_columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    na_rep: Option<Bound<'_, pyo3::types::PyDict>>,
+    nan_rep: Option<Bound<'_, pyo3::types::PyDict>>,
+    inf_rep: Option<Bound<'_, pyo3::types::PyDict>>,
+    error_columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    columns: Option<Bound<'_, pyo3::types::PyDict>>,
+    missing: Option<Bound<'_, pyo3::types::PyDict>>,
+    extra_keys: Option<Bound<'_, pyo3::types::PyDict>>,
+    scan_rows: Option<Bound<'_, pyo3::types::PyDict>>,
+    rename: Option<Bound<'_, pyo3::types::PyDict>>,
+    header_groups: Option<Bound<'_, pyo3::types::PyDict>>,
+    index: Option<Bound<'_, pyo3::types::PyDict>>,
+    merge_index_cells: Option<Bound<'_, pyo3::types::PyDict>>,
+    row_groups: Option<Bound<'_, pyo3::types::PyDict>>,
+    column_groups: Option<Bound<'_, pyo3::types::PyDict>>,
+    outline_column: Option<Bound<'_, pyo3::types::PyDict>>,
+    outline_collapsed: Option<Bound<'_, pyo3::types::PyDict>>,
 ) -> PyResult<()> {
+    // Checked up front, so a bad value fails before any sheet is written.
+    crate::parallel::thread_count(max_threads, 1)?;
     let mut workbook = Workbook::new();
+    if let Some(props) = crate::properties::resolve_properties(properties.as_ref(), reproducible)? {
+        workbook.set_properties(&props);
+    }
+    // Every sheet in the order given, added to the workbook once the queued
+    // ones have been written.
+    let mut slots: Vec<Slot> = Vec::new();
+    // Lower-cased, as Excel compares table names case-insensitively.
+    let mut table_names: HashSet<String> = HashSet::new();
+    // Every requested name is reserved before any continuation sheet is
+    // named, so `Data (2)` never collides with a later sheet of that name.
+    let mut sheet_names: HashSet<String> = records_with_sheet_name
+        .iter()
+        .map(|(name, _)| name.to_lowercase())
+        .collect();
     for (sheet_name, records) in records_with_sheet_name {
         ensure_valid_sheet_name(&sheet_name)?;
 
         let dedupe = keyed_extract::<bool>(dedupe_strings.as_ref(), &sheet_name)?
             .unwrap_or(false);
 
-        let mut worksheet = if dedupe {
-            workbook.add_worksheet()
-        } else {
-            workbook.add_worksheet_with_constant_memory()
+        let sheet_header_row = keyed_extract::<u32>(header_row.as_ref(), &sheet_name)?;
+        let sheet_band = keyed_extract::<String>(banded_rows.as_ref(), &sheet_name)?;
+        let layout = crate::helpers::resolve_layout(
+            sheet_header_row,
+            keyed_get(merge_ranges.as_ref(), &sheet_name)?.as_ref(),
+            keyed_get(row_heights.as_ref(), &sheet_name)?.as_ref(),
+            keyed_get(row_formats.as_ref(), &sheet_name)?.as_ref(),
+            sheet_band,
+            keyed_extract::<bool>(autofilter.as_ref(), &sheet_name)?.unwrap_or(false),
+            keyed_get(totals_row.as_ref(), &sheet_name)?.as_ref(),
+            keyed_extract::<String>(totals_label.as_ref(), &sheet_name)?,
+            keyed_format(totals_format.as_ref(), &sheet_name)?.map(|f| f.inner),
+            keyed_extract::<String>(header_groups.as_ref(), &sheet_name)?,
+            keyed_get(row_groups.as_ref(), &sheet_name)?.as_ref(),
+            keyed_get(column_groups.as_ref(), &sheet_name)?.as_ref(),
+            keyed_extract::<String>(outline_column.as_ref(), &sheet_name)?,
+            keyed_extract::<bool>(outline_collapsed.as_ref(), &sheet_name)?.unwrap_or(false),
+        )?;
+        let records = match &layout.header_groups {
+            Some(groups) => groups.flatten(records, py)?,
+            None => records,
         };
-        worksheet.set_name(&sheet_name).map_err(xlsx_err)?;
+        let (records, sheet_index) = crate::frame_index::FrameIndex::new(
+            keyed_extract::<bool>(index.as_ref(), &sheet_name)?.unwrap_or(false),
+            keyed_extract::<bool>(merge_index_cells.as_ref(), &sheet_name)?.unwrap_or(true),
+        )
+        .apply(records, py)?;
+
+        let default_table_name = (1..)
+            .map(|n| format!("Table{n}"))
+            .find(|n| !table_names.contains(&n.to_lowercase()))
+            .unwrap_or_default();
+        let sheet_table = crate::table::resolve_table(
+            keyed_get(table.as_ref(), &sheet_name)?.as_ref(),
+            &default_table_name,
+            &layout,
+        )?;
+        if let Some(spec) = &sheet_table {
+            if !table_names.insert(spec.name.to_lowercase()) {
+                return Err(crate::helpers::value_err(format!(
+                    "table: name '{}' on sheet '{sheet_name}' is already used by another \
+table; table names must be unique in the workbook",
+                    spec.name
+                )));
+            }
+        }
+
+        let mode = Overflow::parse(
+            keyed_extract::<String>(overflow.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        ensure_splittable(mode, sheet_table.is_some())?;
+        sheet_index.check_table(sheet_table.is_some())?;
+        let limit = RowLimit::new(mode, &layout, &sheet_name);
+        let sheet_wide_decimals = crate::arrow_writer::WideDecimals::parse(
+            keyed_extract::<String>(wide_decimals.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        let sheet_durations = crate::helpers::Durations::parse(
+            keyed_extract::<String>(durations.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        let sheet_nested = crate::nested::NestedColumns::parse(
+            keyed_extract::<String>(nested_columns.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        let sheet_timezone = crate::timezone::Timezone::parse(
+            py,
+            keyed_extract::<String>(timezone.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        let sheet_int_overflow = crate::helpers::IntOverflow::parse(
+            keyed_extract::<String>(int_overflow.as_ref(), &sheet_name)?.as_deref(),
+        )?;
+        let sheet_reps = crate::missing::Reps::parse(
+            keyed_extract::<String>(na_rep.as_ref(), &sheet_name)?.as_deref(),
+            keyed_extract::<String>(nan_rep.as_ref(), &sheet_name)?.as_deref(),
+            keyed_extract::<String>(inf_rep.as_ref(), &sheet_name)?.as_deref(),
+        );
+        let mut sheet_columns = crate::columns::ColumnSelection::parse(
+            keyed_extract::<Vec<String>>(columns.as_ref(), &sheet_name)?,
+            keyed_extract::<IndexMap<String, String>>(rename.as_ref(), &sheet_name)?,
+        )?;
+        sheet_columns.lead_with(sheet_index.names());
+        let sheet_record_keys = crate::records::RecordKeys::parse(
+            keyed_extract::<String>(missing.as_ref(), &sheet_name)?.as_deref(),
+            keyed_extract::<String>(extra_keys.as_ref(), &sheet_name)?.as_deref(),
+            keyed_extract::<usize>(scan_rows.as_ref(), &sheet_name)?,
+        )?;
+
+        // In-cell images are numbered workbook-wide only at save time, so
+        // their rows cannot be flushed early; such sheets are buffered too, as
+        // are those with merged index cells or grouped rows.
+        let sheet_image_cols =
+            keyed_extract::<Vec<String>>(image_columns.as_ref(), &sheet_name)?;
+        let buffered = dedupe
+            || sheet_table.is_some()
+            || sheet_image_cols.is_some()
+            || sheet_index.has_merges()
+            || layout.outline.groups_rows();
 
         let pane = freeze_panes
             .as_ref()
@@ -918,52 +1954,168 @@ pub fn write_worksheets(
             keyed_get(column_formats.as_ref(), &sheet_name)?;
         let sheet_hdr_fmt = keyed_format(header_format.as_ref(), &sheet_name)?;
 
-        let sheet_header_row =
-            keyed_extract::<u32>(header_row.as_ref(), &sheet_name)?.unwrap_or(0);
-        let sheet_band = keyed_extract::<String>(banded_rows.as_ref(), &sheet_name)?;
-        let layout = crate::helpers::resolve_layout(
-            sheet_header_row,
-            keyed_get(merge_ranges.as_ref(), &sheet_name)?.as_ref(),
-            keyed_get(row_heights.as_ref(), &sheet_name)?.as_ref(),
-            keyed_get(row_formats.as_ref(), &sheet_name)?.as_ref(),
-            sheet_band,
-            keyed_extract::<bool>(autofilter.as_ref(), &sheet_name)?.unwrap_or(false),
-            keyed_get(totals_row.as_ref(), &sheet_name)?.as_ref(),
-            keyed_extract::<String>(totals_label.as_ref(), &sheet_name)?,
-            keyed_format(totals_format.as_ref(), &sheet_name)?.map(|f| f.inner),
-        )?;
-
         let sheet_urls = keyed_extract::<Vec<String>>(url_columns.as_ref(), &sheet_name)?;
 
-        write_worksheet_content(
-            &mut worksheet,
-            &records,
-            password.as_ref(),
-            pane.row,
-            pane.col,
-            float_format.as_ref(),
-            datetime_format.as_ref(),
-            index_columns.as_ref(),
+        let opts = SheetOptions {
+            password: password.clone(),
+            freeze_row: pane.row,
+            freeze_col: pane.col,
+            float_format: float_format.clone(),
+            datetime_format: datetime_format.clone(),
+            index_columns: index_columns.clone(),
             autofit,
             bold_headers,
-            sheet_uniform,
-            sheet_spec.as_ref(),
-            sheet_col_fmts.as_ref(),
-            sheet_hdr_fmt.as_ref(),
-            &layout,
-            sheet_urls.as_ref(),
-            keyed_get(formula_columns.as_ref(), &sheet_name)?.as_ref(),
-            py,
-        )?;
+            column_width: sheet_uniform,
+            column_widths: sheet_spec,
+            column_formats: sheet_col_fmts,
+            header_format: sheet_hdr_fmt,
+            layout,
+            url_columns: sheet_urls,
+            id_columns: keyed_extract::<Vec<String>>(id_columns.as_ref(), &sheet_name)?,
+            error_columns: keyed_extract::<Vec<String>>(error_columns.as_ref(), &sheet_name)?,
+            image_columns: sheet_image_cols,
+            formula_columns: keyed_get(formula_columns.as_ref(), &sheet_name)?,
+            conditional_formats: keyed_get(conditional_formats.as_ref(), &sheet_name)?,
+            data_validation: keyed_get(data_validation.as_ref(), &sheet_name)?,
+            table: sheet_table,
+            charts: keyed_get(charts.as_ref(), &sheet_name)?,
+            images: keyed_get(images.as_ref(), &sheet_name)?,
+            comments: keyed_get(comments.as_ref(), &sheet_name)?,
+            wide_decimals: sheet_wide_decimals,
+            durations: sheet_durations,
+            nested: sheet_nested,
+            timezone: sheet_timezone,
+            int_overflow: sheet_int_overflow,
+            reps: sheet_reps,
+            columns: sheet_columns,
+            record_keys: sheet_record_keys,
+            index: sheet_index,
+            limit,
+            buffered,
+        };
+
+        let stream = match &records {
+            // A stream that cannot be produced is left to the serial path,
+            // which falls back to the column-by-column writer.
+            WorksheetData::ArrowDataFrame(obj) if parallel && opts.can_write_in_parallel() => {
+                let stream = opts
+                    .columns
+                    .narrow(obj, py)
+                    .and_then(|obj| crate::arrow_ffi::stream_to_reader(&obj, py));
+                stream
+                    .ok()
+                    .map(|reader| crate::nested::explode_reader(reader, opts.nested))
+                    .map(|reader| opts.columns.project(reader, py))
+                    .transpose()?
+            }
+            _ => None,
+        };
+        if let Some(reader) = stream {
+            let queued = queue_sheet(&mut workbook, &sheet_name, reader, opts, py)?;
+            slots.push(Slot::Queued(Box::new(queued)));
+            continue;
+        }
+
+        let mut sheets = Vec::new();
+        let mut sheet_charts = Vec::new();
+        let mut part_name = sheet_name.clone();
+        let mut resume = None;
+        for part in 2.. {
+            let mut worksheet = new_sheet(&mut workbook, buffered);
+            worksheet.set_name(&part_name).map_err(xlsx_err)?;
+            let (charts, rest) =
+                write_worksheet_content(&mut workbook, &mut worksheet, &records, resume, &opts, py)?;
+            sheets.push(worksheet);
+            sheet_charts.extend(charts);
+            resume = match rest {
+                Some(rest) => Some(rest),
+                None => break,
+            };
+            part_name = continuation_name(&sheet_name, part, &sheet_names);
+            sheet_names.insert(part_name.to_lowercase());
+        }
+        slots.push(Slot::Written(sheets, sheet_charts));
     }
 
+    let tasks: Vec<_> = slots
+        .iter_mut()
+        .filter_map(|slot| match slot {
+            Slot::Queued(queued) => {
+                let QueuedSheet { opts, start, job } = queued.as_mut();
+                Some((
+                    job,
+                    &opts.limit,
+                    &opts.layout,
+                    &start.datetime_fmt,
+                    start.formula_cols.as_slice(),
+                ))
+            }
+            Slot::Written(..) => None,
+        })
+        .collect();
+    if !tasks.is_empty() {
+        let threads = crate::parallel::thread_count(max_threads, tasks.len())?;
+        py.detach(|| {
+            crate::parallel::run_all(
+                tasks,
+                threads,
+                |(job, limit, layout, datetime_fmt, formula_cols)| {
+                    let mut batches = std::mem::take(&mut job.batches).into_iter().map(Ok);
+                    job.streamed = Some(job.arrow.write(
+                        &mut job.worksheet,
+                        &mut batches,
+                        None,
+                        limit,
+                        layout,
+                        datetime_fmt,
+                        formula_cols,
+                    ));
+                },
+            )
+        });
+    }
+
+    // Placed once every sheet exists, so a chart can target any of them.
+    let mut placed_charts = Vec::new();
+    for slot in slots {
+        match slot {
+            Slot::Written(sheets, charts) => {
+                for worksheet in sheets {
+                    workbook.push_worksheet(worksheet);
+                }
+                placed_charts.extend(charts);
+            }
+            Slot::Queued(queued) => {
+                let QueuedSheet { opts, start, job } = *queued;
+                let BatchJob {
+                    mut worksheet,
+                    arrow,
+                    streamed,
+                    ..
+                } = job;
+                let streamed = streamed.expect("every queued sheet is written")?;
+                if let Some(count) = streamed.dropped {
+                    opts.limit.warn_truncated(&opts.layout, count, py)?;
+                }
+                let data = SheetData {
+                    headers: arrow.headers,
+                    rows: streamed.rows,
+                    image_cols: arrow.image_cols,
+                };
+                placed_charts.extend(finish_sheet(&mut worksheet, &opts, &start, data, py)?);
+                workbook.push_worksheet(worksheet);
+            }
+        }
+    }
+    crate::chart::place_charts(&mut workbook, placed_charts)?;
+
     save_workbook(py, &mut workbook, file_name)?;
     Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
 #[pyfunction]
-#[pyo3(signature = (records, file_name, sheet_name = None, password = None, freeze_row = None, freeze_col = None, float_format = None, datetime_format = None, index_columns = None, autofit = true, bold_headers = false, column_width = None, column_widths = None, column_formats = None, header_format = None, dedupe_strings = false, header_row = 0, merge_ranges = None, row_heights = None, row_formats = None, banded_rows = None, autofilter = false, url_columns = None, totals_row = None, totals_label = None, totals_format = None, formula_columns = None))]
+#[pyo3(signature = (records, file_name, sheet_name = None, password = None, freeze_row = None, freeze_col = None, float_format = None, datetime_format = None, index_columns = None, autofit = true, bold_headers = false, column_width = None, column_widths = None, column_formats = None, header_format = None, dedupe_strings = false, header_row = None, merge_ranges = None, row_heights = None, row_formats = None, banded_rows = None, autofilter = false, url_columns = None, totals_row = None, totals_label = None, totals_format = None, formula_columns = None, conditional_formats = None, data_validation = None, table = None, charts = None, image_columns = None, images = None, comments = None, properties = None, reproducible = false, overflow = None, wide_decimals = None, durations = None, nested_columns = None, timezone = None, int_overflow = None, id_columns = None, na_rep = None, nan_rep = None, inf_rep = None, error_columns = None, columns = None, missing = None, extra_keys = None, scan_rows = None, rename = None, header_groups = None, index = false, merge_index_cells = true, row_groups = None, column_groups = None, outline_column = None, outline_collapsed = false))]
 pub fn write_worksheet(
     py: Python,
     records: WorksheetData,
@@ -982,7 +2134,7 @@ pub fn write_worksheet(
     column_formats: Option<Bound<'_, PyAny>>,
     header_format: Option<Bound<'_, crate::format::Format>>,
     dedupe_strings: bool,
-    header_row: u32,
+    header_row: Option<u32>,
     merge_ranges: Option<Bound<'_, PyAny>>,
     row_heights: Option<Bound<'_, PyAny>>,
     row_formats: Option<Bound<'_, PyAny>>,
@@ -993,6 +2145,38 @@ pub fn write_worksheet(
     totals_label: Option<String>,
     totals_format: Option<Bound<'_, crate::format::Format>>,
     formula_columns: Option<Bound<'_, PyAny>>,
+    conditional_formats: Option<Bound<'_, PyAny>>,
+    data_validation: Option<Bound<'_, PyAny>>,
+    table: Option<Bound<'_, PyAny>>,
+    charts: Option<Bound<'_, PyAny>>,
+    image_columns: Option<Vec<String>>,
+    images: Option<Bound<'_, PyAny>>,
+    comments: Option<Bound<'_, PyAny>>,
+    properties: Option<Bound<'_, PyAny>>,
+    reproducible: bool,
+    overflow: Option<String>,
+    wide_decimals: Option<String>,
+    durations: Option<String>,
+    nested_columns: Option<String>,
+    timezone: Option<String>,
+    int_overflow: Option<String>,
+    id_columns: Option<Vec<String>>,
+    na_rep: Option<String>,
+    nan_rep: Option<String>,
+    inf_rep: Option<String>,
+    error_columns: Option<Vec<String>>,
+    columns: Option<Vec<String>>,
+    missing: Option<String>,
+    extra_keys: Option<String>,
+    scan_rows: Option<usize>,
+    rename: Option<IndexMap<String, String>>,
+    header_groups: Option<String>,
+    index: bool,
+    merge_index_cells: bool,
+    row_groups: Option<Bound<'_, PyAny>>,
+    column_groups: Option<Bound<'_, PyAny>>,
+    outline_column: Option<String>,
+    outline_collapsed: bool,
 ) -> PyResult<()> {
     let layout = crate::helpers::resolve_layout(
         header_row,
@@ -1004,42 +2188,106 @@ pub fn write_worksheet(
         totals_row.as_ref(),
         totals_label,
         totals_format.map(|f| f.borrow().inner.clone()),
+        header_groups,
+        row_groups.as_ref(),
+        column_groups.as_ref(),
+        outline_column,
+        outline_collapsed,
     )?;
-    let mut workbook = Workbook::new();
-    let mut worksheet = if dedupe_strings {
-        workbook.add_worksheet()
-    } else {
-        workbook.add_worksheet_with_constant_memory()
+    let records = match &layout.header_groups {
+        Some(groups) => groups.flatten(records, py)?,
+        None => records,
     };
-
-    if let Some(sheet_name) = sheet_name {
-        ensure_valid_sheet_name(&sheet_name)?;
-        worksheet.set_name(sheet_name).map_err(xlsx_err)?;
+    let (records, frame_index) =
+        crate::frame_index::FrameIndex::new(index, merge_index_cells).apply(records, py)?;
+    let table = crate::table::resolve_table(table.as_ref(), "Table1", &layout)?;
+    frame_index.check_table(table.is_some())?;
+    let mode = Overflow::parse(overflow.as_deref())?;
+    ensure_splittable(mode, table.is_some())?;
+    let wide_decimals = crate::arrow_writer::WideDecimals::parse(wide_decimals.as_deref())?;
+    let durations = crate::helpers::Durations::parse(durations.as_deref())?;
+    let nested = crate::nested::NestedColumns::parse(nested_columns.as_deref())?;
+    let timezone = crate::timezone::Timezone::parse(py, timezone.as_deref())?;
+    let int_overflow = crate::helpers::IntOverflow::parse(int_overflow.as_deref())?;
+    let reps =
+        crate::missing::Reps::parse(na_rep.as_deref(), nan_rep.as_deref(), inf_rep.as_deref());
+    let mut columns = crate::columns::ColumnSelection::parse(columns, rename)?;
+    columns.lead_with(frame_index.names());
+    let record_keys =
+        crate::records::RecordKeys::parse(missing.as_deref(), extra_keys.as_deref(), scan_rows)?;
+    let sheet_name = sheet_name.unwrap_or_else(|| "Sheet1".to_string());
+    ensure_valid_sheet_name(&sheet_name)?;
+    let limit = RowLimit::new(mode, &layout, &sheet_name);
+    let mut workbook = Workbook::new();
+    if let Some(props) = crate::properties::resolve_properties(properties.as_ref(), reproducible)? {
+        workbook.set_properties(&props);
     }
-
-    let hdr_borrow = header_format.as_ref().map(|h| h.borrow());
-    let hdr_ref = hdr_borrow.as_deref();
-
-    write_worksheet_content(
-        &mut worksheet,
-        &records,
-        password.as_ref(),
+    // In-cell images are numbered workbook-wide only at save time, so their
+    // rows cannot be flushed early; such sheets are buffered in memory, as are
+    // those with merged index cells or grouped rows.
+    let buffered = dedupe_strings
+        || table.is_some()
+        || image_columns.is_some()
+        || frame_index.has_merges()
+        || layout.outline.groups_rows();
+
+    let opts = SheetOptions {
+        password,
         freeze_row,
         freeze_col,
-        float_format.as_ref(),
-        datetime_format.as_ref(),
-        index_columns.as_ref(),
+        float_format,
+        datetime_format,
+        index_columns,
         autofit,
         bold_headers,
         column_width,
-        column_widths.as_ref(),
-        column_formats.as_ref(),
-        hdr_ref,
-        &layout,
-        url_columns.as_ref(),
-        formula_columns.as_ref(),
-        py,
-    )?;
+        column_widths,
+        column_formats,
+        header_format: header_format.map(|h| h.borrow().clone()),
+        layout,
+        url_columns,
+        id_columns,
+        error_columns,
+        image_columns,
+        formula_columns,
+        conditional_formats,
+        data_validation,
+        table,
+        charts,
+        images,
+        comments,
+        wide_decimals,
+        durations,
+        nested,
+        timezone,
+        int_overflow,
+        reps,
+        columns,
+        record_keys,
+        index: frame_index,
+        limit,
+        buffered,
+    };
+
+    let mut placed_charts = Vec::new();
+    let mut sheet_names = HashSet::from([sheet_name.to_lowercase()]);
+    let mut part_name = sheet_name.clone();
+    let mut resume = None;
+    for part in 2.. {
+        let mut worksheet = new_sheet(&mut workbook, buffered);
+        worksheet.set_name(&part_name).map_err(xlsx_err)?;
+        let (sheet_charts, rest) =
+            write_worksheet_content(&mut workbook, &mut worksheet, &records, resume, &opts, py)?;
+        workbook.push_worksheet(worksheet);
+        placed_charts.extend(sheet_charts);
+        resume = match rest {
+            Some(rest) => Some(rest),
+            None => break,
+        };
+        part_name = continuation_name(&sheet_name, part, &sheet_names);
+        sheet_names.insert(part_name.to_lowercase());
+    }
+    crate::chart::place_charts(&mut workbook, placed_charts)?;
 
     save_workbook(py, &mut workbook, file_name)?;
     Ok(())
@@ -1,11 +1,20 @@
+import html
+import io
 import random
-from typing import Any, Dict, List
+import re
+import zipfile
+from typing import Any, Dict, List, Optional, Tuple, Union
 
 import pytest
 from faker import Faker
 
+from rustpy_xlsxwriter import write_csv, write_worksheet
+
 XLSX_MAGIC = b"PK\x03\x04"
 
+# A cell reference, such as ``B2``.
+_REF = r'<c r="([A-Z]+\d+)"'
+
 
 def _make_record(fake: Faker) -> Dict[str, Any]:
     return {
@@ -43,3 +52,91 @@ def small_records():
 def medium_records():
     """1000 records for moderate tests."""
     return generate_records(1000)
+
+
+# Readers for the parts of a written workbook, for tests that check the XML
+# the writer produced rather than what a reader makes of it.
+
+
+def xlsx_book(data, **kwargs) -> zipfile.ZipFile:
+    """Write ``data`` with ``write_worksheet`` and open the result."""
+    buf = io.BytesIO()
+    write_worksheet(data, buf, **kwargs)
+    return zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+
+
+def sheet_xml(book: zipfile.ZipFile, sheet: int = 1) -> str:
+    return book.read(f"xl/worksheets/sheet{sheet}.xml").decode()
+
+
+def cell_values(book: zipfile.ZipFile, sheet: int = 1) -> Dict[str, Union[str, float]]:
+    """``{ref: value}`` for the sheet's text (``str``) and number (``float``) cells.
+
+    Text is read inline, as streamed sheets write it, or from the shared
+    string table, where sheets kept in memory store it.
+    """
+    xml = sheet_xml(book, sheet)
+    texts = re.findall(_REF + r'[^>]*t="inlineStr"><is><t[^>]*>(.*?)</t></is></c>', xml)
+    shared = []
+    if "xl/sharedStrings.xml" in book.namelist():
+        sst = book.read("xl/sharedStrings.xml").decode()
+        shared = re.findall(r"<si><t[^>]*>(.*?)</t></si>", sst)
+    refs = re.findall(_REF + r'[^>]*t="s"><v>(\d+)</v></c>', xml)
+    numbers = re.findall(_REF + r'(?: s="\d+")?><v>(.*?)</v></c>', xml)
+    return {
+        **{ref: html.unescape(text) for ref, text in texts},
+        **{ref: html.unescape(shared[int(i)]) for ref, i in refs},
+        **{ref: float(v) for ref, v in numbers},
+    }
+
+
+def cell_parts(book: zipfile.ZipFile, sheet: int = 1) -> Dict[str, Tuple[str, str]]:
+    """``{ref: (attributes, inner xml)}`` for the sheet's cells."""
+    return {
+        ref: (attrs, body)
+        for ref, attrs, body in re.findall(_REF + r"([^>]*)>(.*?)</c>", sheet_xml(book, sheet))
+    }
+
+
+def cell_number(cell: Tuple[str, str]) -> float:
+    """The number of a cell from :func:`cell_parts`."""
+    return float(re.search(r"<v>(.*)</v>", cell[1]).group(1))
+
+
+def num_format_of(book: zipfile.ZipFile, attrs: str) -> Optional[str]:
+    """The number format code of a cell's style, or ``None`` for General."""
+    styles = book.read("xl/styles.xml").decode()
+    codes = dict(re.findall(r'<numFmt numFmtId="(\d+)" formatCode="([^"]*)"', styles))
+    xfs = re.search(r"<cellXfs[^>]*>(.*?)</cellXfs>", styles, re.S).group(1)
+    xf_ids = re.findall(r'<xf numFmtId="(\d+)"', xfs)
+    style = re.search(r's="(\d+)"', attrs)
+    num_fmt_id = xf_ids[int(style.group(1))] if style else "0"
+    return codes.get(num_fmt_id)
+
+
+def text_cells(book: zipfile.ZipFile, sheet: int = 1) -> Dict[str, str]:
+    """``{ref: str}`` for the sheet's inline text cells, as streamed sheets write them."""
+    cells = _REF + r'[^>]*t="inlineStr"><is><t>(.*?)</t></is></c>'
+    return dict(re.findall(cells, sheet_xml(book, sheet)))
+
+
+def number_cells(book: zipfile.ZipFile, sheet: int = 1) -> Dict[str, str]:
+    """``{ref: str}`` for the sheet's number cells, digits as written."""
+    return dict(re.findall(_REF + r'[^>]*><v>(.*?)</v></c>', sheet_xml(book, sheet)))
+
+
+def error_cells(book: zipfile.ZipFile, sheet: int = 1) -> Dict[str, str]:
+    """``{ref: str}`` for the sheet's error cells."""
+    cells = _REF + r'[^>]*t="e"><f>[^<]*</f><v>(.*?)</v></c>'
+    return dict(re.findall(cells, sheet_xml(book, sheet)))
+
+
+def merged_ranges(book: zipfile.ZipFile, sheet: int = 1) -> List[str]:
+    return re.findall(r'<mergeCell ref="([A-Z0-9:]+)"/>', sheet_xml(book, sheet))
+
+
+def csv_lines(data, **kwargs) -> List[str]:
+    """Write ``data`` with ``write_csv`` and split the output into lines."""
+    buf = io.BytesIO()
+    write_csv(data, buf, **kwargs)
+    return buf.getvalue().decode().splitlines()
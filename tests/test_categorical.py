@@ -0,0 +1,129 @@
+"""Dictionary-encoded Arrow columns — pandas ``category``, Polars ``Categorical``.
+
+A dictionary column is written through its values: each row shows the value
+its key points at, with the value type deciding the cell type and format.
+Cells are read straight from the sheet XML.
+"""
+
+import datetime
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import write_csv, write_worksheet, write_worksheets
+
+from conftest import cell_number, cell_parts, xlsx_book
+
+pa = pytest.importorskip("pyarrow")
+
+COLORS = ["red", "green", None, "red", "blue", "green"]
+SIZES = [3, 1, 3, None, 2, 1]
+
+
+def _table():
+    return pa.table(
+        {
+            "color": pa.array(COLORS).dictionary_encode(),
+            "size": pa.array(SIZES).dictionary_encode(),
+        }
+    )
+
+
+def _text(book, cell):
+    """The text of a string cell, inline or from the shared-string table."""
+    attrs, body = cell
+    if 't="s"' in attrs:
+        sst = book.read("xl/sharedStrings.xml").decode()
+        strings = re.findall(r"<si><t[^>]*>(.*?)</t></si>", sst)
+        return strings[int(re.search(r"<v>(\d+)</v>", body).group(1))]
+    return re.search(r"<t[^>]*>(.*?)</t>", body).group(1)
+
+
+def test_strings_are_written_as_text():
+    book = xlsx_book(_table())
+    cells = cell_parts(book)
+    assert [_text(book, cells[f"A{r}"]) for r in (2, 3, 5, 6, 7)] == [
+        "red",
+        "green",
+        "red",
+        "blue",
+        "green",
+    ]
+    assert "<v>" not in cells.get("A4", ("", ""))[1] and "<t" not in cells.get("A4", ("", ""))[1]
+
+
+def test_integers_are_written_as_numbers():
+    cells = cell_parts(xlsx_book(_table()))
+    assert [cell_number(cells[f"B{r}"]) for r in (2, 3, 4, 6, 7)] == [3, 1, 3, 2, 1]
+    assert "<v>" not in cells.get("B5", ("", ""))[1]
+
+
+def test_dates_keep_the_date_format():
+    days = [datetime.date(2024, 1, 1), datetime.date(2024, 1, 1), datetime.date(2024, 2, 1)]
+    book = xlsx_book(pa.table({"day": pa.array(days).dictionary_encode()}))
+    cells = cell_parts(book)
+    assert cell_number(cells["A2"]) == cell_number(cells["A3"]) == 45292
+    assert 's="' in cells["A2"][0]
+
+
+def test_dedupe_stores_each_category_once():
+    book = xlsx_book(_table(), dedupe_strings=True)
+    sst = book.read("xl/sharedStrings.xml").decode()
+    # "color" header plus the three colors; "size" header.
+    assert 'uniqueCount="5"' in sst
+    assert sorted(re.findall(r"<si><t[^>]*>(.*?)</t></si>", sst)) == [
+        "blue",
+        "color",
+        "green",
+        "red",
+        "size",
+    ]
+    cells = cell_parts(book)
+    assert _text(book, cells["A5"]) == "red"
+
+
+def test_csv(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_table(), str(path))
+    assert path.read_text().splitlines() == [
+        "color,size",
+        "red,3",
+        "green,1",
+        ",3",
+        "red,",
+        "blue,2",
+        "green,1",
+    ]
+
+
+def test_note_column(tmp_path):
+    path = tmp_path / "notes.xlsx"
+    table = pa.table(
+        {
+            "sku": ["a", "b", "c"],
+            "source": pa.array(["list A", None, "list A"]).dictionary_encode(),
+        }
+    )
+    write_worksheet(table, str(path), comments={"sku": "source"})
+    xml = zipfile.ZipFile(path).read("xl/comments1.xml").decode()
+    notes = dict(re.findall(r'<comment ref="([A-Z]+\d+)".*?<t(?: [^>]*)?>(.*?)</t>', xml))
+    assert notes == {"A2": "list A", "A4": "list A"}
+
+
+def test_parallel_matches_serial():
+    table = pa.Table.from_batches(_table().to_batches(max_chunksize=4))
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table), ("C", table)],
+            buf,
+            banded_rows={"B": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
@@ -0,0 +1,308 @@
+"""Charts — declared by header name, resolved to ranges once the rows are known.
+
+Charts are drawings rather than cells, so they are built after the last row is
+written and inserted once every sheet exists. These tests read the chart XML
+directly: the series ranges, the chart kinds, where each chart is anchored,
+and the ``ValueError``s raised before anything is written.
+"""
+
+import re
+import zipfile
+
+import openpyxl
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+
+def _records(n=5):
+    return [
+        {"month": f"m{i}", "sales": i * 10, "cost": i * 3, "margin": i / 10}
+        for i in range(1, n + 1)
+    ]
+
+
+def _chart_xml(path, n=1):
+    return zipfile.ZipFile(path).read(f"xl/charts/chart{n}.xml").decode()
+
+
+def _chart_count(path):
+    names = zipfile.ZipFile(path).namelist()
+    return len([n for n in names if re.fullmatch(r"xl/charts/chart\d+\.xml", n)])
+
+
+def _refs(xml):
+    return re.findall(r"<c:f>(.*?)</c:f>", xml)
+
+
+def _anchors(path, n=1):
+    """``(col, row)`` of each chart's top-left corner in drawing ``n``."""
+    xml = zipfile.ZipFile(path).read(f"xl/drawings/drawing{n}.xml").decode()
+    return [
+        (int(col), int(row))
+        for col, row in re.findall(
+            r"<xdr:from><xdr:col>(\d+)</xdr:col><xdr:colOff>\d+</xdr:colOff>"
+            r"<xdr:row>(\d+)</xdr:row>",
+            xml,
+        )
+    ]
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet(_records(), str(path))
+    assert _chart_count(path) == 0
+
+
+def test_column_chart_ranges_follow_the_data(tmp_path):
+    path = tmp_path / "column.xlsx"
+    write_worksheet(
+        _records(5),
+        str(path),
+        sheet_name="Data",
+        charts={"type": "column", "categories": "month", "values": ["sales", "cost"]},
+    )
+    xml = _chart_xml(path)
+    assert "<c:barDir val=\"col\"/>" in xml
+    # Each series is named by its header and spans the five data rows.
+    assert _refs(xml) == [
+        "Data!$B$1",
+        "Data!$A$2:$A$6",
+        "Data!$B$2:$B$6",
+        "Data!$C$1",
+        "Data!$A$2:$A$6",
+        "Data!$C$2:$C$6",
+    ]
+
+
+@pytest.mark.parametrize(
+    "spec,marker",
+    [
+        ({"type": "bar"}, '<c:barDir val="bar"/>'),
+        ({"type": "line"}, "<c:lineChart>"),
+        ({"type": "area", "subtype": "stacked"}, '<c:grouping val="stacked"/>'),
+        ({"type": "column", "subtype": "percent_stacked"}, '<c:grouping val="percentStacked"/>'),
+        ({"type": "pie"}, "<c:pieChart>"),
+        ({"type": "doughnut"}, "<c:doughnutChart>"),
+        ({"type": "scatter", "subtype": "smooth"}, '<c:scatterStyle val="smoothMarker"/>'),
+    ],
+)
+def test_chart_types(tmp_path, spec, marker):
+    path = tmp_path / "types.xlsx"
+    write_worksheet(
+        _records(3), str(path), charts={**spec, "categories": "month", "values": "sales"}
+    )
+    assert marker in _chart_xml(path)
+
+
+def test_titles_legend_and_size(tmp_path):
+    path = tmp_path / "titles.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        charts={
+            "type": "line",
+            "values": "sales",
+            "title": "Sales",
+            "x_axis_title": "Month",
+            "y_axis_title": "EUR",
+            "legend": "bottom",
+        },
+    )
+    xml = _chart_xml(path)
+    for text in ("Sales", "Month", "EUR"):
+        assert f"<a:t>{text}</a:t>" in xml
+    assert '<c:legendPos val="b"/>' in xml
+
+
+def test_hidden_legend(tmp_path):
+    path = tmp_path / "nolegend.xlsx"
+    write_worksheet(
+        _records(3), str(path), charts={"type": "line", "values": "sales", "legend": False}
+    )
+    assert "<c:legend>" not in _chart_xml(path)
+
+
+def test_secondary_axis_combines_two_chart_kinds(tmp_path):
+    path = tmp_path / "combo.xlsx"
+    write_worksheet(
+        _records(4),
+        str(path),
+        sheet_name="Data",
+        charts={
+            "type": "column",
+            "categories": "month",
+            "values": "sales",
+            "secondary": {"type": "line", "values": "margin", "y_axis_title": "Margin"},
+        },
+    )
+    xml = _chart_xml(path)
+    assert "<c:barChart>" in xml and "<c:lineChart>" in xml
+    # A second value axis for the line series.
+    assert xml.count("<c:valAx>") == 2
+    assert "Data!$D$2:$D$5" in _refs(xml)
+    assert "<a:t>Margin</a:t>" in xml
+
+
+def test_default_anchor_is_right_of_the_data_and_stacks(tmp_path):
+    path = tmp_path / "stack.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        charts=[{"type": "line", "values": "sales"}, {"type": "line", "values": "cost"}],
+    )
+    # Four data columns, one blank column, then the charts; the second starts
+    # below the first (288px / 20px rows, plus one row of gap).
+    assert _anchors(path) == [(5, 0), (5, 16)]
+
+
+def test_explicit_anchor(tmp_path):
+    path = tmp_path / "anchor.xlsx"
+    write_worksheet(
+        _records(3), str(path), charts={"type": "line", "values": "sales", "anchor": "B20"}
+    )
+    assert _anchors(path) == [(1, 19)]
+
+
+def test_follows_header_row(tmp_path):
+    path = tmp_path / "offset.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        sheet_name="Data",
+        header_row=2,
+        merge_ranges=[(0, 0, 0, 3, "Banner")],
+        charts={"type": "line", "values": "sales"},
+    )
+    assert _refs(_chart_xml(path)) == ["Data!$B$3", "Data!$B$4:$B$6"]
+    assert _anchors(path) == [(5, 2)]
+
+
+def test_chart_on_a_new_sheet(tmp_path):
+    path = tmp_path / "dash.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        sheet_name="Data",
+        charts={"type": "pie", "categories": "month", "values": "sales", "sheet": "Dashboard"},
+    )
+    assert openpyxl.load_workbook(path).sheetnames == ["Data", "Dashboard"]
+    # The chart still plots the data sheet, from the new sheet's top-left.
+    assert "Data!$B$2:$B$4" in _refs(_chart_xml(path))
+    assert _anchors(path) == [(0, 0)]
+
+
+def test_skipped_without_data(tmp_path):
+    path = tmp_path / "empty.xlsx"
+    write_worksheet([], str(path), charts={"type": "line", "values": "sales"})
+    assert _chart_count(path) == 0
+
+
+def test_unknown_column_warns(tmp_path):
+    path = tmp_path / "unknown.xlsx"
+    with pytest.warns(UserWarning, match="unknown column 'nope'"):
+        write_worksheet(
+            _records(), str(path), charts={"type": "line", "values": ["sales", "nope"]}
+        )
+    assert len(re.findall(r"<c:ser>", _chart_xml(path))) == 1
+
+
+def test_chart_without_any_known_column_is_skipped(tmp_path):
+    path = tmp_path / "none.xlsx"
+    with pytest.warns(UserWarning, match="chart skipped"):
+        write_worksheet(_records(), str(path), charts={"type": "line", "values": "nope"})
+    assert _chart_count(path) == 0
+
+
+@pytest.mark.parametrize(
+    "spec,message",
+    [
+        ({"values": "sales"}, "missing required option 'type'"),
+        ({"type": "radar", "values": "sales"}, "unknown type 'radar'"),
+        ({"type": "column", "subtype": "smooth", "values": "sales"}, "unknown subtype 'smooth'"),
+        ({"type": "line"}, "missing required option 'values'"),
+        ({"type": "line", "values": []}, "non-empty list"),
+        ({"type": "line", "values": "sales", "colour": "red"}, "unknown option 'colour'"),
+        ({"type": "pie", "values": ["sales", "cost"]}, "single 'values' column"),
+        ({"type": "pie", "values": "sales", "y_axis_title": "x"}, "no axes"),
+        ({"type": "scatter", "values": "sales"}, "needs 'categories'"),
+        ({"type": "line", "values": "sales", "anchor": "2B"}, "'anchor' must be a cell"),
+        ({"type": "line", "values": "sales", "legend": "middle"}, "unknown legend 'middle'"),
+        ({"type": "line", "values": "sales", "width": 0}, "positive number of pixels"),
+        ({"type": "line", "values": "sales", "sheet": "a/b"}, "sheet"),
+        (
+            {"type": "bar", "values": "sales", "secondary": {"type": "line", "values": "cost"}},
+            "only be combined with another bar chart",
+        ),
+        (
+            {"type": "pie", "values": "sales", "secondary": {"values": "cost"}},
+            "no axes to combine",
+        ),
+    ],
+)
+def test_invalid_specs_raise(tmp_path, spec, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_records(), str(path), charts=spec)
+    assert not path.exists()
+
+
+def test_not_a_dict_raises(tmp_path):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match="chart dict or a list"):
+        write_worksheet(_records(), str(path), charts="column")
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"x": ["a", "b", "c"], "y": [4.0, 5.0, 6.0]})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(
+        df, str(path), sheet_name="S", charts={"type": "bar", "categories": "x", "values": "y"}
+    )
+    assert "S!$B$2:$B$4" in _refs(_chart_xml(path))
+
+
+def test_multi_sheet_charts_and_shared_target(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("A", _records(2)), ("B", _records(3))],
+        str(path),
+        charts={
+            "A": {"type": "line", "values": "sales", "sheet": "Charts"},
+            "B": {"type": "area", "values": "cost", "sheet": "Charts"},
+        },
+    )
+    assert openpyxl.load_workbook(path).sheetnames == ["A", "B", "Charts"]
+    refs = _refs(_chart_xml(path, 1)) + _refs(_chart_xml(path, 2))
+    assert "A!$B$2:$B$3" in refs and "B!$C$2:$C$4" in refs
+    # Both land on the Charts sheet, one below the other.
+    assert _anchors(path) == [(0, 0), (0, 16)]
+
+
+def test_chart_can_target_a_later_sheet(tmp_path):
+    path = tmp_path / "later.xlsx"
+    write_worksheets(
+        [("Data", _records(2)), ("Summary", [{"note": "see chart"}])],
+        str(path),
+        charts={"Data": {"type": "column", "values": "sales", "sheet": "Summary", "anchor": "C3"}},
+    )
+    assert openpyxl.load_workbook(path).sheetnames == ["Data", "Summary"]
+    assert _anchors(path) == [(2, 2)]
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    FastExcel(str(path)).sheet(
+        "S", _records(4), charts=[{"type": "line", "values": "sales"}]
+    ).save()
+    assert _refs(_chart_xml(path)) == ["S!$B$1", "S!$B$2:$B$5"]
+
+
+def test_csv_warns_that_charts_are_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="charts"):
+        FastExcel(str(path)).sheet(
+            "S", _records(2), charts={"type": "line", "values": "sales"}
+        ).save()
@@ -0,0 +1,194 @@
+"""Picking, ordering and renaming columns — ``columns`` and ``rename``.
+
+Cells are read straight from the sheet XML.
+"""
+
+import io
+import warnings
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheets
+
+from conftest import cell_values, csv_lines, xlsx_book
+
+ROWS = [{"sku": "A-1", "cost": 2.5, "price": 4.0}, {"sku": "B-2", "cost": 3.0, "price": 5.5}]
+
+
+def test_records_pick_order_and_rename():
+    cells = cell_values(xlsx_book(ROWS, columns=["price", "sku"], rename={"sku": "SKU"}))
+    assert cells == {"A1": "price", "B1": "SKU", "A2": 4.0, "B2": "A-1", "A3": 5.5, "B3": "B-2"}
+
+
+def test_other_options_name_the_written_header():
+    book = xlsx_book(ROWS, rename={"sku": "SKU"}, id_columns=["SKU"], error_columns=["SKU"])
+    assert cell_values(book)["A2"] == "A-1"
+    with pytest.warns(UserWarning, match="id_columns: unknown column 'sku', skipped"):
+        xlsx_book(ROWS, rename={"sku": "SKU"}, id_columns=["sku"])
+
+
+def test_unknown_rename_key_warns():
+    with pytest.warns(UserWarning, match="rename: unknown column 'nope', skipped"):
+        cells = cell_values(xlsx_book(ROWS, rename={"nope": "x", "cost": "Cost"}))
+    assert cells["B1"] == "Cost"
+
+
+def test_column_listed_twice():
+    with pytest.raises(ValueError, match="columns: 'sku' is listed twice"):
+        xlsx_book(ROWS, columns=["sku", "sku"])
+
+
+def test_csv_records():
+    assert csv_lines(ROWS, columns=["price"], rename={"price": "Price"}) == ["Price", "4.0", "5.5"]
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Full", ROWS), ("Short", ROWS)],
+        buf,
+        columns={"Short": ["sku"]},
+        rename={"general": {"sku": "SKU"}},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert (cell_values(book, 1)["A1"], cell_values(book, 1)["C1"]) == ("SKU", "price")
+    assert cell_values(book, 2) == {"A1": "SKU", "A2": "A-1", "A3": "B-2"}
+
+
+def test_fastexcel(tmp_path):
+    path = tmp_path / "out.csv"
+    # CSV honours the options, so there is no "ignored" warning.
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        FastExcel(str(path)).sheet("S", ROWS, columns=["sku"], rename={"sku": "SKU"}).save()
+    assert path.read_text().splitlines() == ["SKU", "A-1", "B-2"]
+
+    buf = io.BytesIO()
+    FastExcel(buf).sheet("S", ROWS, columns=["cost"], rename={"cost": "Cost"}).save()
+    cells = cell_values(zipfile.ZipFile(io.BytesIO(buf.getvalue())))
+    assert cells == {"A1": "Cost", "A2": 2.5, "A3": 3.0}
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def _table(pa):
+    return pa.table(
+        {
+            "sku": pa.array(["A-1", "B-2"], type=pa.string()),
+            "cost": pa.array([2.5, 3.0]),
+            "price": pa.array([4.0, 5.5]),
+        }
+    )
+
+
+def test_arrow_projection():
+    pa = pytest.importorskip("pyarrow")
+    cells = cell_values(xlsx_book(_table(pa), columns=["price", "sku"], rename={"price": "Price"}))
+    assert cells == {"A1": "Price", "B1": "sku", "A2": 4.0, "B2": "A-1", "A3": 5.5, "B3": "B-2"}
+
+
+def test_arrow_unknown_column_raises():
+    pa = pytest.importorskip("pyarrow")
+    with pytest.raises(ValueError, match="columns: unknown column 'qty'. The data has sku"):
+        xlsx_book(_table(pa), columns=["sku", "qty"])
+    with pytest.raises(ValueError, match="columns: unknown column 'qty'"):
+        csv_lines(_table(pa), columns=["qty"])
+
+
+def test_arrow_csv():
+    pa = pytest.importorskip("pyarrow")
+    lines = csv_lines(_table(pa), columns=["sku", "cost"], rename={"cost": "Cost"})
+    assert lines == ["sku,Cost", "A-1,2.5", "B-2,3.0"]
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = _table(pa)
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            columns={"A": ["price", "sku"]},
+            rename={"B": {"cost": "Cost"}},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+# ---------------------------------------------------------------------------
+# DataFrames without an Arrow stream
+# ---------------------------------------------------------------------------
+
+
+class _Series:
+    def __init__(self, name, values):
+        self._name = name
+        self._values = values
+
+    def tolist(self):
+        if self._name == "secret":
+            raise AssertionError("an unselected column was converted")
+        return list(self._values)
+
+    to_list = tolist
+
+
+class _Dtype:
+    def __init__(self, kind):
+        self.kind = kind
+
+    def __str__(self):
+        return {"f": "Float64", "O": "String"}[self.kind]
+
+
+class _Frame:
+    """Pandas-shaped, without ``__arrow_c_stream__``."""
+
+    def __init__(self):
+        self._data = {"sku": ["A-1", "B-2"], "secret": ["x", "y"], "price": [4.0, 5.5]}
+        self.columns = list(self._data)
+        self.dtypes = [_Dtype(k) for k in "OOf"]
+
+    def __len__(self):
+        return 2
+
+    def __getitem__(self, key):
+        return _Series(key, self._data[key])
+
+
+class _PolarsFrame(_Frame):
+    schema = None
+
+    def get_column(self, name):
+        return _Series(name, self._data[name])
+
+
+@pytest.mark.parametrize("frame_cls", [_Frame, _PolarsFrame], ids=["pandas", "polars"])
+def test_frame_converts_only_picked_columns(frame_cls):
+    cells = cell_values(xlsx_book(frame_cls(), columns=["price", "sku"], rename={"sku": "SKU"}))
+    assert cells == {"A1": "price", "B1": "SKU", "A2": 4.0, "B2": "A-1", "A3": 5.5, "B3": "B-2"}
+    with pytest.raises(ValueError, match="columns: unknown column 'qty'"):
+        xlsx_book(frame_cls(), columns=["qty"])
+
+
+def test_polars_frame_csv():
+    lines = csv_lines(_PolarsFrame(), columns=["sku"], rename={"sku": "SKU"})
+    assert lines == ["SKU", "A-1", "B-2"]
+
+
+def test_pandas():
+    pd = pytest.importorskip("pandas")
+    pytest.importorskip("pyarrow")
+    df = pd.DataFrame({"sku": ["A-1", "B-2"], "cost": [2.5, 3.0], "price": [4.0, 5.5]})
+    cells = cell_values(xlsx_book(df, columns=["price", "sku"], rename={"price": "Price"}))
+    assert cells == {"A1": "Price", "B1": "sku", "A2": 4.0, "B2": "A-1", "A3": 5.5, "B3": "B-2"}
+    assert csv_lines(df, columns=["sku"]) == ["sku", "A-1", "B-2"]
@@ -0,0 +1,167 @@
+"""Cell notes — fixed ``(row, col)`` notes and ``{column: note_column}`` pairs.
+
+A note column travels with the data but is never written: its values become
+the notes on another column's cells, and everything downstream sees only the
+remaining columns. These tests read ``xl/comments1.xml`` for the notes and
+the sheet XML for the cells actually written.
+"""
+
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+
+def _notes(path, n=1):
+    """``{cell: text}`` for every note in comments part ``n``."""
+    names = zipfile.ZipFile(path).namelist()
+    if f"xl/comments{n}.xml" not in names:
+        return {}
+    xml = zipfile.ZipFile(path).read(f"xl/comments{n}.xml").decode()
+    return dict(re.findall(r'<comment ref="([A-Z]+\d+)".*?<t(?: [^>]*)?>(.*?)</t>', xml))
+
+
+def _header_cells(path, sheet=1):
+    xml = zipfile.ZipFile(path).read(f"xl/worksheets/sheet{sheet}.xml").decode()
+    row = re.search(r'<row r="1"[^>]*>(.*?)</row>', xml).group(1)
+    return re.findall(r"<t[^>]*>(.*?)</t>", row)
+
+
+def _rows():
+    return [
+        {"sku": "a", "price": 1.5, "source": "list A"},
+        {"sku": "b", "price": 2.5, "source": None},
+        {"sku": "c", "price": 3.5, "source": ""},
+        {"sku": "d", "price": 4.5, "source": 7},
+    ]
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet(_rows(), str(path))
+    assert _notes(path) == {}
+    assert _header_cells(path) == ["sku", "price", "source"]
+
+
+def test_fixed_cells(tmp_path):
+    path = tmp_path / "cells.xlsx"
+    write_worksheet(
+        [{"a": 1}, {"a": 2}], str(path), comments={(0, 0): "Header", (2, 3): "Off the data"}
+    )
+    assert _notes(path) == {"A1": "Header", "D3": "Off the data"}
+
+
+def test_note_column_is_not_written(tmp_path):
+    path = tmp_path / "column.xlsx"
+    write_worksheet(_rows(), str(path), comments={"price": "source"})
+    assert _header_cells(path) == ["sku", "price"]
+    # None and "" add no note; other values are shown as text.
+    assert _notes(path) == {"B2": "list A", "B5": "7"}
+
+
+def test_note_column_before_its_target(tmp_path):
+    path = tmp_path / "order.xlsx"
+    rows = [{"why": "estimate", "sku": "a", "qty": 3}]
+    write_worksheet(rows, str(path), comments={"qty": "why"})
+    assert _header_cells(path) == ["sku", "qty"]
+    assert _notes(path) == {"B2": "estimate"}
+
+
+def test_options_see_the_remaining_columns(tmp_path):
+    path = tmp_path / "shifted.xlsx"
+    rows = [{"source": "x", "sku": "a", "url": "https://example.com"}]
+    write_worksheet(
+        rows, str(path), comments={"sku": "source"}, url_columns=["url"], autofilter=True
+    )
+    sheet = zipfile.ZipFile(path).read("xl/worksheets/sheet1.xml").decode()
+    assert '<autoFilter ref="A1:B2"/>' in sheet
+    assert '<hyperlink ref="B2"' in sheet
+    assert _notes(path) == {"A2": "x"}
+
+
+def test_follows_header_row(tmp_path):
+    path = tmp_path / "offset.xlsx"
+    write_worksheet(
+        _rows()[:1], str(path), header_row=2, comments={"price": "source", (0, 0): "Title"}
+    )
+    assert _notes(path) == {"A1": "Title", "B4": "list A"}
+
+
+def test_unknown_column_warns_and_keeps_the_data(tmp_path):
+    path = tmp_path / "unknown.xlsx"
+    with pytest.warns(UserWarning, match="comments: unknown column 'nope'"):
+        write_worksheet(_rows(), str(path), comments={"nope": "source"})
+    assert _header_cells(path) == ["sku", "price", "source"]
+    assert _notes(path) == {}
+
+
+def test_dataframe_fallback_path(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame(
+        {"sku": ["a", "b"], "price": [1.5, 2.5], "source": ["list A", None]},
+        kinds=["O", "f", "O"],
+    )
+    path = tmp_path / "fallback.xlsx"
+    write_worksheet(df, str(path), comments={"price": "source"})
+    assert _header_cells(path) == ["sku", "price"]
+    assert _notes(path) == {"B2": "list A"}
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame(
+        {"sku": ["a", "b", "c"], "price": [1.5, 2.5, 3.5], "source": ["list A", None, "list C"]}
+    )
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(df, str(path), comments={"price": "source", (0, 1): "EUR"})
+    assert _header_cells(path) == ["sku", "price"]
+    assert _notes(path) == {"B1": "EUR", "B2": "list A", "B4": "list C"}
+
+
+@pytest.mark.parametrize(
+    "spec,message",
+    [
+        ("price", "must be a dict"),
+        ({(0,): "x"}, r"\(row, col\) pair"),
+        ({(-1, 0): "x"}, r"\(row, col\) pair"),
+        ({(1048576, 0): "x"}, "outside Excel's sheet limits"),
+        ({(0, 0): 5}, "note must be a string"),
+        ({"price": 5}, "must name the column holding the notes"),
+        ({"price": "price"}, "cannot hold its own notes"),
+        ({"price": "source", "source": "sku"}, "is itself a note column"),
+        ({1: "x"}, "must be a \\(row, col\\) tuple or a column name"),
+    ],
+)
+def test_invalid_specs_raise(tmp_path, spec, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_rows(), str(path), comments=spec)
+    assert not path.exists()
+
+
+def test_multi_sheet_is_per_sheet(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("Prices", _rows()), ("Plain", _rows())],
+        str(path),
+        comments={"Prices": {"price": "source"}},
+    )
+    assert _notes(path) == {"B2": "list A", "B5": "7"}
+    assert _header_cells(path, 2) == ["sku", "price", "source"]
+    assert "xl/comments2.xml" not in zipfile.ZipFile(path).namelist()
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    FastExcel(str(path)).sheet("S", _rows(), comments={"price": "source"}).save()
+    assert _notes(path) == {"B2": "list A", "B5": "7"}
+
+
+def test_csv_warns_that_comments_are_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="comments"):
+        FastExcel(str(path)).sheet("S", _rows(), comments={(0, 0): "x"}).save()
@@ -0,0 +1,257 @@
+"""Conditional formatting — highlighting rules keyed by column name.
+
+Rules live in the worksheet footer, so like the autofilter they are added after
+the data and their range follows the rows actually written. These tests pin the
+computed ranges, the rule types, and the up-front validation.
+"""
+
+import openpyxl
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_worksheet, write_worksheets
+
+RED = Format().set_font_color("#9C0006")
+
+
+def _records(n=5):
+    return [{"name": f"n{i}", "amount": i * 10 - 20, "qty": i} for i in range(n)]
+
+
+def _rules(path, sheet=None):
+    """``{range: [rule, ...]}`` for one sheet."""
+    wb = openpyxl.load_workbook(path)
+    ws = wb[sheet] if sheet else wb.active
+    return {str(cf.sqref): list(cf.rules) for cf in ws.conditional_formatting}
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet(_records(), str(path))
+    assert _rules(path) == {}
+
+
+def test_cell_rule_covers_the_data_rows(tmp_path):
+    path = tmp_path / "cell.xlsx"
+    write_worksheet(
+        _records(5),
+        str(path),
+        conditional_formats={
+            "amount": {"type": "cell", "criteria": "<", "value": 0, "format": RED}
+        },
+    )
+    rules = _rules(path)
+    # Header on row 1, data on rows 2-6.
+    (rule,) = rules["B2:B6"]
+    assert rule.type == "cellIs"
+    assert rule.operator == "lessThan"
+    assert rule.formula == ["0"]
+    assert rule.dxf.font.color.rgb.endswith("9C0006")
+
+
+def test_between_and_formula_value(tmp_path):
+    path = tmp_path / "between.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        conditional_formats={
+            "amount": {
+                "type": "cell",
+                "criteria": "between",
+                "minimum": -5,
+                "maximum": "=$H$1",
+                "format": RED,
+            }
+        },
+    )
+    (rule,) = _rules(path)["B2:B4"]
+    assert rule.operator == "between"
+    assert rule.formula == ["-5", "$H$1"]
+
+
+def test_text_value_is_quoted(tmp_path):
+    path = tmp_path / "text.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        conditional_formats={
+            "name": {"type": "cell", "criteria": "==", "value": "n1", "format": RED}
+        },
+    )
+    (rule,) = _rules(path)["A2:A4"]
+    assert rule.formula == ['"n1"']
+
+
+def test_several_rules_keep_their_order(tmp_path):
+    path = tmp_path / "list.xlsx"
+    write_worksheet(
+        _records(4),
+        str(path),
+        conditional_formats={
+            "amount": [
+                {"type": "cell", "criteria": ">", "value": 0, "format": RED},
+                {"type": "data_bar", "bar_color": "#638EC6"},
+            ]
+        },
+    )
+    rules = sorted(_rules(path)["B2:B5"], key=lambda r: r.priority)
+    assert [r.type for r in rules] == ["cellIs", "dataBar"]
+
+
+@pytest.mark.parametrize(
+    "rule,expected_type",
+    [
+        ({"type": "2_color_scale"}, "colorScale"),
+        ({"type": "3_color_scale", "mid_color": "#FFEB84"}, "colorScale"),
+        ({"type": "data_bar"}, "dataBar"),
+        ({"type": "icon_set", "icon_type": "3_arrows"}, "iconSet"),
+        ({"type": "top", "value": 2, "format": RED}, "top10"),
+        ({"type": "bottom", "value": 10, "percent": True, "format": RED}, "top10"),
+        ({"type": "formula", "criteria": "=$C2>2", "format": RED}, "expression"),
+    ],
+)
+def test_rule_types(tmp_path, rule, expected_type):
+    path = tmp_path / "types.xlsx"
+    write_worksheet(_records(5), str(path), conditional_formats={"qty": rule})
+    (written,) = _rules(path)["C2:C6"]
+    assert written.type == expected_type
+
+
+def test_top_and_bottom_options(tmp_path):
+    path = tmp_path / "top.xlsx"
+    write_worksheet(
+        _records(5),
+        str(path),
+        conditional_formats={
+            "qty": {"type": "bottom", "value": 20, "percent": True, "format": RED}
+        },
+    )
+    (rule,) = _rules(path)["C2:C6"]
+    assert rule.rank == 20
+    assert rule.percent
+    assert rule.bottom
+
+
+def test_formula_placeholders(tmp_path):
+    path = tmp_path / "formula.xlsx"
+    write_worksheet(
+        _records(4),
+        str(path),
+        header_row=2,
+        merge_ranges=[(0, 0, 0, 2, "Banner")],
+        conditional_formats={
+            "name": {
+                "type": "formula",
+                "criteria": "=AND({col}{first}<>\"\",ROW()<{last})",
+                "format": RED,
+            }
+        },
+    )
+    # Header on sheet row 3, data on rows 4-7.
+    (rule,) = _rules(path)["A4:A7"]
+    assert rule.formula == ['AND(A4<>"",ROW()<7)']
+
+
+def test_icon_set_options(tmp_path):
+    path = tmp_path / "icons.xlsx"
+    write_worksheet(
+        _records(5),
+        str(path),
+        conditional_formats={
+            "qty": {"type": "icon_set", "icon_type": "4_ratings", "reverse_icons": True}
+        },
+    )
+    (rule,) = _rules(path)["C2:C6"]
+    assert rule.iconSet.iconSet == "4Rating"
+    assert rule.iconSet.reverse
+
+
+def test_skipped_without_data(tmp_path):
+    path = tmp_path / "empty.xlsx"
+    write_worksheet(
+        [],
+        str(path),
+        conditional_formats={"qty": {"type": "data_bar"}},
+    )
+    assert _rules(path) == {}
+
+
+def test_unknown_column_warns(tmp_path):
+    path = tmp_path / "unknown.xlsx"
+    with pytest.warns(UserWarning, match="unknown column 'nope'"):
+        write_worksheet(
+            _records(), str(path), conditional_formats={"nope": {"type": "data_bar"}}
+        )
+    assert _rules(path) == {}
+
+
+@pytest.mark.parametrize(
+    "rule,message",
+    [
+        ({"type": "sparkle"}, "unknown type 'sparkle'"),
+        ({"criteria": ">", "value": 1}, "missing required option 'type'"),
+        ({"type": "cell", "criteria": ">", "value": 1}, "missing required option 'format'"),
+        ({"type": "cell", "criteria": "~", "value": 1, "format": RED}, "unknown criteria"),
+        ({"type": "cell", "criteria": "between", "minimum": 1, "format": RED}, "'maximum'"),
+        ({"type": "data_bar", "colour": "red"}, "unknown option 'colour'"),
+        ({"type": "data_bar", "bar_color": "nope"}, "invalid color"),
+        ({"type": "icon_set", "icon_type": "3_hearts"}, "unknown icon_type"),
+        (
+            {"type": "2_color_scale", "min_type": "percent", "min_value": 150},
+            "0..100",
+        ),
+        ({"type": "formula", "criteria": "=SUM(A1", "format": RED}, "malformed"),
+        ({"type": "cell", "criteria": ">", "value": 1, "format": "bold"}, "Format"),
+    ],
+)
+def test_invalid_rules_raise(tmp_path, rule, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_records(), str(path), conditional_formats={"amount": rule})
+    assert not path.exists()
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"a": [1, 2, 3], "b": [4.0, 5.0, 6.0]})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(df, str(path), conditional_formats={"b": {"type": "data_bar"}})
+    assert list(_rules(path)) == ["B2:B4"]
+
+
+def test_dataframe_fallback_path(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"a": [1, 2, 3, 4]}, kinds=["i"])
+    path = tmp_path / "fallback.xlsx"
+    write_worksheet(df, str(path), conditional_formats={"a": {"type": "data_bar"}})
+    assert list(_rules(path)) == ["A2:A5"]
+
+
+def test_multi_sheet_is_per_sheet(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("Ranked", _records(3)), ("Plain", _records(3))],
+        str(path),
+        conditional_formats={"Ranked": {"qty": {"type": "data_bar"}}},
+    )
+    assert list(_rules(path, "Ranked")) == ["C2:C4"]
+    assert _rules(path, "Plain") == {}
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    (
+        FastExcel(str(path))
+        .sheet("S", _records(6), conditional_formats={"qty": {"type": "3_color_scale"}})
+        .save()
+    )
+    assert list(_rules(path, "S")) == ["C2:C7"]
+
+
+def test_csv_warns_that_conditional_formats_are_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="conditional_formats"):
+        FastExcel(str(path)).sheet(
+            "S", _records(2), conditional_formats={"qty": {"type": "data_bar"}}
+        ).save()
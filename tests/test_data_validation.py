@@ -0,0 +1,260 @@
+"""Data validation — dropdowns and input limits keyed by column name.
+
+Validations sit in the worksheet footer next to the conditional formats, so
+they are added after the data and cover exactly the rows written. These tests
+pin the ranges, each validation kind, and the up-front ``ValueError``s.
+"""
+
+import datetime
+
+import openpyxl
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+
+def _records(n=5):
+    return [
+        {"status": "open", "qty": i, "due": datetime.date(2024, 1, i + 1), "sku": f"S{i}"}
+        for i in range(n)
+    ]
+
+
+def _validations(path, sheet=None):
+    """``{range: DataValidation}`` for one sheet."""
+    wb = openpyxl.load_workbook(path)
+    ws = wb[sheet] if sheet else wb.active
+    return {str(dv.sqref): dv for dv in ws.data_validations.dataValidation}
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet(_records(), str(path))
+    assert _validations(path) == {}
+
+
+def test_list_dropdown_covers_the_data_rows(tmp_path):
+    path = tmp_path / "list.xlsx"
+    write_worksheet(
+        _records(5),
+        str(path),
+        data_validation={"status": {"validate": "list", "source": ["open", "closed"]}},
+    )
+    # Header on row 1, data on rows 2-6.
+    dv = _validations(path)["A2:A6"]
+    assert dv.type == "list"
+    assert dv.formula1 == '"open,closed"'
+    # openpyxl maps Excel's inverted showDropDown flag back to "hidden".
+    assert not dv.showDropDown
+
+
+def test_list_from_range_and_hidden_arrow(tmp_path):
+    path = tmp_path / "range.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        data_validation={
+            "status": {"validate": "list", "source": "=$H$1:$H$3", "dropdown": False}
+        },
+    )
+    dv = _validations(path)["A2:A4"]
+    assert dv.formula1 == "$H$1:$H$3"
+    assert dv.showDropDown
+
+
+def test_whole_between_with_formula_bound(tmp_path):
+    path = tmp_path / "whole.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        data_validation={
+            "qty": {
+                "validate": "whole",
+                "criteria": "between",
+                "minimum": 0,
+                "maximum": "=$H$1",
+            }
+        },
+    )
+    dv = _validations(path)["B2:B4"]
+    assert dv.type == "whole"
+    assert (dv.formula1, dv.formula2) == ("0", "$H$1")
+
+
+@pytest.mark.parametrize(
+    "spec,expected",
+    [
+        ({"validate": "decimal", "criteria": "<", "value": 2.5}, ("decimal", "lessThan", "2.5")),
+        (
+            {"validate": "date", "criteria": ">=", "value": datetime.date(2024, 1, 1)},
+            ("date", "greaterThanOrEqual", "45292"),
+        ),
+        (
+            {"validate": "time", "criteria": "<", "value": datetime.time(12, 0)},
+            ("time", "lessThan", "0.5"),
+        ),
+        ({"validate": "length", "criteria": "<=", "value": 8}, ("textLength", "lessThanOrEqual", "8")),
+    ],
+)
+def test_range_kinds(tmp_path, spec, expected):
+    path = tmp_path / "kinds.xlsx"
+    write_worksheet(_records(4), str(path), data_validation={"sku": spec})
+    dv = _validations(path)["D2:D5"]
+    assert (dv.type, dv.operator, dv.formula1) == expected
+
+
+def test_custom_formula_placeholders(tmp_path):
+    path = tmp_path / "custom.xlsx"
+    write_worksheet(
+        _records(4),
+        str(path),
+        header_row=2,
+        merge_ranges=[(0, 0, 0, 3, "Banner")],
+        data_validation={
+            "sku": {"validate": "custom", "value": "=AND(LEN({col}{first})<=4,ROW()<={last})"}
+        },
+    )
+    # Header on sheet row 3, data on rows 4-7.
+    dv = _validations(path)["D4:D7"]
+    assert dv.type == "custom"
+    assert dv.formula1 == "AND(LEN(D4)<=4,ROW()<=7)"
+
+
+def test_messages(tmp_path):
+    path = tmp_path / "messages.xlsx"
+    write_worksheet(
+        _records(2),
+        str(path),
+        data_validation={
+            "status": {
+                "validate": "list",
+                "source": ["open", "closed"],
+                "input_title": "Status",
+                "input_message": "Pick one",
+                "error_title": "Invalid status",
+                "error_message": "Use the dropdown",
+                "error_type": "warning",
+            }
+        },
+    )
+    dv = _validations(path)["A2:A3"]
+    assert (dv.promptTitle, dv.prompt) == ("Status", "Pick one")
+    assert (dv.errorTitle, dv.error) == ("Invalid status", "Use the dropdown")
+    assert dv.errorStyle == "warning"
+
+
+def test_skipped_without_columns(tmp_path):
+    path = tmp_path / "empty.xlsx"
+    write_worksheet(
+        [], str(path), data_validation={"qty": {"validate": "any", "input_message": "hi"}}
+    )
+    assert _validations(path) == {}
+
+
+def test_header_only_sheet_validates_the_first_row(tmp_path):
+    pa = pytest.importorskip("pyarrow")
+    path = tmp_path / "template.xlsx"
+    template = pa.table(
+        {"status": pa.array([], type=pa.string()), "qty": pa.array([], type=pa.int64())}
+    )
+    write_worksheet(
+        template,
+        str(path),
+        data_validation={"status": {"validate": "list", "source": ["open", "closed"]}},
+    )
+    assert list(_validations(path)) == ["A2"]
+
+
+def test_unknown_column_warns(tmp_path):
+    path = tmp_path / "unknown.xlsx"
+    with pytest.warns(UserWarning, match="unknown column 'nope'"):
+        write_worksheet(
+            _records(),
+            str(path),
+            data_validation={"nope": {"validate": "list", "source": ["a"]}},
+        )
+    assert _validations(path) == {}
+
+
+@pytest.mark.parametrize(
+    "spec,message",
+    [
+        ({"validate": "colour"}, "unknown validate 'colour'"),
+        ({"source": ["a"]}, "missing required option 'validate'"),
+        ({"validate": "list"}, "missing required option 'source'"),
+        ({"validate": "list", "source": []}, "'source' is empty"),
+        ({"validate": "list", "source": "a,b"}, "range reference"),
+        ({"validate": "list", "source": ["x" * 300]}, "255"),
+        ({"validate": "list", "source": ["a"], "drop_down": True}, "unknown option 'drop_down'"),
+        ({"validate": "whole", "value": 1}, "missing required option 'criteria'"),
+        ({"validate": "whole", "criteria": "~", "value": 1}, "unknown criteria"),
+        ({"validate": "whole", "criteria": ">", "value": 1.5}, "must be an int"),
+        ({"validate": "whole", "criteria": ">", "value": 2**40}, "whole-number range"),
+        ({"validate": "whole", "criteria": "between", "minimum": 1}, "'maximum'"),
+        ({"validate": "date", "criteria": ">", "value": "2024-01-01"}, "date or datetime"),
+        ({"validate": "time", "criteria": ">", "value": 1}, "must be a time"),
+        ({"validate": "custom", "value": "=LEN(A1"}, "malformed"),
+        ({"validate": "any", "input_title": "x" * 40}, "32"),
+        ({"validate": "any", "error_type": "loud"}, "unknown error_type"),
+    ],
+)
+def test_invalid_specs_raise(tmp_path, spec, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_records(), str(path), data_validation={"qty": spec})
+    assert not path.exists()
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"a": [1, 2, 3], "b": ["x", "y", "z"]})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(
+        df, str(path), data_validation={"b": {"validate": "list", "source": ["x", "y", "z"]}}
+    )
+    assert list(_validations(path)) == ["B2:B4"]
+
+
+def test_dataframe_fallback_path(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"a": [1, 2, 3, 4]}, kinds=["i"])
+    path = tmp_path / "fallback.xlsx"
+    write_worksheet(
+        df, str(path), data_validation={"a": {"validate": "whole", "criteria": ">", "value": 0}}
+    )
+    assert list(_validations(path)) == ["A2:A5"]
+
+
+def test_multi_sheet_is_per_sheet(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("Orders", _records(3)), ("Plain", _records(3))],
+        str(path),
+        data_validation={"Orders": {"status": {"validate": "list", "source": ["open"]}}},
+    )
+    assert list(_validations(path, "Orders")) == ["A2:A4"]
+    assert _validations(path, "Plain") == {}
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    (
+        FastExcel(str(path))
+        .sheet(
+            "S",
+            _records(6),
+            data_validation={"qty": {"validate": "decimal", "criteria": ">=", "value": 0}},
+        )
+        .save()
+    )
+    assert list(_validations(path, "S")) == ["B2:B7"]
+
+
+def test_csv_warns_that_data_validation_is_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="data_validation"):
+        FastExcel(str(path)).sheet(
+            "S", _records(2), data_validation={"qty": {"validate": "any"}}
+        ).save()
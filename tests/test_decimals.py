@@ -0,0 +1,212 @@
+"""Arrow decimals — ``decimal128`` / ``decimal256`` columns.
+
+Decimals are written as numbers carrying a number format with the column's
+scale. Values with more significant digits than Excel keeps follow
+``wide_decimals``; CSV always prints the exact digits. Cells are read straight
+from the sheet XML.
+"""
+
+import io
+import zipfile
+from decimal import Decimal
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_csv, write_worksheets
+
+from conftest import cell_number, cell_parts, num_format_of, xlsx_book
+
+pa = pytest.importorskip("pyarrow")
+
+#: 19 significant digits — past the 15 Excel keeps.
+WIDE = Decimal("12345678901234567.89")
+
+
+def _table(values, precision=12, scale=2):
+    return pa.table({"amount": pa.array(values, type=pa.decimal128(precision, scale))})
+
+
+def test_written_as_numbers_with_the_scale_format():
+    book = xlsx_book(_table([Decimal("1234.50"), Decimal("-0.05"), None]))
+    cells = cell_parts(book)
+    assert cell_number(cells["A2"]) == 1234.5
+    assert cell_number(cells["A3"]) == -0.05
+    assert "A4" not in cells or "<v>" not in cells["A4"][1]
+    assert num_format_of(book, cells["A2"][0]) == "0.00"
+
+
+def test_zero_and_negative_scales_show_whole_numbers():
+    book = xlsx_book(
+        pa.table(
+            {
+                "units": pa.array([Decimal(42)], type=pa.decimal128(5, 0)),
+                "thousands": pa.array([Decimal("12E+3")], type=pa.decimal128(5, -3)),
+            }
+        )
+    )
+    cells = cell_parts(book)
+    assert cell_number(cells["A2"]) == 42
+    assert cell_number(cells["B2"]) == 12000
+    assert num_format_of(book, cells["A2"][0]) == "0"
+    assert num_format_of(book, cells["B2"][0]) == "0"
+
+
+def test_column_format_wins():
+    book = xlsx_book(
+        _table([Decimal("3.25")], scale=4),
+        column_formats={"amount": Format().set_num_format("#,##0.0")},
+    )
+    assert num_format_of(book, cell_parts(book)["A2"][0]) == "#,##0.0"
+
+
+def test_banded_rows_keep_the_scale_format():
+    book = xlsx_book(
+        _table([Decimal("1.5"), Decimal("2.5"), Decimal("3.5")], scale=3),
+        banded_rows="#EEEEEE",
+    )
+    cells = cell_parts(book)
+    assert cells["A2"][0] != cells["A3"][0]
+    assert num_format_of(book, cells["A2"][0]) == "0.000"
+    assert num_format_of(book, cells["A3"][0]) == "0.000"
+
+
+def test_wide_values_are_rounded_by_default():
+    cells = cell_parts(xlsx_book(_table([WIDE], precision=20)))
+    assert cell_number(cells["A2"]) == float(WIDE)
+
+
+def test_wide_values_as_text_keep_every_digit():
+    cells = cell_parts(
+        xlsx_book(_table([WIDE, Decimal("1.25")], precision=20), wide_decimals="text")
+    )
+    assert "12345678901234567.89" in cells["A2"][1]
+    assert cell_number(cells["A3"]) == 1.25
+
+
+def test_wide_values_can_raise():
+    with pytest.raises(ValueError, match=r"column 'amount'.*12345678901234567\.89"):
+        xlsx_book(_table([Decimal("1.25"), WIDE], precision=20), wide_decimals="error")
+
+
+class _Series(list):
+    def tolist(self):
+        return list(self)
+
+
+class _Dtype:
+    kind = "O"
+
+
+class _Frame:
+    """Pandas-shaped: an Arrow stream, and columns for the fallback writer."""
+
+    def __init__(self, table):
+        self._table = table
+        self.columns = table.column_names
+        self.dtypes = [_Dtype() for _ in self.columns]
+
+    def __arrow_c_stream__(self, requested_schema=None):
+        return self._table.__arrow_c_stream__(requested_schema)
+
+    def __len__(self):
+        return self._table.num_rows
+
+    def __getitem__(self, key):
+        return _Series(self._table.column(key).to_pylist())
+
+
+def test_wide_values_raise_for_dataframes():
+    # The error must not send the frame to the column-by-column writer, which
+    # would round the value it is there to catch.
+    frame = _Frame(_table([Decimal("1.25"), WIDE], precision=20))
+    with pytest.raises(ValueError, match=r"column 'amount'.*12345678901234567\.89"):
+        xlsx_book(frame, wide_decimals="error")
+
+
+def test_wide_values_raise_for_pandas_arrow_dtypes():
+    pd = pytest.importorskip("pandas")
+    values = pd.array([1, 10**20 + 1], dtype=pd.ArrowDtype(pa.decimal128(38, 0)))
+    with pytest.raises(ValueError, match=r"column 'amount'.*100000000000000000001"):
+        xlsx_book(pd.DataFrame({"amount": values}), wide_decimals="error")
+
+
+def test_wide_values_raise_for_polars_decimals():
+    pl = pytest.importorskip("polars")
+    frame = pl.DataFrame(
+        {"amount": [Decimal(1), Decimal(10**20 + 1)]}, schema={"amount": pl.Decimal(38, 0)}
+    )
+    with pytest.raises(ValueError, match=r"column 'amount'.*100000000000000000001"):
+        xlsx_book(frame, wide_decimals="error")
+
+
+def test_trailing_zeros_are_not_significant():
+    # 10**17 has one significant digit, so it is not wide.
+    table = _table([Decimal("100000000000000000.00")], precision=20)
+    xlsx_book(table, wide_decimals="error")
+
+
+def test_unknown_mode_raises():
+    with pytest.raises(ValueError, match="wide_decimals must be"):
+        xlsx_book(_table([Decimal(1)]), wide_decimals="round")
+
+
+def test_decimal256():
+    big = Decimal("123456789012345678901234567890123456789012.5")
+    table = pa.table(
+        {
+            "small": pa.array([Decimal("7.125")], type=pa.decimal256(40, 3)),
+            "big": pa.array([big], type=pa.decimal256(50, 1)),
+        }
+    )
+    cells = cell_parts(xlsx_book(table, wide_decimals="text"))
+    assert cell_number(cells["A2"]) == 7.125
+    assert str(big) in cells["B2"][1]
+
+
+def test_csv_prints_the_exact_digits(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_table([WIDE, Decimal("-0.05"), None], precision=20), str(path))
+    assert path.read_text().splitlines() == [
+        "amount",
+        "12345678901234567.89",
+        "-0.05",
+        "",
+    ]
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Text", _table([WIDE], precision=20)), ("Number", _table([WIDE], precision=20))],
+        buf,
+        wide_decimals={"Text": "text"},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert "12345678901234567.89" in cell_parts(book, 1)["A2"][1]
+    assert "<v>" in cell_parts(book, 2)["A2"][1]
+
+
+def test_parallel_matches_serial():
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [
+                (f"S{scale}", _table([Decimal(n) / 2 for n in range(30)], scale=scale))
+                for scale in range(1, 5)
+            ],
+            buf,
+            banded_rows={"S2": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+def test_fastexcel_sheet_option():
+    buf = io.BytesIO()
+    writer = FastExcel(buf)
+    writer.sheet("Ledger", _table([WIDE], precision=20), wide_decimals="error")
+    with pytest.raises(ValueError, match="column 'amount'"):
+        writer.save()
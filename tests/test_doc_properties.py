@@ -0,0 +1,172 @@
+"""Document properties — ``properties`` maps onto the workbook's docProps parts.
+
+Standard fields land in ``docProps/core.xml`` (and ``company``/``manager`` in
+``app.xml``), custom ones in ``docProps/custom.xml`` with their Excel type.
+A fixed ``created`` date is what makes repeated exports byte-identical.
+"""
+
+import datetime
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+
+def _part(path, name):
+    return zipfile.ZipFile(path).read(name).decode()
+
+
+def _custom(path):
+    """``{name: (type, text)}`` for every custom property."""
+    xml = _part(path, "docProps/custom.xml")
+    return {
+        name: (kind, text)
+        for name, kind, text in re.findall(
+            r'name="([^"]+)"><vt:(\w+)>(.*?)</vt:\w+>', xml
+        )
+    }
+
+
+def _created(path):
+    return re.search(r"<dcterms:created[^>]*>(.*?)<", _part(path, "docProps/core.xml")).group(1)
+
+
+def test_standard_fields(tmp_path):
+    path = tmp_path / "props.xlsx"
+    write_worksheet(
+        [{"a": 1}],
+        str(path),
+        properties={
+            "title": "Q3 revenue",
+            "subject": "Revenue",
+            "author": "Finance",
+            "manager": "CFO",
+            "company": "Acme",
+            "category": "Reports",
+            "keywords": "revenue, q3",
+            "comment": "Nightly export",
+            "status": "Final",
+        },
+    )
+    core = _part(path, "docProps/core.xml")
+    for tag, text in [
+        ("dc:title", "Q3 revenue"),
+        ("dc:subject", "Revenue"),
+        ("dc:creator", "Finance"),
+        ("cp:keywords", "revenue, q3"),
+        ("dc:description", "Nightly export"),
+        ("cp:category", "Reports"),
+        ("cp:contentStatus", "Final"),
+    ]:
+        assert f"<{tag}>{text}</{tag}>" in core
+    app = _part(path, "docProps/app.xml")
+    assert "<Company>Acme</Company>" in app
+    assert "<Manager>CFO</Manager>" in app
+
+
+def test_custom_properties_keep_their_type(tmp_path):
+    path = tmp_path / "custom.xlsx"
+    write_worksheet(
+        [{"a": 1}],
+        str(path),
+        properties={
+            "custom": {
+                "classification": "internal",
+                "generated_by": "nightly-export",
+                "revision": 3,
+                "rows": 2**40,
+                "ratio": 0.5,
+                "approved": True,
+                "as_of": datetime.date(2024, 5, 6),
+                "run_at": datetime.datetime(2024, 5, 6, 7, 8, 9),
+            }
+        },
+    )
+    assert _custom(path) == {
+        "classification": ("lpwstr", "internal"),
+        "generated_by": ("lpwstr", "nightly-export"),
+        "revision": ("i4", "3"),
+        # Wider than 32 bits, so stored as a real.
+        "rows": ("r8", "1099511627776"),
+        "ratio": ("r8", "0.5"),
+        "approved": ("bool", "true"),
+        "as_of": ("filetime", "2024-05-06T00:00:00Z"),
+        "run_at": ("filetime", "2024-05-06T07:08:09Z"),
+    }
+
+
+def test_created_is_pinned_and_aware_datetimes_become_utc(tmp_path):
+    path = tmp_path / "created.xlsx"
+    plus_two = datetime.timezone(datetime.timedelta(hours=2))
+    write_worksheet(
+        [{"a": 1}],
+        str(path),
+        properties={"created": datetime.datetime(2024, 1, 2, 3, 4, 5, tzinfo=plus_two)},
+    )
+    assert _created(path) == "2024-01-02T01:04:05Z"
+
+
+def test_created_date(tmp_path):
+    path = tmp_path / "date.xlsx"
+    write_worksheet([{"a": 1}], str(path), properties={"created": datetime.date(2024, 1, 2)})
+    assert _created(path) == "2024-01-02T00:00:00Z"
+
+
+def test_fixed_created_date_gives_identical_bytes():
+    props = {"author": "Finance", "created": datetime.datetime(2024, 1, 1)}
+    outputs = []
+    for _ in range(2):
+        buf = io.BytesIO()
+        write_worksheet([{"a": 1, "b": "x"}], buf, properties=props)
+        outputs.append(buf.getvalue())
+    assert outputs[0] == outputs[1]
+
+
+def test_multi_sheet(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("A", [{"a": 1}]), ("B", [{"b": 2}])],
+        str(path),
+        properties={"title": "Both", "custom": {"generated_by": "job"}},
+    )
+    assert "<dc:title>Both</dc:title>" in _part(path, "docProps/core.xml")
+    assert _custom(path) == {"generated_by": ("lpwstr", "job")}
+
+
+def test_fastexcel_builder(tmp_path):
+    for sheets in (["S"], ["S", "T"]):
+        path = tmp_path / f"builder{len(sheets)}.xlsx"
+        writer = FastExcel(str(path), properties={"company": "Acme"})
+        for name in sheets:
+            writer.sheet(name, [{"a": 1}])
+        writer.save()
+        assert "<Company>Acme</Company>" in _part(path, "docProps/app.xml")
+
+
+@pytest.mark.parametrize(
+    "spec,message",
+    [
+        ("Acme", "properties must be a dict"),
+        ({"owner": "x"}, "unknown option 'owner'"),
+        ({"title": 5}, "'title' must be a string"),
+        ({"created": "2024-01-01"}, "'created' must be a date or datetime"),
+        ({"created": datetime.date(1800, 1, 1)}, "'created'"),
+        ({"custom": ["x"]}, "'custom' must be a dict"),
+        ({"custom": {1: "x"}}, "must be a string"),
+        ({"custom": {"x": [1]}}, "must be a str, int, float, bool, date or datetime"),
+    ],
+)
+def test_invalid_specs_raise(tmp_path, spec, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet([{"a": 1}], str(path), properties=spec)
+    assert not path.exists()
+
+
+def test_csv_warns_that_properties_are_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="properties"):
+        FastExcel(str(path), properties={"title": "x"}).sheet("S", [{"a": 1}]).save()
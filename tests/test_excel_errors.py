@@ -0,0 +1,124 @@
+"""Excel error values and explicit blanks — ``ExcelError``, ``Blank`` and
+``error_columns``.
+
+Cells are read straight from the sheet XML.
+"""
+
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import Blank, ExcelError, FastExcel, Format, write_csv, write_worksheets
+
+from conftest import error_cells, sheet_xml, text_cells, xlsx_book
+
+
+def test_every_error_value():
+    errors = [
+        ExcelError.NA,
+        ExcelError.DIV0,
+        ExcelError.NAME,
+        ExcelError.NULL,
+        ExcelError.NUM,
+        ExcelError.REF,
+        ExcelError.VALUE,
+    ]
+    book = xlsx_book([{"e": e} for e in errors])
+    assert list(error_cells(book).values()) == [str(e) for e in errors]
+    assert [str(e) for e in errors] == [
+        "#N/A",
+        "#DIV/0!",
+        "#NAME?",
+        "#NULL!",
+        "#NUM!",
+        "#REF!",
+        "#VALUE!",
+    ]
+
+
+def test_error_among_numbers():
+    book = xlsx_book([{"price": 1.5}, {"price": ExcelError.NA}, {"price": 2.5}])
+    assert error_cells(book) == {"A3": "#N/A"}
+    assert '<c r="A4"><v>2.5</v></c>' in sheet_xml(book)
+
+
+def test_blank_carries_its_format():
+    boxed = Format().set_border("thin")
+    sheet = sheet_xml(xlsx_book([{"a": 1, "note": Blank(boxed)}, {"a": 2, "note": Blank()}]))
+    assert re.search(r'<c r="B2" s="[1-9]\d*"/>', sheet)
+    assert 'r="B3"' not in sheet
+
+
+def test_blank_ignores_na_rep():
+    texts = text_cells(xlsx_book([{"a": None, "b": Blank()}], na_rep="n/a"))
+    assert texts["A2"] == "n/a"
+    assert "B2" not in texts
+
+
+def test_error_columns():
+    rows = [{"lookup": "#N/A"}, {"lookup": "found"}, {"lookup": "#REF!"}, {"lookup": "#n/a"}]
+    book = xlsx_book(rows, error_columns=["lookup"])
+    assert error_cells(book) == {"A2": "#N/A", "A4": "#REF!"}
+    assert text_cells(book)["A3"] == "found"
+    # Only an exact spelling counts.
+    assert text_cells(book)["A5"] == "#n/a"
+    # Without the option the spelling is plain text.
+    assert error_cells(xlsx_book(rows)) == {}
+
+
+def test_error_columns_unknown_name_warns():
+    with pytest.warns(UserWarning, match="error_columns: unknown column 'nope', skipped"):
+        xlsx_book([{"a": "#N/A"}], error_columns=["nope"])
+
+
+def test_csv():
+    buf = io.BytesIO()
+    write_csv([{"a": ExcelError.DIV0, "b": Blank(), "c": 1}], buf)
+    assert buf.getvalue().decode().splitlines() == ["a,b,c", "#DIV/0!,,1"]
+
+
+def test_write_worksheets_and_fastexcel():
+    rows = [{"v": "#N/A"}]
+    buf = io.BytesIO()
+    write_worksheets([("A", rows), ("B", rows)], buf, error_columns={"B": ["v"]})
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert error_cells(book, 1) == {}
+    assert error_cells(book, 2) == {"A2": "#N/A"}
+
+    buf = io.BytesIO()
+    FastExcel(buf).sheet("S", rows, error_columns=["v"]).save()
+    assert error_cells(zipfile.ZipFile(io.BytesIO(buf.getvalue()))) == {"A2": "#N/A"}
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def test_arrow_error_columns():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"v": pa.array(["#N/A", "ok", None, "#VALUE!"], type=pa.string())})
+    book = xlsx_book(table, error_columns=["v"])
+    assert error_cells(book) == {"A2": "#N/A", "A5": "#VALUE!"}
+    assert text_cells(book)["A3"] == "ok"
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"v": pa.array(["#N/A", "ok"], type=pa.string()), "x": pa.array([1.5, 2.5])})
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            error_columns={"A": ["v"]},
+            banded_rows={"general": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
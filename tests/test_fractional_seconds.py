@@ -0,0 +1,101 @@
+"""Fractional seconds in datetimes — Excel serials and CSV ``datetime_precision``.
+
+Python ``datetime`` values and Arrow timestamps keep their microseconds in the
+cell value; CSV prints as many digits as a value needs unless told otherwise.
+Cells are read straight from the sheet XML.
+"""
+
+import datetime
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_csv
+
+from conftest import cell_values, xlsx_book
+
+MILLIS = datetime.datetime(2024, 3, 1, 9, 30, 15, 123000)
+MICROS = datetime.datetime(2024, 3, 1, 9, 30, 15, 123456)
+WHOLE = datetime.datetime(2024, 3, 1, 9, 30, 15)
+
+
+def _serial(dt):
+    delta = dt - datetime.datetime(1899, 12, 30)
+    return delta.days + (delta.seconds + delta.microseconds / 1e6) / 86400
+
+
+def _rows(*values):
+    return [{"at": v} for v in values]
+
+
+def test_records_keep_milliseconds():
+    values = cell_values(xlsx_book(_rows(MILLIS, MICROS)))
+    # Well inside a millisecond, which a whole-second value would miss by 123.
+    assert values["A2"] == pytest.approx(_serial(MILLIS), abs=1e-9)
+    assert values["A3"] == pytest.approx(_serial(MICROS), abs=1e-9)
+    assert values["A2"] - _serial(WHOLE) > 1e-6
+
+
+def test_csv_digits_follow_the_value(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_rows(WHOLE, MILLIS, MICROS), str(path))
+    assert path.read_text().splitlines() == [
+        "at",
+        "2024-03-01T09:30:15",
+        "2024-03-01T09:30:15.123",
+        "2024-03-01T09:30:15.123456",
+    ]
+
+
+def test_csv_fixed_precision_cuts_off(tmp_path):
+    path = tmp_path / "out.csv"
+    late = datetime.datetime(2024, 3, 1, 9, 30, 15, 999999)
+    write_csv(_rows(WHOLE, late), str(path), datetime_precision=3)
+    assert path.read_text().splitlines()[1:] == [
+        "2024-03-01T09:30:15.000",
+        "2024-03-01T09:30:15.999",
+    ]
+    write_csv(_rows(MICROS), str(path), datetime_precision=0)
+    assert path.read_text().splitlines()[1] == "2024-03-01T09:30:15"
+
+
+def test_csv_precision_out_of_range(tmp_path):
+    with pytest.raises(ValueError, match="datetime_precision must be between 0 and 6, got 9"):
+        write_csv(_rows(MICROS), str(tmp_path / "out.csv"), datetime_precision=9)
+
+
+def test_fastexcel_csv_target(tmp_path):
+    path = tmp_path / "out.csv"
+    FastExcel(str(path), datetime_precision=6).sheet("S", _rows(MILLIS)).save()
+    assert path.read_text().splitlines()[1] == "2024-03-01T09:30:15.123000"
+
+
+def test_arrow_keeps_milliseconds():
+    pa = pytest.importorskip("pyarrow")
+    for unit in ("ms", "us", "ns"):
+        table = pa.table({"at": pa.array([MILLIS], type=pa.timestamp(unit))})
+        assert cell_values(xlsx_book(table))["A2"] == pytest.approx(_serial(MILLIS), abs=1e-9)
+
+
+def test_arrow_before_1970():
+    pa = pytest.importorskip("pyarrow")
+    early = datetime.datetime(1969, 12, 31, 23, 0, 0, 250000)
+    table = pa.table({"at": pa.array([early], type=pa.timestamp("us"))})
+    assert cell_values(xlsx_book(table))["A2"] == pytest.approx(_serial(early), abs=1e-9)
+
+
+def test_arrow_csv(tmp_path):
+    pa = pytest.importorskip("pyarrow")
+    path = tmp_path / "out.csv"
+    table = pa.table({"at": pa.array([WHOLE, MILLIS, MICROS], type=pa.timestamp("us"))})
+    write_csv(table, str(path))
+    assert path.read_text().splitlines()[1:] == [
+        "2024-03-01T09:30:15",
+        "2024-03-01T09:30:15.123",
+        "2024-03-01T09:30:15.123456",
+    ]
+    write_csv(table, str(path), datetime_precision=1)
+    assert path.read_text().splitlines()[1:] == [
+        "2024-03-01T09:30:15.0",
+        "2024-03-01T09:30:15.1",
+        "2024-03-01T09:30:15.1",
+    ]
@@ -0,0 +1,272 @@
+"""Writing a pandas DataFrame's index — ``index`` and ``merge_index_cells``.
+
+Cells and merged ranges are read straight from the sheet XML. The frames
+below are pandas-shaped stand-ins, with and without an Arrow stream, so both
+write paths are covered without pandas installed.
+"""
+
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+from conftest import cell_values, merged_ranges, sheet_xml, xlsx_book
+
+
+# ---------------------------------------------------------------------------
+# Stand-ins
+# ---------------------------------------------------------------------------
+
+
+class RangeIndex:
+    nlevels = 1
+    names = [None]
+
+
+class Index:
+    nlevels = 1
+
+    def __init__(self, values, name=None):
+        self.names = [name]
+        self.values = [list(values)]
+
+
+class MultiIndex:
+    def __init__(self, tuples, names):
+        self.nlevels = len(names)
+        self.names = names
+        self.values = [list(level) for level in zip(*tuples)]
+        self.codes = [[sorted(set(level)).index(v) for v in level] for level in self.values]
+
+
+class _Series:
+    def __init__(self, values):
+        self._values = values
+
+    def tolist(self):
+        return list(self._values)
+
+
+class _Dtype:
+    def __init__(self, kind):
+        self.kind = kind
+
+
+class _Frame:
+    """Pandas-shaped, without ``__arrow_c_stream__``."""
+
+    def __init__(self, data, index=None):
+        self._data = data
+        self.columns = list(data)
+        self.index = index or RangeIndex()
+        self.dtypes = [_Dtype("f" if isinstance(v[0], float) else "O") for v in data.values()]
+
+    def __len__(self):
+        return len(next(iter(self._data.values())))
+
+    def __getitem__(self, key):
+        if isinstance(key, list):
+            return type(self)({k: self._data[k] for k in key}, self.index)
+        return _Series(self._data[key])
+
+    def reset_index(self, drop=False):
+        if drop or isinstance(self.index, RangeIndex):
+            return type(self)(dict(self._data))
+        data = {}
+        for i, (name, values) in enumerate(zip(self.index.names, self.index.values)):
+            data[name or ("index" if self.index.nlevels == 1 else f"level_{i}")] = values
+        return type(self)({**data, **self._data})
+
+
+class _ArrowFrame(_Frame):
+    """Streams like pandas: any index but a ``RangeIndex`` trails as columns."""
+
+    def __arrow_c_stream__(self, requested_schema=None):
+        import pyarrow as pa
+
+        data = dict(self._data)
+        if not isinstance(self.index, RangeIndex):
+            for i, (name, values) in enumerate(zip(self.index.names, self.index.values)):
+                data[name or f"__index_level_{i}__"] = values
+        return pa.table(data).__arrow_c_stream__(requested_schema)
+
+
+FRAMES = pytest.mark.parametrize("frame_cls", [_Frame, _ArrowFrame], ids=["fallback", "arrow"])
+
+
+def _skip_without_arrow(frame_cls):
+    if frame_cls is _ArrowFrame:
+        pytest.importorskip("pyarrow")
+
+
+def _by_id(frame_cls):
+    return frame_cls({"sales": [1.0, 2.0]}, Index(["x", "y"], name="id"))
+
+
+def _by_region(frame_cls):
+    stores = [("N", "a"), ("N", "b"), ("S", "a"), ("S", "c"), ("S", "d")]
+    index = MultiIndex(stores, ["region", None])
+    return frame_cls({"sales": [0.0, 1.0, 2.0, 3.0, 4.0]}, index)
+
+
+# ---------------------------------------------------------------------------
+# index
+# ---------------------------------------------------------------------------
+
+
+@FRAMES
+def test_index_left_out_by_default(frame_cls):
+    _skip_without_arrow(frame_cls)
+    assert cell_values(xlsx_book(_by_id(frame_cls))) == {"A1": "sales", "A2": 1.0, "A3": 2.0}
+
+
+@FRAMES
+def test_index_as_leading_bold_columns(frame_cls):
+    _skip_without_arrow(frame_cls)
+    book = xlsx_book(_by_id(frame_cls), index=True)
+    cells = cell_values(book)
+    assert cells == {"A1": "id", "B1": "sales", "A2": "x", "B2": 1.0, "A3": "y", "B3": 2.0}
+    assert re.search(r'<col min="1" max="1"[^>]*style="\d+"', sheet_xml(book))
+    assert "<b/>" in book.read("xl/styles.xml").decode()
+
+
+@FRAMES
+def test_columns_keeps_the_index_in_front(frame_cls):
+    _skip_without_arrow(frame_cls)
+    frame = frame_cls({"cost": [3.0, 4.0], "sales": [1.0, 2.0]}, Index(["x", "y"], name="id"))
+    cells = cell_values(xlsx_book(frame, index=True, columns=["sales"], rename={"id": "ID"}))
+    assert cells == {"A1": "ID", "B1": "sales", "A2": "x", "B2": 1.0, "A3": "y", "B3": 2.0}
+
+
+# ---------------------------------------------------------------------------
+# merge_index_cells
+# ---------------------------------------------------------------------------
+
+
+@FRAMES
+def test_outer_levels_are_merged(frame_cls):
+    _skip_without_arrow(frame_cls)
+    book = xlsx_book(_by_region(frame_cls), index=True)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("A1", "B1", "C1")] == ["region", "level_1", "sales"]
+    assert (cells["A2"], cells["A4"]) == ("N", "S")
+    assert [cells[f"B{r}"] for r in range(2, 7)] == ["a", "b", "a", "c", "d"]
+    # The cells under a merged label are blank.
+    assert not {"A3", "A5", "A6"} & set(cells)
+    assert merged_ranges(book) == ["A2:A3", "A4:A6"]
+
+
+@FRAMES
+def test_runs_end_where_an_outer_level_changes(frame_cls):
+    _skip_without_arrow(frame_cls)
+    index = MultiIndex([("N", "x", "1"), ("N", "x", "2"), ("S", "x", "3")], ["a", "b", "c"])
+    book = xlsx_book(frame_cls({"v": [1.0, 2.0, 3.0]}, index), index=True)
+    assert merged_ranges(book) == ["A2:A3", "B2:B3"]
+    assert cell_values(book)["B4"] == "x"
+
+
+@FRAMES
+def test_merge_index_cells_off(frame_cls):
+    _skip_without_arrow(frame_cls)
+    book = xlsx_book(_by_region(frame_cls), index=True, merge_index_cells=False)
+    assert merged_ranges(book) == []
+    assert [cell_values(book)[f"A{r}"] for r in range(2, 7)] == ["N", "N", "S", "S", "S"]
+
+
+def test_table_needs_merges_off():
+    frame = _by_region(_Frame)
+    with pytest.raises(ValueError, match="merge_index_cells and table cannot be combined"):
+        xlsx_book(frame, index=True, table=True)
+    book = xlsx_book(frame, index=True, merge_index_cells=False, table=True)
+    assert "xl/tables/table1.xml" in book.namelist()
+
+
+@FRAMES
+def test_split_merges_each_sheet_on_its_own(frame_cls):
+    _skip_without_arrow(frame_cls)
+    # With the header on the sheet's fourth-last row only three rows fit.
+    header = 1_048_575 - 3
+    top = header + 2
+    buf = io.BytesIO()
+    write_worksheet(
+        _by_region(frame_cls), buf, header_row=header, overflow="split", index=True
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    # N fills the first two rows; S starts on the last row and goes on.
+    assert merged_ranges(book, 1) == [f"A{top}:A{top + 1}"]
+    assert cell_values(book, 1)[f"A{top + 2}"] == "S"
+    assert merged_ranges(book, 2) == [f"A{top}:A{top + 1}"]
+    assert cell_values(book, 2)[f"A{top}"] == "S"
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Plain", _by_region(_Frame)), ("Indexed", _by_region(_Frame))],
+        buf,
+        index={"Indexed": True},
+        merge_index_cells={"general": False},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_values(book, 1)["A1"] == "sales"
+    assert cell_values(book, 2)["A3"] == "N"
+    assert merged_ranges(book, 2) == []
+
+
+def test_arrow_parallel_matches_serial():
+    pytest.importorskip("pyarrow")
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", _by_region(_ArrowFrame)), ("B", _by_id(_ArrowFrame))],
+            buf,
+            index={"general": True},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+def test_fastexcel_keeps_merges_off():
+    # A given False reaches the writer, rather than falling back to its
+    # default of merging.
+    for names in (["S"], ["S", "T"]):
+        buf = io.BytesIO()
+        builder = FastExcel(buf)
+        for name in names:
+            builder.sheet(name, _by_region(_Frame), index=True, merge_index_cells=False)
+        builder.save()
+        book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+        assert merged_ranges(book) == []
+
+
+def test_fastexcel_csv_ignores_it(tmp_path):
+    path = tmp_path / "out.csv"
+    with pytest.warns(UserWarning, match="index"):
+        FastExcel(str(path)).sheet("S", [{"a": 1}], index=True).save()
+
+
+# ---------------------------------------------------------------------------
+# pandas
+# ---------------------------------------------------------------------------
+
+
+def test_pandas():
+    pd = pytest.importorskip("pandas")
+    pytest.importorskip("pyarrow")
+    df = pd.DataFrame(
+        {"region": ["N", "N", "S"], "store": ["a", "b", "a"], "sales": [1.0, 2.0, 3.0]}
+    ).set_index(["region", "store"])
+    assert cell_values(xlsx_book(df))["A1"] == "sales"
+    book = xlsx_book(df, index=True)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("A1", "B1", "C1", "A2", "B3", "C4")] == [
+        "region", "store", "sales", "N", "b", 3.0
+    ]
+    assert merged_ranges(book) == ["A2:A3"]
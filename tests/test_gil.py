@@ -0,0 +1,95 @@
+"""GIL release — saving, and the Arrow batch loop, let other threads run.
+
+A background thread waits for the write to start, then needs the GIL to note
+that it ran. The switch interval is set far longer than any write, so the
+thread cannot take the GIL by force: it only runs if the write releases it.
+The buffer's ``.write`` checks whether it did, since it runs once the GIL is
+back at the end of the save. An Arrow stream checks earlier, before its last
+batch is read, since the save would release the GIL anyway.
+"""
+
+import io
+import sys
+import threading
+
+import pytest
+
+from rustpy_xlsxwriter import write_worksheet, write_worksheets
+
+ROWS = [
+    {"id": i, "name": f"name {i}", "score": i * 0.5, "note": "x" * 40}
+    for i in range(20_000)
+]
+
+
+class _Sink(io.BytesIO):
+    """A buffer that notes whether the background thread had run when the
+    bytes arrived, or at an earlier :meth:`note`."""
+
+    def __init__(self, ran):
+        super().__init__()
+        self._ran = ran
+        self.had_run = None
+
+    def note(self):
+        if self.had_run is None:
+            self.had_run = bool(self._ran)
+
+    def write(self, data):
+        self.note()
+        return super().write(data)
+
+
+def _other_thread_ran_during(write):
+    """Whether a thread waiting on the GIL ran Python code during ``write(sink)``."""
+    started = threading.Event()
+    done = threading.Event()
+    ran = []
+
+    def note_a_run():
+        started.wait()
+        ran.append(True)
+        done.wait()
+
+    old = sys.getswitchinterval()
+    sys.setswitchinterval(60)
+    thread = threading.Thread(target=note_a_run)
+    thread.start()
+    try:
+        sink = _Sink(ran)
+        started.set()
+        write(sink)
+    finally:
+        done.set()
+        thread.join()
+        sys.setswitchinterval(old)
+    return sink.had_run
+
+
+def test_save_releases_the_gil():
+    # The records loop itself holds the GIL; only the save can free it.
+    assert _other_thread_ran_during(lambda sink: write_worksheet(ROWS, sink))
+
+
+def test_multi_sheet_save_releases_the_gil():
+    sheets = [("A", ROWS), ("B", ROWS)]
+    assert _other_thread_ran_during(lambda sink: write_worksheets(sheets, sink))
+
+
+def test_arrow_batches_release_the_gil():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.Table.from_pylist(ROWS)
+
+    def write(sink):
+        *first, last = table.to_batches(max_chunksize=5_000)
+
+        def batches():
+            # Reading a batch takes the GIL back, so check here, with the
+            # earlier batches written and the save still to come.
+            yield from first
+            sink.note()
+            yield last
+
+        write_worksheet(pa.RecordBatchReader.from_batches(table.schema, batches()), sink)
+
+    assert _other_thread_ran_during(write)
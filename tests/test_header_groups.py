@@ -0,0 +1,157 @@
+"""Multi-row headers — ``header_groups``.
+
+Cells and merged ranges are read straight from the sheet XML.
+"""
+
+import io
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheets
+
+from conftest import cell_values, merged_ranges, sheet_xml, xlsx_book
+
+ROWS = [{"sku": "A-1", "Q1.Revenue": 120, "Q1.Cost": 80, "Q2.Revenue": 140}]
+
+
+def test_groups_are_merged_above_the_leaf_names():
+    book = xlsx_book(ROWS, header_groups=".", header_row=1)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("B1", "D1")] == ["Q1", "Q2"]
+    assert [cells[c] for c in ("A2", "B2", "C2", "D2")] == ["sku", "Revenue", "Cost", "Revenue"]
+    assert (cells["B3"], cells["D3"]) == (120, 140)
+    # A single-column group is a plain cell; a column with no group is blank.
+    assert merged_ranges(book) == ["B1:C1"]
+    assert "A1" not in cells
+
+
+def test_groups_split_by_full_path():
+    rows = [{"2024.Q1.a": 1, "2024.Q2.a": 2, "2025.Q2.a": 3}]
+    book = xlsx_book(rows, header_groups=".", header_row=2)
+    cells = cell_values(book)
+    assert (cells["A1"], cells["C1"]) == ("2024", "2025")
+    assert [cells[c] for c in ("A2", "B2", "C2")] == ["Q1", "Q2", "Q2"]
+    assert merged_ranges(book) == ["A1:B1"]
+
+
+def test_unset_header_row_moves_below_the_groups():
+    book = xlsx_book(ROWS, header_groups=".", autofilter=True, freeze_row=1)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("B1", "A2", "B2", "B3")] == ["Q1", "sku", "Revenue", 120]
+    assert merged_ranges(book) == ["B1:C1"]
+    assert '<autoFilter ref="A2:D3"/>' in sheet_xml(book)
+    assert 'ySplit="2"' in sheet_xml(book)
+
+
+def test_header_row_must_leave_room():
+    with pytest.raises(ValueError, match="pass header_row=1 or more, or leave it unset, got 0"):
+        xlsx_book(ROWS, header_groups=".", header_row=0)
+    with pytest.raises(ValueError, match="header_groups must be a separator"):
+        xlsx_book(ROWS, header_groups="", header_row=1)
+
+
+def test_merge_over_the_group_rows():
+    with pytest.raises(ValueError, match=r"reaches row 1, but rows 1 to 1 hold the header groups"):
+        xlsx_book(ROWS, header_groups=".", header_row=2, merge_ranges=[(0, 0, 1, 3, "Title")])
+    book = xlsx_book(ROWS, header_groups=".", header_row=2, merge_ranges=[(0, 0, 0, 3, "Title")])
+    assert merged_ranges(book) == ["A1:D1", "B2:C2"]
+
+
+def test_table_cannot_be_combined():
+    with pytest.raises(ValueError, match="header_groups and table cannot be combined"):
+        xlsx_book(ROWS, header_groups=".", header_row=1, table=True)
+
+
+def test_autofilter_and_column_options_use_the_header_row():
+    book = xlsx_book(
+        ROWS,
+        header_groups=".",
+        header_row=1,
+        autofilter=True,
+        id_columns=["Q1.Cost"],
+    )
+    assert '<autoFilter ref="A2:D3"/>' in sheet_xml(book)
+    assert cell_values(book)["C3"] == "80"
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Flat", ROWS), ("Grouped", ROWS)],
+        buf,
+        header_groups={"Grouped": "."},
+        header_row={"Grouped": 1},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_values(book, 1)["B1"] == "Q1.Revenue"
+    assert cell_values(book, 2)["B2"] == "Revenue"
+
+
+def test_write_worksheets_without_header_row():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Flat", ROWS), ("Grouped", ROWS)],
+        buf,
+        header_groups={"Grouped": "."},
+        header_row={"Flat": 0},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_values(book, 1)["B2"] == 120
+    assert [cell_values(book, 2)[c] for c in ("B1", "B2", "B3")] == ["Q1", "Revenue", 120]
+
+
+def test_fastexcel_csv_ignores_it(tmp_path):
+    path = tmp_path / "out.csv"
+    with pytest.warns(UserWarning, match="header_groups"):
+        FastExcel(str(path)).sheet("S", ROWS, header_groups=".").save()
+    assert path.read_text().splitlines()[0] == "sku,Q1.Revenue,Q1.Cost,Q2.Revenue"
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def _table(pa):
+    return pa.table({"sku": ["A-1"], "Q1.Revenue": [120.0], "Q1.Cost": [80.0]})
+
+
+def test_arrow():
+    pa = pytest.importorskip("pyarrow")
+    book = xlsx_book(_table(pa), header_groups=".", header_row=1)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("B1", "A2", "B2", "C2", "C3")] == ["Q1", "sku", "Revenue", "Cost", 80]
+    assert merged_ranges(book) == ["B1:C1"]
+    assert cell_values(xlsx_book(_table(pa), header_groups="."))["C3"] == 80
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = _table(pa)
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            header_groups={"general": "."},
+            header_row={"general": 1},
+            bold_headers=True,
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+def test_pandas_multiindex():
+    pd = pytest.importorskip("pandas")
+    pytest.importorskip("pyarrow")
+    columns = pd.MultiIndex.from_tuples([("sku", ""), ("Q1", "Revenue"), ("Q1", "Cost")])
+    df = pd.DataFrame([["A-1", 120, 80]], columns=columns)
+    book = xlsx_book(df, header_groups=".", header_row=1)
+    cells = cell_values(book)
+    assert [cells[c] for c in ("B1", "A2", "B2", "C2")] == ["Q1", "sku", "Revenue", "Cost"]
+    assert merged_ranges(book) == ["B1:C1"]
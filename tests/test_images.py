@@ -0,0 +1,213 @@
+"""Images — in-cell thumbnails from ``image_columns``, floating ``images``.
+
+An image column is data, so a value that is not a readable image is left
+blank with one warning per column rather than aborting the export. A floating
+image is layout, so a bad one raises before anything is written. These tests
+read the package parts directly: embedded images are rich values in the cell,
+floating ones are drawings.
+"""
+
+import base64
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+# A 1x1 PNG.
+PNG = base64.b64decode(
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9aw"
+    "AAAABJRU5ErkJggg=="
+)
+
+
+def _png_file(tmp_path, name="thumb.png"):
+    path = tmp_path / name
+    path.write_bytes(PNG)
+    return path
+
+
+def _parts(path):
+    return zipfile.ZipFile(path).namelist()
+
+
+def _embedded_cells(path, sheet=1):
+    """Cells holding an in-cell image (an error cell with a value-metadata index)."""
+    xml = zipfile.ZipFile(path).read(f"xl/worksheets/sheet{sheet}.xml").decode()
+    return re.findall(r'<c r="([A-Z]+\d+)"[^>]* vm="\d+"', xml)
+
+
+def _cell_xml(path, cell, sheet=1):
+    xml = zipfile.ZipFile(path).read(f"xl/worksheets/sheet{sheet}.xml").decode()
+    match = re.search(rf'<c r="{cell}"[^>]*?(?:/>|>.*?</c>)', xml)
+    return match.group(0) if match else None
+
+
+def _anchors(path, n=1):
+    """``(col, row)`` of each floating image's top-left corner."""
+    xml = zipfile.ZipFile(path).read(f"xl/drawings/drawing{n}.xml").decode()
+    return [
+        (int(col), int(row))
+        for col, row in re.findall(
+            r"<xdr:from><xdr:col>(\d+)</xdr:col><xdr:colOff>\d+</xdr:colOff>"
+            r"<xdr:row>(\d+)</xdr:row>",
+            xml,
+        )
+    ]
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet([{"sku": "a", "thumb": "not/a/path.png"}], str(path))
+    assert not any(p.startswith("xl/media/") for p in _parts(path))
+    assert _embedded_cells(path) == []
+
+
+def test_bytes_and_paths_are_embedded(tmp_path):
+    thumb = _png_file(tmp_path)
+    path = tmp_path / "catalog.xlsx"
+    write_worksheet(
+        [
+            {"sku": "a", "thumb": PNG},
+            {"sku": "b", "thumb": str(thumb)},
+            {"sku": "c", "thumb": thumb},
+        ],
+        str(path),
+        image_columns=["thumb"],
+    )
+    assert _embedded_cells(path) == ["B2", "B3", "B4"]
+    assert "xl/richData/rdrichvalue.xml" in _parts(path)
+    # The same picture is stored once.
+    assert [p for p in _parts(path) if p.startswith("xl/media/")] == ["xl/media/image1.png"]
+
+
+def test_unreadable_values_are_blank_with_one_warning(tmp_path):
+    path = tmp_path / "bad.xlsx"
+    rows = [
+        {"sku": "a", "thumb": PNG},
+        {"sku": "b", "thumb": b"not an image"},
+        {"sku": "c", "thumb": str(tmp_path / "missing.png")},
+        {"sku": "d", "thumb": 42},
+    ]
+    with pytest.warns(UserWarning, match=r"3 value\(s\) in column 'thumb'.*first at row 3"):
+        write_worksheet(rows, str(path), image_columns=["thumb"])
+    assert _embedded_cells(path) == ["B2"]
+    for cell in ("B3", "B4", "B5"):
+        assert "<v>" not in (_cell_xml(path, cell) or "")
+
+
+def test_none_and_empty_are_blank_without_warning(tmp_path, recwarn):
+    path = tmp_path / "blank.xlsx"
+    write_worksheet(
+        [{"thumb": None}, {"thumb": ""}, {"thumb": PNG}], str(path), image_columns=["thumb"]
+    )
+    assert _embedded_cells(path) == ["A4"]
+    assert not [w for w in recwarn if "image_columns" in str(w.message)]
+
+
+def test_unknown_column_warns(tmp_path):
+    path = tmp_path / "unknown.xlsx"
+    with pytest.warns(UserWarning, match="image_columns: unknown column 'nope'"):
+        write_worksheet([{"thumb": PNG}], str(path), image_columns=["nope"])
+    assert _embedded_cells(path) == []
+
+
+def test_follows_header_row(tmp_path):
+    path = tmp_path / "offset.xlsx"
+    write_worksheet(
+        [{"sku": "a", "thumb": PNG}, {"sku": "b", "thumb": PNG}],
+        str(path),
+        header_row=3,
+        image_columns=["thumb"],
+    )
+    assert _embedded_cells(path) == ["B5", "B6"]
+
+
+def test_dataframe_fallback_path(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"sku": ["a", "b", "c"], "thumb": [PNG, None, b"junk"]}, kinds=["O", "O"])
+    path = tmp_path / "fallback.xlsx"
+    with pytest.warns(UserWarning, match=r"1 value\(s\) in column 'thumb'"):
+        write_worksheet(df, str(path), image_columns=["thumb"])
+    assert _embedded_cells(path) == ["B2"]
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"sku": ["a", "b"], "thumb": [PNG, PNG]})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(df, str(path), image_columns=["thumb"])
+    assert _embedded_cells(path) == ["B2", "B3"]
+
+
+def test_floating_image_with_scale_and_offset(tmp_path):
+    logo = _png_file(tmp_path, "logo.png")
+    path = tmp_path / "logo.xlsx"
+    write_worksheet(
+        [{"a": 1}],
+        str(path),
+        images=[
+            {"anchor": "C2", "image": str(logo), "x_scale": 2, "alt_text": "Logo"},
+            {"anchor": "E5", "image": PNG, "x_offset": 4},
+        ],
+    )
+    assert _anchors(path) == [(2, 1), (4, 4)]
+    drawing = zipfile.ZipFile(path).read("xl/drawings/drawing1.xml").decode()
+    assert 'descr="Logo"' in drawing
+    # Floating images are drawings, not cell values.
+    assert _embedded_cells(path) == []
+
+
+@pytest.mark.parametrize(
+    "spec,message",
+    [
+        ({"image": PNG}, "missing required option 'anchor'"),
+        ({"anchor": "A1"}, "missing required option 'image'"),
+        ({"anchor": "1A", "image": PNG}, "'anchor' must be a cell"),
+        ({"anchor": "A1", "image": b"junk"}, "cannot read image"),
+        ({"anchor": "A1", "image": "/no/such/file.png"}, "cannot read image"),
+        ({"anchor": "A1", "image": 5}, "bytes or a file path"),
+        ({"anchor": "A1", "image": PNG, "x_scale": 0}, "positive number"),
+        ({"anchor": "A1", "image": PNG, "scale": 2}, "unknown option 'scale'"),
+    ],
+)
+def test_invalid_images_raise(tmp_path, spec, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet([{"a": 1}], str(path), images=[spec])
+    assert not path.exists()
+
+
+def test_images_must_be_a_list(tmp_path):
+    with pytest.raises(ValueError, match="list of image dicts"):
+        write_worksheet([{"a": 1}], str(tmp_path / "bad.xlsx"), images={"anchor": "A1"})
+
+
+def test_multi_sheet_is_per_sheet(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("Catalog", [{"thumb": PNG}]), ("Plain", [{"thumb": "text"}])],
+        str(path),
+        image_columns={"Catalog": ["thumb"]},
+        images={"Plain": [{"anchor": "C1", "image": PNG}]},
+    )
+    assert _embedded_cells(path, 1) == ["A2"]
+    assert _embedded_cells(path, 2) == []
+    assert _anchors(path) == [(2, 0)]
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    FastExcel(str(path)).sheet("S", [{"thumb": PNG}], image_columns=["thumb"]).save()
+    assert _embedded_cells(path) == ["A2"]
+
+
+def test_csv_warns_that_images_are_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="images"):
+        FastExcel(str(path)).sheet(
+            "S", [{"a": 1}], images=[{"anchor": "A1", "image": PNG}]
+        ).save()
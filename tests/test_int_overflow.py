@@ -0,0 +1,140 @@
+"""Integers past 2^53 — ``int_overflow`` and ``id_columns``.
+
+An Excel number is a double, so integers beyond 2^53 lose their low digits.
+Cells are read straight from the sheet XML.
+"""
+
+import io
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheets
+
+from conftest import number_cells, sheet_xml, text_cells, xlsx_book
+
+BIG = 2**53 + 1  # 9007199254740993, the first integer a double cannot hold
+SAFE = 2**53
+
+
+def test_records_default_rounds():
+    numbers = number_cells(xlsx_book([{"id": BIG}, {"id": SAFE}]))
+    assert float(numbers["A2"]) == float(BIG)
+    assert float(numbers["A3"]) == float(SAFE)
+
+
+def test_records_text_keeps_digits():
+    book = xlsx_book([{"id": BIG}, {"id": -BIG}, {"id": SAFE}, {"id": 10**40}], int_overflow="text")
+    texts = text_cells(book)
+    assert texts["A2"] == str(BIG)
+    assert texts["A3"] == str(-BIG)
+    assert texts["A5"] == str(10**40)
+    # Exact values stay numbers.
+    assert float(number_cells(book)["A4"]) == SAFE
+
+
+def test_records_error_names_the_column():
+    with pytest.raises(ValueError, match=f"column 'id': {BIG} is past 2\\^53"):
+        xlsx_book([{"name": "a", "id": 1}, {"name": "b", "id": BIG}], int_overflow="error")
+
+
+def test_unknown_policy_raises():
+    with pytest.raises(ValueError, match="int_overflow must be 'number', 'text' or 'error'"):
+        xlsx_book([{"id": 1}], int_overflow="round")
+
+
+def test_id_columns_write_text():
+    rows = [
+        {"id": 42, "code": 7.0, "n": 1},
+        {"id": BIG, "code": 7.5, "n": 2},
+        {"id": "A-9", "code": None, "n": 3},
+    ]
+    book = xlsx_book(rows, id_columns=["id", "code"])
+    texts = text_cells(book)
+    assert texts["A2"] == "42"
+    assert texts["A3"] == str(BIG)
+    assert texts["A4"] == "A-9"
+    assert texts["B2"] == "7"
+    assert texts["B3"] == "7.5"
+    assert float(number_cells(book)["C2"]) == 1
+    # Excel would otherwise flag every digit-only cell.
+    assert '<ignoredError sqref="A2:A4 B2:B4" numberStoredAsText="1"/>' in sheet_xml(book)
+
+
+def test_id_columns_unknown_name_warns():
+    with pytest.warns(UserWarning, match="id_columns: unknown column 'nope', skipped"):
+        xlsx_book([{"id": 1}], id_columns=["nope"])
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Num", [{"id": BIG}]), ("Txt", [{"id": BIG}])],
+        buf,
+        int_overflow={"Txt": "text"},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert float(number_cells(book, 1)["A2"]) == float(BIG)
+    assert text_cells(book, 2)["A2"] == str(BIG)
+
+
+def test_fastexcel_options():
+    buf = io.BytesIO()
+    rows = [{"id": 5, "big": BIG}]
+    FastExcel(buf).sheet("S", rows, id_columns=["id"], int_overflow="text").save()
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    texts = text_cells(book)
+    assert texts["A2"] == "5"
+    assert texts["B2"] == str(BIG)
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def test_arrow_int64_and_uint64():
+    pa = pytest.importorskip("pyarrow")
+    unsigned = pa.array([2**64 - 1, 1, 2], type=pa.uint64())
+    table = pa.table({"signed": pa.array([BIG, -BIG, 3], type=pa.int64()), "unsigned": unsigned})
+    texts = text_cells(xlsx_book(table, int_overflow="text"))
+    assert texts["A2"] == str(BIG)
+    assert texts["A3"] == str(-BIG)
+    assert texts["B2"] == str(2**64 - 1)
+    assert float(number_cells(xlsx_book(table))["A2"]) == float(BIG)
+    with pytest.raises(ValueError, match=f"column 'unsigned': {2**64 - 1} is past 2\\^53"):
+        xlsx_book(pa.table({"unsigned": unsigned}), int_overflow="error")
+
+
+def test_arrow_id_columns():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table(
+        {
+            "id": pa.array([BIG, None], type=pa.int64()),
+            "code": pa.array([12.0, 1.5], type=pa.float64()),
+        }
+    )
+    texts = text_cells(xlsx_book(table, id_columns=["id", "code"]))
+    assert texts["A2"] == str(BIG)
+    assert texts["B2"] == "12"
+    assert texts["B3"] == "1.5"
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"id": pa.array([BIG, 1], type=pa.int64()), "x": pa.array([1.5, 2.5])})
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            int_overflow={"A": "text"},
+            id_columns={"B": ["x"]},
+            banded_rows={"general": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
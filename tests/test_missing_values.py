@@ -0,0 +1,134 @@
+"""Missing and non-finite values — ``na_rep``, ``nan_rep`` and ``inf_rep``.
+
+Cells are read straight from the sheet XML.
+"""
+
+import io
+import math
+import warnings
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheets
+
+from conftest import csv_lines, error_cells, text_cells, xlsx_book
+
+ROWS = [
+    {"name": "a", "x": 1.5},
+    {"name": None, "x": math.nan},
+    {"name": "c", "x": math.inf},
+    {"name": "d", "x": -math.inf},
+]
+
+
+def test_default_is_blank():
+    book = xlsx_book(ROWS)
+    assert text_cells(book) == {"A1": "name", "B1": "x", "A2": "a", "A4": "c", "A5": "d"}
+    assert error_cells(book) == {}
+
+
+def test_text_reps():
+    texts = text_cells(xlsx_book(ROWS, na_rep="n/a", nan_rep="NaN", inf_rep="inf"))
+    assert texts["A3"] == "n/a"
+    assert texts["B3"] == "NaN"
+    assert texts["B4"] == "inf"
+    assert texts["B5"] == "-inf"
+
+
+def test_error_reps():
+    book = xlsx_book(ROWS, na_rep="#N/A", nan_rep="#NUM!", inf_rep="#DIV/0!")
+    assert error_cells(book) == {"A3": "#N/A", "B3": "#NUM!", "B4": "#DIV/0!", "B5": "#DIV/0!"}
+
+
+def test_raise_names_the_cell():
+    with pytest.raises(ValueError, match='cell B3 holds NaN, which nan_rep="raise" refuses'):
+        xlsx_book(ROWS, nan_rep="raise")
+    with pytest.raises(ValueError, match="cell A3 holds a missing value"):
+        xlsx_book(ROWS, na_rep="raise")
+    with pytest.raises(ValueError, match="cell B4 holds inf"):
+        xlsx_book(ROWS, inf_rep="raise")
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets([("A", ROWS), ("B", ROWS)], buf, nan_rep={"B": "#N/A"})
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert error_cells(book, 1) == {}
+    assert error_cells(book, 2) == {"B3": "#N/A"}
+
+
+def test_csv():
+    lines = csv_lines(ROWS, na_rep="#N/A", nan_rep="NaN", inf_rep="inf")
+    assert lines == ["name,x", "a,1.5", "#N/A,NaN", "c,inf", "d,-inf"]
+    assert csv_lines(ROWS)[1:] == ["a,1.5", ",", "c,", "d,"]
+
+
+def test_csv_raise_names_the_field():
+    with pytest.raises(ValueError, match="data row 3, field 2 holds inf"):
+        csv_lines(ROWS, inf_rep="raise")
+
+
+def test_fastexcel(tmp_path):
+    path = tmp_path / "out.csv"
+    # CSV honours the options, so there is no "ignored" warning.
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        FastExcel(str(path)).sheet("S", ROWS, nan_rep="NaN", na_rep="-").save()
+    assert path.read_text().splitlines()[2] == "-,NaN"
+
+    buf = io.BytesIO()
+    FastExcel(buf).sheet("S", ROWS, inf_rep="#N/A").save()
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert error_cells(book) == {"B4": "#N/A", "B5": "#N/A"}
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def _table(pa):
+    return pa.table(
+        {
+            "name": pa.array(["a", None, "c", "d"], type=pa.string()),
+            "x": pa.array([1.5, math.nan, math.inf, None], type=pa.float64()),
+        }
+    )
+
+
+def test_arrow_matches_records():
+    pa = pytest.importorskip("pyarrow")
+    book = xlsx_book(_table(pa), na_rep="#N/A", nan_rep="NaN", inf_rep="inf")
+    assert error_cells(book) == {"A3": "#N/A", "B5": "#N/A"}
+    texts = text_cells(book)
+    assert texts["B3"] == "NaN"
+    assert texts["B4"] == "inf"
+
+
+def test_arrow_csv():
+    pa = pytest.importorskip("pyarrow")
+    lines = csv_lines(_table(pa), na_rep="NA", nan_rep="NaN", inf_rep="inf")
+    assert lines[1:] == ["a,1.5", "NA,NaN", "c,inf", "d,NA"]
+    with pytest.raises(ValueError, match="data row 2, field 2 holds NaN"):
+        csv_lines(_table(pa), nan_rep="raise")
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = _table(pa)
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            na_rep={"A": "#N/A"},
+            nan_rep={"B": "NaN"},
+            banded_rows={"general": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
@@ -0,0 +1,248 @@
+"""Nested values — ``nested_columns``.
+
+Arrow ``List``/``Struct``/``Map`` columns and ``list``/``tuple``/``dict``
+values in records are written as JSON (default), joined list items, exploded
+``parent.field`` columns, or refused. Cells are read straight from the sheet
+XML.
+"""
+
+import io
+import warnings
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_csv, write_worksheets
+
+from conftest import cell_values, xlsx_book
+
+ROWS = [
+    {"id": 1, "tags": ["a", "b"], "owner": {"name": "Ann", "team": "ops"}},
+    {"id": 2, "tags": [], "owner": {"name": "Bo", "team": None}},
+]
+
+
+def test_records_default_to_json():
+    texts = cell_values(xlsx_book(ROWS))
+    assert texts["B2"] == '["a", "b"]'
+    assert texts["C2"] == '{"name": "Ann", "team": "ops"}'
+    assert texts["B3"] == "[]"
+    assert texts["C3"] == '{"name": "Bo", "team": null}'
+
+
+def test_tuples_are_lists():
+    texts = cell_values(xlsx_book([{"point": (1, 2.5)}]))
+    assert texts["A2"] == "[1, 2.5]"
+
+
+def test_records_join():
+    rows = [{"tags": ["a", "b"], "mixed": [1, None, "x", True, [2]], "owner": {"n": 1}}]
+    texts = cell_values(xlsx_book(rows, nested_columns="join"))
+    assert texts["A2"] == "a, b"
+    assert texts["B2"] == "1, , x, true, [2]"
+    assert texts["C2"] == '{"n": 1}'
+
+
+def test_records_explode():
+    rows = [{"id": 1, "owner": {"name": "Ann", "team": {"code": "ops"}}, "tags": ["a"]}]
+    book = xlsx_book(rows, nested_columns="explode")
+    texts = cell_values(book)
+    assert [texts[f"{c}1"] for c in "ABCD"] == ["id", "owner.name", "owner.team.code", "tags"]
+    assert float(texts["A2"]) == 1
+    assert [texts[f"{c}2"] for c in "BCD"] == ["Ann", "ops", '["a"]']
+
+
+def test_records_error():
+    with pytest.raises(ValueError, match=r"found a list value\. Pass nested_columns="):
+        xlsx_book(ROWS, nested_columns="error")
+
+
+def test_unknown_mode_raises():
+    with pytest.raises(ValueError, match="nested_columns must be 'json', 'join', 'explode'"):
+        xlsx_book(ROWS, nested_columns="flatten")
+
+
+def test_records_csv(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(ROWS[:1], str(path))
+    assert path.read_text().splitlines()[1] == (
+        '1,"[""a"", ""b""]","{""name"": ""Ann"", ""team"": ""ops""}"'
+    )
+    write_csv(ROWS[:1], str(path), nested_columns="explode")
+    assert path.read_text().splitlines() == [
+        "id,tags,owner.name,owner.team",
+        '1,"[""a"", ""b""]",Ann,ops',
+    ]
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Json", ROWS), ("Joined", ROWS)],
+        buf,
+        nested_columns={"Joined": "join"},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_values(book, 1)["B2"] == '["a", "b"]'
+    assert cell_values(book, 2)["B2"] == "a, b"
+
+
+def test_fastexcel_csv_target_uses_the_option(tmp_path):
+    path = tmp_path / "out.csv"
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        FastExcel(str(path)).sheet("S", ROWS[:1], nested_columns="join").save()
+    assert path.read_text().splitlines()[1].startswith('1,"a, b",')
+
+
+def _arrow_table():
+    pa = pytest.importorskip("pyarrow")
+    return pa.table(
+        {
+            "id": [1, 2, 3],
+            "tags": pa.array([["a", "b"], None, ["c", None]], type=pa.list_(pa.string())),
+            "owner": pa.array(
+                [{"name": "Ann", "age": 30}, {"name": "Bo", "age": None}, None],
+                type=pa.struct([("name", pa.string()), ("age", pa.int64())]),
+            ),
+        }
+    )
+
+
+def test_arrow_json():
+    texts = cell_values(xlsx_book(_arrow_table()))
+    assert texts["B2"] == '["a", "b"]'
+    assert texts["B4"] == '["c", null]'
+    assert texts["C2"] == '{"name": "Ann", "age": 30}'
+    assert texts["C3"] == '{"name": "Bo", "age": null}'
+    assert texts.get("B3", "") == ""
+    assert texts.get("C4", "") == ""
+
+
+def test_arrow_join():
+    texts = cell_values(xlsx_book(_arrow_table(), nested_columns="join"))
+    assert texts["B2"] == "a, b"
+    assert texts["B4"] == "c, "
+    assert texts["C2"] == '{"name": "Ann", "age": 30}'
+
+
+def test_arrow_explode():
+    book = xlsx_book(_arrow_table(), nested_columns="explode")
+    texts = cell_values(book)
+    assert [texts[f"{c}1"] for c in "ABCD"] == ["id", "tags", "owner.name", "owner.age"]
+    assert texts["C2"] == "Ann" and float(texts["D2"]) == 30
+    assert texts["C3"] == "Bo" and texts.get("D3", "") == ""
+    # A null struct nulls every field.
+    assert texts.get("C4", "") == "" and texts.get("D4", "") == ""
+
+
+def test_arrow_explode_nested_structs_and_column_formats():
+    pa = pytest.importorskip("pyarrow")
+    inner = pa.struct([("code", pa.string()), ("size", pa.float64())])
+    table = pa.table(
+        {"team": pa.array([{"info": {"code": "ops", "size": 2.5}}], type=pa.struct([("info", inner)]))}
+    )
+    book = xlsx_book(
+        table,
+        nested_columns="explode",
+        column_formats={"team.info.size": Format().set_num_format("0.000")},
+    )
+    texts = cell_values(book)
+    assert [texts["A1"], texts["B1"]] == ["team.info.code", "team.info.size"]
+    assert float(texts["B2"]) == 2.5
+    assert "0.000" in book.read("xl/styles.xml").decode()
+
+
+def test_arrow_map():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table(
+        {"attrs": pa.array([[("x", 1), ("y", 2)]], type=pa.map_(pa.string(), pa.int64()))}
+    )
+    assert cell_values(xlsx_book(table))["A2"] == '{"x": 1, "y": 2}'
+
+
+def test_arrow_error_names_the_column():
+    with pytest.raises(ValueError, match=r"column 'tags' holds List.*Pass nested_columns="):
+        xlsx_book(_arrow_table(), nested_columns="error")
+
+
+class _Series(list):
+    def tolist(self):
+        return list(self)
+
+
+class _Dtype:
+    kind = "O"
+
+
+class _Frame:
+    """Pandas-shaped: an Arrow stream, and columns for the fallback writer."""
+
+    def __init__(self, table):
+        self._table = table
+        self.columns = table.column_names
+        self.dtypes = [_Dtype() for _ in self.columns]
+
+    def __arrow_c_stream__(self, requested_schema=None):
+        return self._table.__arrow_c_stream__(requested_schema)
+
+    def __len__(self):
+        return self._table.num_rows
+
+    def __getitem__(self, key):
+        return _Series(self._table.column(key).to_pylist())
+
+
+def test_dataframe_error_is_not_routed_round():
+    # The Arrow refusal stands; the frame is not handed to the
+    # column-by-column writer instead.
+    with pytest.raises(ValueError, match=r"column 'tags' holds List"):
+        xlsx_book(_Frame(_arrow_table()), nested_columns="error")
+
+
+def test_pandas_and_polars_errors():
+    pa = pytest.importorskip("pyarrow")
+    pd = pytest.importorskip("pandas")
+    owner = pa.struct([("name", pa.string())])
+    frame = pd.DataFrame(
+        {"owner": pd.array([{"name": "Ann"}], dtype=pd.ArrowDtype(owner))}
+    )
+    with pytest.raises(ValueError, match=r"column 'owner' holds Struct"):
+        xlsx_book(frame, nested_columns="error")
+    pl = pytest.importorskip("polars")
+    with pytest.raises(ValueError, match=r"column 'tags' holds List"):
+        xlsx_book(pl.DataFrame({"tags": [["a", "b"]]}), nested_columns="error")
+
+
+def test_arrow_csv(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_arrow_table(), str(path), nested_columns="explode")
+    assert path.read_text().splitlines() == [
+        "id,tags,owner.name,owner.age",
+        '1,"[""a"", ""b""]",Ann,30',
+        "2,,Bo,",
+        '3,"[""c"", null]",,',
+    ]
+
+
+def test_arrow_csv_error(tmp_path):
+    with pytest.raises(ValueError, match="column 'tags'"):
+        write_csv(_arrow_table(), str(tmp_path / "out.csv"), nested_columns="error")
+
+
+def test_arrow_parallel_matches_serial():
+    table = _arrow_table()
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table), ("C", table)],
+            buf,
+            nested_columns={"B": "explode", "C": "join"},
+            banded_rows={"general": "#EEEEEE"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
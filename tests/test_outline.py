@@ -0,0 +1,299 @@
+"""Outline groups — ``row_groups``, ``column_groups``, ``outline_column`` and
+``outline_collapsed``.
+
+Row and column levels are read straight from the sheet XML. A sheet with
+grouped rows is kept in memory until save, which stores its text in the shared
+string table rather than inline.
+"""
+
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheets
+
+from conftest import cell_values, sheet_xml, xlsx_book
+
+ROWS = [{"store": f"s{i}", "sales": i} for i in range(10)]
+
+
+def _attrs(tag):
+    return dict(re.findall(r'(\w+)="([^"]*)"', tag))
+
+
+def _rows(book, sheet=1):
+    """``{1-based row: attributes}`` for every ``<row>`` in the sheet."""
+    rows = (_attrs(tag) for tag in re.findall(r"<row [^>]*>", sheet_xml(book, sheet)))
+    return {int(row["r"]): row for row in rows}
+
+
+def _levels(book, sheet=1):
+    """``{1-based row: outline level}`` for the grouped rows."""
+    return {r: int(a["outlineLevel"]) for r, a in _rows(book, sheet).items() if "outlineLevel" in a}
+
+
+def _flagged(book, flag, sheet=1):
+    return sorted(r for r, a in _rows(book, sheet).items() if a.get(flag) == "1")
+
+
+def _cols(book):
+    """``{1-based column: attributes}``, from ``<col>`` ranges."""
+    cols = {}
+    for tag in re.findall(r"<col [^>]*/>", sheet_xml(book)):
+        attrs = _attrs(tag)
+        for col in range(int(attrs["min"]), int(attrs["max"]) + 1):
+            cols[col] = attrs
+    return cols
+
+
+# ---------------------------------------------------------------------------
+# row_groups
+# ---------------------------------------------------------------------------
+
+
+def test_row_groups_take_the_deepest_level():
+    book = xlsx_book(ROWS, row_groups=[(1, 6, 1), (2, 3, 2), (5, 5, 2)])
+    assert _levels(book) == {2: 1, 3: 2, 4: 2, 5: 1, 6: 2, 7: 1}
+    assert _flagged(book, "hidden") == []
+
+
+def test_row_groups_collapsed():
+    book = xlsx_book(ROWS, row_groups=[(1, 4, 1), (2, 3, 2)], outline_collapsed=True)
+    assert _flagged(book, "hidden") == [2, 3, 4, 5]
+    # The row after each group carries its collapsed mark: the inner group
+    # ends on row 4 (1-based), the outer on row 5.
+    assert _flagged(book, "collapsed") == [5, 6]
+
+
+def test_row_groups_outside_the_data():
+    # Groups are laid down before the first row, like row_heights, so any
+    # sheet row may be grouped — here past the last data row.
+    book = xlsx_book(ROWS[:2], row_groups=[(5, 6, 3)])
+    assert _levels(book) == {6: 3, 7: 3}
+
+
+@pytest.mark.parametrize(
+    "groups, message",
+    [
+        ([(3, 1, 1)], r"row_groups: group \(3, 1, 1\) is inverted"),
+        ([(1, 2, 8)], r"row_groups: group \(1, 2, 8\) has level 8; levels run from 1 to 7"),
+        ([(1, 2, 0)], r"has level 0"),
+        ([(1, 1_048_576, 1)], r"reaches row 1048576, past the last one"),
+        ([(1, 2)], r"row_groups must be a list of \(first_row, last_row, level\) tuples"),
+    ],
+)
+def test_row_groups_checked(groups, message):
+    with pytest.raises(ValueError, match=message):
+        xlsx_book(ROWS, row_groups=groups)
+
+
+def test_row_groups_and_outline_column_cannot_be_combined():
+    with pytest.raises(ValueError, match="row_groups and outline_column cannot be combined"):
+        xlsx_book(ROWS, row_groups=[(1, 2, 1)], outline_column="sales")
+
+
+# ---------------------------------------------------------------------------
+# column_groups
+# ---------------------------------------------------------------------------
+
+
+def test_column_groups():
+    rows = [{"a": 1, "b": 2, "c": 3, "d": 4}]
+    book = xlsx_book(rows, column_groups=[(1, 3, 1), (2, 2, 2)], autofit=False)
+    levels = {col: int(c["outlineLevel"]) for col, c in _cols(book).items()}
+    assert levels == {2: 1, 3: 2, 4: 1}
+    with pytest.raises(ValueError, match=r"column_groups: group \(1, 16384, 1\) reaches col 16384"):
+        xlsx_book(rows, column_groups=[(1, 16_384, 1)])
+
+
+def test_column_groups_collapsed():
+    rows = [{"a": 1, "b": 2, "c": 3, "d": 4}]
+    book = xlsx_book(rows, column_groups=[(1, 2, 1)], outline_collapsed=True, autofit=False)
+    cols = _cols(book)
+    assert [cols[c].get("hidden") for c in (2, 3)] == ["1", "1"]
+    assert cols[4].get("collapsed") == "1"
+
+
+# ---------------------------------------------------------------------------
+# outline_column
+# ---------------------------------------------------------------------------
+
+# A region subtotal below its stores, and a grand total below the regions.
+REPORT = [
+    {"level": 2, "store": "a", "sales": 1},
+    {"level": 2, "store": "b", "sales": 2},
+    {"level": 1, "store": "North", "sales": 3},
+    {"level": 2, "store": "c", "sales": 4},
+    {"level": 1, "store": "South", "sales": 4},
+    {"level": None, "store": "Total", "sales": 7},
+]
+
+
+def _report_columns(report):
+    return {key: [row[key] for row in report] for key in report[0]}
+
+
+def test_outline_column_sets_levels_and_is_not_written():
+    book = xlsx_book(REPORT, outline_column="level")
+    assert _levels(book) == {2: 2, 3: 2, 4: 1, 5: 2, 6: 1}
+    cells = cell_values(book)
+    assert (cells["A1"], cells["B1"], cells["A7"]) == ("store", "sales", "Total")
+    assert "C1" not in cells
+
+
+def test_outline_column_collapsed():
+    book = xlsx_book(REPORT, outline_column="level", outline_collapsed=True)
+    assert _flagged(book, "hidden") == [2, 3, 4, 5, 6]
+    assert _flagged(book, "collapsed") == [4, 6, 7]
+
+
+def test_outline_column_keeps_notes_in_place():
+    report = [dict(row, note="check") for row in REPORT]
+    book = xlsx_book(report, outline_column="level", comments={"sales": "note"})
+    assert _levels(book)[4] == 1
+    assert cell_values(book)["B1"] == "sales"
+    with pytest.raises(ValueError, match="comments: column 'level' is the outline_column"):
+        xlsx_book(report, outline_column="level", comments={"level": "note"})
+
+
+def test_outline_column_split_continues_on_the_next_sheet():
+    header = 1_048_575 - 3
+    book = xlsx_book(REPORT, outline_column="level", header_row=header, overflow="split")
+    top = header + 2
+    # Each sheet ends every group still open on its last row.
+    assert _levels(book, 1) == {top: 2, top + 1: 2, top + 2: 1}
+    assert _levels(book, 2) == {top: 2, top + 1: 1}
+
+
+@pytest.mark.parametrize("level", [8, -1, "2", 1.5])
+def test_outline_column_levels_checked(level):
+    report = [{"level": 1}, {"level": level}]
+    with pytest.raises(ValueError, match="outline_column: row 3 has level"):
+        xlsx_book(report, outline_column="level")
+
+
+def test_unknown_outline_column_warns():
+    with pytest.warns(UserWarning, match="outline_column: unknown column 'depth', skipped"):
+        book = xlsx_book(REPORT, outline_column="depth")
+    assert _levels(book) == {}
+    assert cell_values(book)["A1"] == "level"
+
+
+def test_outline_column_uses_the_renamed_header():
+    book = xlsx_book(REPORT, outline_column="depth", rename={"level": "depth"})
+    assert _levels(book)[4] == 1
+
+
+# ---------------------------------------------------------------------------
+# DataFrames
+# ---------------------------------------------------------------------------
+
+
+class _Series:
+    def __init__(self, values):
+        self._values = values
+
+    def tolist(self):
+        return list(self._values)
+
+
+class _Dtype:
+    kind = "O"
+
+
+class _Frame:
+    """Pandas-shaped, without ``__arrow_c_stream__``."""
+
+    def __init__(self, data):
+        self._data = data
+        self.columns = list(data)
+        self.dtypes = [_Dtype() for _ in data]
+
+    def __len__(self):
+        return len(next(iter(self._data.values())))
+
+    def __getitem__(self, key):
+        return _Series(self._data[key])
+
+
+def test_fallback_frame():
+    book = xlsx_book(
+        _Frame(_report_columns(REPORT)), outline_column="level", outline_collapsed=True
+    )
+    assert _levels(book) == {2: 2, 3: 2, 4: 1, 5: 2, 6: 1}
+    assert _flagged(book, "collapsed") == [4, 6, 7]
+    assert cell_values(book)["A1"] == "store"
+
+
+def test_arrow():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table(_report_columns(REPORT))
+    book = xlsx_book(table, outline_column="level", outline_collapsed=True)
+    assert _levels(book) == {2: 2, 3: 2, 4: 1, 5: 2, 6: 1}
+    assert _flagged(book, "collapsed") == [4, 6, 7]
+    assert cell_values(book)["A1"] == "store"
+
+
+def test_arrow_levels_across_batches():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table(_report_columns(REPORT))
+    # One-row batches: each level is set from the batch after it.
+    batches = pa.Table.from_batches(table.to_batches(max_chunksize=1))
+    book = xlsx_book(batches, outline_column="level", outline_collapsed=True)
+    assert _flagged(book, "collapsed") == [4, 6, 7]
+
+
+def test_arrow_levels_must_be_integers():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"level": ["1"], "v": [1]})
+    with pytest.raises(ValueError, match="outline_column: levels must be integers"):
+        xlsx_book(table, outline_column="level")
+    with pytest.raises(ValueError, match="outline_column: row 2 has level 9"):
+        xlsx_book(pa.table({"level": [9], "v": [1]}), outline_column="level")
+
+
+def test_arrow_parallel_matches_serial():
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table(_report_columns(REPORT))
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            outline_column={"general": "level"},
+            outline_collapsed={"B": True},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+# ---------------------------------------------------------------------------
+# write_worksheets and FastExcel
+# ---------------------------------------------------------------------------
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Flat", ROWS), ("Grouped", ROWS), ("Report", REPORT)],
+        buf,
+        row_groups={"Grouped": [(1, 2, 1)]},
+        outline_column={"Report": "level"},
+        outline_collapsed={"general": True},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert _levels(book, 1) == {}
+    assert _levels(book, 2) == {2: 1, 3: 1}
+    assert _flagged(book, "collapsed", 3) == [4, 6, 7]
+
+
+def test_fastexcel_csv_ignores_it(tmp_path):
+    path = tmp_path / "out.csv"
+    with pytest.warns(UserWarning, match="row_groups"):
+        FastExcel(str(path)).sheet("S", ROWS, row_groups=[(1, 2, 1)]).save()
@@ -0,0 +1,249 @@
+"""Sheet overflow — data rows past Excel's 1,048,576-row limit.
+
+Writing a million rows per test would be slow, so these tests put the header
+near the bottom of the sheet instead: with ``header_row=HEADER`` only
+``ROOM`` data rows fit, and everything past that exercises the same code a
+genuinely oversized export would. Cells are read straight from the sheet XML.
+"""
+
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_worksheet, write_worksheets
+
+#: Last row index of a sheet (0-based).
+LAST_ROW = 1_048_575
+HEADER = LAST_ROW - 3
+#: Data rows that fit below ``HEADER``.
+ROOM = 3
+
+
+def _rows(n):
+    return [{"id": i, "name": f"row{i}"} for i in range(n)]
+
+
+def _sheet_xml(path, sheet):
+    return zipfile.ZipFile(path).read(f"xl/worksheets/sheet{sheet}.xml").decode()
+
+
+def _sheet_names(path):
+    xml = zipfile.ZipFile(path).read("xl/workbook.xml").decode()
+    return re.findall(r'<sheet name="([^"]+)"', xml)
+
+
+def _ids(path, sheet):
+    """Values of column A below the header, in row order."""
+    xml = _sheet_xml(path, sheet)
+    cells = re.findall(r'<c r="A(\d+)"[^>]*><v>([^<]*)</v>', xml)
+    return [int(float(v)) for r, v in cells if int(r) > HEADER + 1]
+
+
+def _header(path, sheet):
+    xml = _sheet_xml(path, sheet)
+    row = re.search(rf'<row r="{HEADER + 1}"[^>]*>(.*?)</row>', xml).group(1)
+    return re.findall(r"<t[^>]*>(.*?)</t>", row)
+
+
+def test_default_raises_a_clear_error(tmp_path):
+    path = tmp_path / "err.xlsx"
+    with pytest.raises(ValueError, match=r"sheet 'Sheet1'.*overflow=\"split\""):
+        write_worksheet(_rows(ROOM + 1), str(path), header_row=HEADER)
+    assert not path.exists()
+
+
+def test_exact_fit_needs_no_overflow(tmp_path):
+    path = tmp_path / "fit.xlsx"
+    write_worksheet(_rows(ROOM), str(path), header_row=HEADER)
+    assert _ids(path, 1) == [0, 1, 2]
+    assert _sheet_names(path) == ["Sheet1"]
+
+
+def test_split_records(tmp_path):
+    path = tmp_path / "split.xlsx"
+    write_worksheet(
+        (row for row in _rows(8)),
+        str(path),
+        sheet_name="Data",
+        header_row=HEADER,
+        overflow="split",
+    )
+    assert _sheet_names(path) == ["Data", "Data (2)", "Data (3)"]
+    assert [_ids(path, n) for n in (1, 2, 3)] == [[0, 1, 2], [3, 4, 5], [6, 7]]
+    for n in (1, 2, 3):
+        assert _header(path, n) == ["id", "name"]
+
+
+def test_split_repeats_layout(tmp_path):
+    path = tmp_path / "layout.xlsx"
+    write_worksheet(
+        _rows(5),
+        str(path),
+        header_row=HEADER,
+        freeze_row=HEADER + 1,
+        autofilter=True,
+        column_formats={"id": Format().set_num_format("0.00")},
+        overflow="split",
+    )
+    assert _sheet_names(path) == ["Sheet1", "Sheet1 (2)"]
+    first, second = _sheet_xml(path, 1), _sheet_xml(path, 2)
+    for xml in (first, second):
+        assert f'ySplit="{HEADER + 1}"' in xml
+        assert re.search(r'<col min="1" max="1"[^>]*style="\d+"', xml)
+    assert f'<autoFilter ref="A{HEADER + 1}:B{LAST_ROW + 1}"/>' in first
+    assert f'<autoFilter ref="A{HEADER + 1}:B{HEADER + 3}"/>' in second
+
+
+def test_totals_row_is_reserved_and_repeated(tmp_path):
+    path = tmp_path / "totals.xlsx"
+    write_worksheet(
+        _rows(4),
+        str(path),
+        header_row=HEADER,
+        totals_row={"id": "sum"},
+        overflow="split",
+    )
+    # Two data rows per sheet, the last row of each taken by the totals.
+    assert [_ids(path, n) for n in (1, 2)] == [[0, 1], [2, 3]]
+    for n in (1, 2):
+        assert f"SUM(A{HEADER + 2}:A{HEADER + 3})" in _sheet_xml(path, n)
+
+
+def test_charts_and_fixed_notes_stay_on_the_first_sheet(tmp_path):
+    path = tmp_path / "charts.xlsx"
+    write_worksheet(
+        [dict(row, note=f"n{row['id']}") for row in _rows(8)],
+        str(path),
+        header_row=HEADER,
+        charts={"type": "column", "categories": "name", "values": "id"},
+        comments={(0, 0): "Title", "name": "note"},
+        overflow="split",
+    )
+    book = zipfile.ZipFile(path)
+    names = book.namelist()
+    assert len([n for n in names if re.fullmatch(r"xl/charts/chart\d+\.xml", n)]) == 1
+    rels = book.read("xl/worksheets/_rels/sheet1.xml.rels").decode()
+    assert "drawing" in rels
+    # The note column still annotates every part; the fixed note only the first.
+    notes = [
+        re.findall(r'<comment ref="([A-Z]+\d+)"', book.read(n).decode())
+        for n in sorted(n for n in names if re.fullmatch(r"xl/comments\d+\.xml", n))
+    ]
+    assert notes == [
+        ["A1", f"B{HEADER + 2}", f"B{HEADER + 3}", f"B{HEADER + 4}"],
+        [f"B{HEADER + 2}", f"B{HEADER + 3}", f"B{HEADER + 4}"],
+        [f"B{HEADER + 2}", f"B{HEADER + 3}"],
+    ]
+
+
+def test_truncate_warns_and_keeps_what_fits(tmp_path):
+    path = tmp_path / "trunc.xlsx"
+    with pytest.warns(UserWarning, match="keeps the first 3 data rows; 4 more row"):
+        write_worksheet(
+            (row for row in _rows(7)), str(path), header_row=HEADER, overflow="truncate"
+        )
+    assert _sheet_names(path) == ["Sheet1"]
+    assert _ids(path, 1) == [0, 1, 2]
+
+
+@pytest.mark.parametrize("mode", ["split", "truncate"])
+def test_dataframe_fallback_path(tmp_path, mode):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"id": list(range(7)), "name": list("abcdefg")}, kinds=["i", "O"])
+    path = tmp_path / f"{mode}.xlsx"
+    if mode == "truncate":
+        with pytest.warns(UserWarning, match="4 more row"):
+            write_worksheet(df, str(path), header_row=HEADER, overflow=mode)
+        assert _ids(path, 1) == [0, 1, 2]
+    else:
+        write_worksheet(df, str(path), header_row=HEADER, overflow=mode)
+        assert [_ids(path, n) for n in (1, 2, 3)] == [[0, 1, 2], [3, 4, 5], [6]]
+
+
+def test_dataframe_fallback_path_raises_before_writing(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"id": list(range(4))}, kinds=["i"])
+    with pytest.raises(ValueError, match="more data rows than fit"):
+        write_worksheet(df, str(tmp_path / "x.xlsx"), header_row=HEADER)
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframes_split(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"id": list(range(7)), "name": list("abcdefg")})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(df, str(path), header_row=HEADER, overflow="split")
+    assert [_ids(path, n) for n in (1, 2, 3)] == [[0, 1, 2], [3, 4, 5], [6]]
+
+
+def test_arrow_split_across_batches(tmp_path):
+    pa = pytest.importorskip("pyarrow")
+    # Batches of 2 and 5 rows: the split falls inside the second one.
+    table = pa.Table.from_batches(
+        [
+            pa.record_batch({"id": [0, 1], "name": ["a", "b"]}),
+            pa.record_batch({"id": [2, 3, 4, 5, 6], "name": list("cdefg")}),
+        ]
+    )
+    path = tmp_path / "arrow.xlsx"
+    write_worksheet(table, str(path), header_row=HEADER, overflow="split")
+    assert [_ids(path, n) for n in (1, 2, 3)] == [[0, 1, 2], [3, 4, 5], [6]]
+
+
+def test_multi_sheet_names_skip_existing_sheets(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("Data", _rows(5)), ("data (2)", _rows(1))],
+        str(path),
+        header_row={"general": HEADER},
+        overflow={"Data": "split"},
+    )
+    assert _sheet_names(path) == ["Data", "Data (3)", "data (2)"]
+    assert _ids(path, 2) == [3, 4]
+
+
+def test_multi_sheet_modes_are_per_sheet(tmp_path):
+    path = tmp_path / "modes.xlsx"
+    with pytest.raises(ValueError, match="sheet 'B'"):
+        write_worksheets(
+            [("A", _rows(5)), ("B", _rows(5))],
+            str(path),
+            header_row={"general": HEADER},
+            overflow={"A": "split"},
+        )
+
+
+def test_long_names_are_shortened(tmp_path):
+    path = tmp_path / "long.xlsx"
+    name = "x" * 31
+    write_worksheet(_rows(4), str(path), sheet_name=name, header_row=HEADER, overflow="split")
+    assert _sheet_names(path) == [name, "x" * 27 + " (2)"]
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    FastExcel(str(path)).sheet("S", _rows(4), header_row=HEADER, overflow="split").save()
+    assert _sheet_names(path) == ["S", "S (2)"]
+
+
+def test_csv_does_not_warn(tmp_path, recwarn):
+    path = tmp_path / "o.csv"
+    FastExcel(str(path)).sheet("S", _rows(2), overflow="split").save()
+    assert not [w for w in recwarn if "overflow" in str(w.message)]
+
+
+@pytest.mark.parametrize(
+    "kwargs,message",
+    [
+        ({"overflow": "wrap"}, "overflow must be 'error', 'split' or 'truncate'"),
+        ({"overflow": "split", "table": True}, "cannot be combined with table"),
+    ],
+)
+def test_invalid_options_raise(tmp_path, kwargs, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_rows(1), str(path), **kwargs)
+    assert not path.exists()
@@ -0,0 +1,141 @@
+"""Parallel sheets — ``write_worksheets(..., parallel=True)``.
+
+Arrow-backed sheets have their batches written on a thread pool. The promise is
+that the file does not change, so most tests write the same workbook both ways
+with ``reproducible=True`` and compare the bytes. Formats are where a race would
+show: a constant-memory sheet numbers them workbook-wide on first use, so the
+sheets here each bring their own float, column and banding formats.
+"""
+
+import datetime
+import io
+import re
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_worksheets
+
+pa = pytest.importorskip("pyarrow")
+
+#: Last row index of a sheet (0-based); see tests/test_overflow.py.
+LAST_ROW = 1_048_575
+
+
+def _table(n, offset=0, batch=7):
+    table = pa.table(
+        {
+            "id": [i + offset if i % 4 else None for i in range(n)],
+            "score": [i * 0.25 for i in range(n)],
+            "name": [f"n{i}" if i % 5 else None for i in range(n)],
+            "url": [f"https://example.com/{i}" for i in range(n)],
+            "day": [datetime.date(2024, 1, 1 + i % 28) for i in range(n)],
+            "at": [datetime.datetime(2024, 5, 6, 7, 8, i % 60) for i in range(n)],
+            "ok": [i % 2 == 0 for i in range(n)],
+        }
+    )
+    return pa.Table.from_batches(table.to_batches(max_chunksize=batch))
+
+
+def _rows(n):
+    return [{"id": i, "name": f"r{i}", "score": i * 1.5} for i in range(n)]
+
+
+def _both(sheets, **kwargs):
+    """The workbook written serially and in parallel."""
+    out = []
+    for parallel in (False, True):
+        buf = io.BytesIO()
+        write_worksheets(sheets(), buf, reproducible=True, parallel=parallel, **kwargs)
+        out.append(buf.getvalue())
+    return out
+
+
+def _sheet_names(data):
+    xml = zipfile.ZipFile(io.BytesIO(data)).read("xl/workbook.xml").decode()
+    return re.findall(r'<sheet name="([^"]+)"', xml)
+
+
+def test_arrow_sheets_match_serial():
+    serial, parallel = _both(
+        lambda: [(f"S{n}", _table(40 + n, offset=n)) for n in range(6)],
+        float_format="0.00",
+        url_columns={"general": ["url"]},
+        banded_rows={"S1": "#DDEEFF", "S4": "#FFEEDD"},
+        column_formats={
+            "S2": {"score": Format().set_num_format("#,##0.000")},
+            "S5": {"id": Format().set_bold()},
+        },
+        totals_row={"S3": {"score": "sum"}},
+        totals_format={"general": Format().set_italic()},
+        max_threads=3,
+    )
+    assert parallel == serial
+
+
+def test_mixed_sources_keep_their_order():
+    from tests.test_row_layout import _FakeFrame
+
+    def sheets():
+        return [
+            ("Arrow A", _table(30)),
+            ("Records", _rows(10)),
+            ("Generator", (row for row in _rows(5))),
+            ("Frame", _FakeFrame({"id": [1, 2], "score": [0.5, 1.5]}, kinds=["i", "f"])),
+            ("Arrow B", _table(25, offset=100)),
+        ]
+
+    serial, parallel = _both(
+        sheets, float_format="0.0", banded_rows={"general": "#EEEEEE"}
+    )
+    assert parallel == serial
+    assert _sheet_names(parallel) == ["Arrow A", "Records", "Generator", "Frame", "Arrow B"]
+
+
+def test_serial_only_sheets_match():
+    # Split and row formats under the header keep a sheet off the pool.
+    serial, parallel = _both(
+        lambda: [("Split", _table(9)), ("Rows", _table(20)), ("Plain", _table(20))],
+        header_row={"Split": LAST_ROW - 4},
+        overflow={"Split": "split"},
+        row_formats={"Rows": {3: Format().set_bold()}},
+        column_formats={"general": {"score": Format().set_num_format("0.0")}},
+    )
+    assert parallel == serial
+    assert _sheet_names(parallel) == ["Split", "Split (2)", "Split (3)", "Rows", "Plain"]
+
+
+def test_truncate_still_warns():
+    with pytest.warns(UserWarning, match="keeps the first 3 data rows; 6 more row"):
+        write_worksheets(
+            [("A", _table(9)), ("B", _table(2))],
+            io.BytesIO(),
+            header_row={"A": LAST_ROW - 3},
+            overflow={"A": "truncate"},
+            parallel=True,
+        )
+
+
+def test_errors_name_the_sheet():
+    with pytest.raises(ValueError, match="sheet 'B'"):
+        write_worksheets(
+            [("A", _table(3)), ("B", _table(9))],
+            io.BytesIO(),
+            header_row={"general": LAST_ROW - 3},
+            parallel=True,
+        )
+
+
+def test_fastexcel_forwards_the_option():
+    def build(parallel):
+        buf = io.BytesIO()
+        writer = FastExcel(buf, reproducible=True, parallel=parallel, max_threads=2)
+        writer.sheet("A", _table(20)).sheet("B", _table(30)).save()
+        return buf.getvalue()
+
+    assert build(True) == build(False)
+
+
+def test_max_threads_must_be_positive():
+    with pytest.raises(ValueError, match="max_threads must be at least 1"):
+        write_worksheets([("A", _table(2))], io.BytesIO(), parallel=True, max_threads=0)
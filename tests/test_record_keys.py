@@ -0,0 +1,120 @@
+"""Lining dict records up with the header row — ``columns``, ``missing``,
+``extra_keys`` and ``scan_rows``.
+
+Cells are read straight from the sheet XML.
+"""
+
+import io
+import warnings
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+from conftest import cell_values, csv_lines, xlsx_book
+
+
+def test_values_follow_keys_not_position():
+    rows = [{"id": 1, "name": "a"}, {"name": "b", "id": 2}]
+    cells = cell_values(xlsx_book(rows))
+    assert cells == {"A1": "id", "B1": "name", "A2": 1, "B2": "a", "A3": 2, "B3": "b"}
+
+
+def test_missing_key():
+    rows = [{"a": 1, "b": 2}, {"a": 3}, {"b": 4}]
+    cells = cell_values(xlsx_book(rows))
+    assert "B3" not in cells and "A4" not in cells
+    assert cell_values(xlsx_book(rows, na_rep="-"))["A4"] == "-"
+    with pytest.raises(ValueError, match="row 3: no value for column 'b'"):
+        xlsx_book(rows, missing="error")
+
+
+def test_extra_keys():
+    rows = [{"a": 1}, {"a": 2, "b": 3}]
+    assert cell_values(xlsx_book(rows)) == {"A1": "a", "A2": 1, "A3": 2}
+    with pytest.raises(ValueError, match="row 3: key 'b' is not a column"):
+        xlsx_book(rows, extra_keys="error")
+    cells = cell_values(xlsx_book(rows, extra_keys="append"))
+    assert cells == {"A1": "a", "B1": "b", "A2": 1, "A3": 2, "B3": 3}
+
+
+def test_append_scans_generators_up_to_scan_rows():
+    def rows():
+        yield {"a": 1}
+        yield {"b": 2}
+        yield {"c": 3}
+
+    cells = cell_values(xlsx_book(rows(), extra_keys="append"))
+    assert [cells[c] for c in ("A1", "B1", "C1", "B3", "C4")] == ["a", "b", "c", 2, 3]
+    with pytest.raises(ValueError, match=r"only adds keys seen in the first 2 records"):
+        xlsx_book(rows(), extra_keys="append", scan_rows=2)
+
+
+def test_columns_picks_and_orders():
+    rows = [{"a": 1, "b": 2, "c": 3}]
+    cells = cell_values(xlsx_book(rows, columns=["c", "a"]))
+    assert cells == {"A1": "c", "B1": "a", "A2": 3, "B2": 1}
+    # A column no record has is blank, or an error under missing="error".
+    assert cell_values(xlsx_book(rows, columns=["a", "z"]))["B1"] == "z"
+    with pytest.raises(ValueError, match="no value for column 'z'"):
+        xlsx_book(rows, columns=["z"], missing="error")
+
+
+def test_overflow_continuation_keeps_headers():
+    # With the header on the sheet's fourth-last row only three records fit.
+    header = 1_048_575 - 3
+    rows = [{"a": i} for i in range(3)] + [{"b": 9, "a": 3}]
+    buf = io.BytesIO()
+    write_worksheet(rows, buf, header_row=header, extra_keys="append", overflow="split")
+    second = cell_values(zipfile.ZipFile(io.BytesIO(buf.getvalue())), 2)
+    top, first = header + 1, header + 2
+    assert (second[f"A{top}"], second[f"B{top}"]) == ("a", "b")
+    assert (second[f"A{first}"], second[f"B{first}"]) == (3, 9)
+
+
+def test_bad_option_values():
+    rows = [{"a": 1}]
+    with pytest.raises(ValueError, match="missing must be 'blank' or 'error', got 'skip'"):
+        xlsx_book(rows, missing="skip")
+    with pytest.raises(ValueError, match="extra_keys must be 'ignore', 'error' or 'append'"):
+        xlsx_book(rows, extra_keys="keep")
+    with pytest.raises(ValueError, match="scan_rows must be at least 1"):
+        xlsx_book(rows, extra_keys="append", scan_rows=0)
+
+
+def test_csv():
+    rows = [{"a": 1, "b": 2}, {"b": 4, "c": 5}]
+    assert csv_lines(rows) == ["a,b", "1,2", ",4"]
+    assert csv_lines(rows, extra_keys="append") == ["a,b,c", "1,2,", ",4,5"]
+    assert csv_lines(rows, columns=["b"]) == ["b", "2", "4"]
+    with pytest.raises(ValueError, match="data row 2: no value for column 'a'"):
+        csv_lines(rows, missing="error")
+
+
+def test_write_worksheets_per_sheet():
+    rows = [{"a": 1}, {"a": 2, "b": 3}]
+    buf = io.BytesIO()
+    write_worksheets([("A", rows), ("B", rows)], buf, extra_keys={"B": "append"})
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert "B1" not in cell_values(book, 1)
+    assert cell_values(book, 2)["B3"] == 3
+
+
+def test_fastexcel(tmp_path):
+    rows = [{"a": 1, "b": 2}, {"b": 4, "a": 3}]
+    path = tmp_path / "out.csv"
+    # CSV honours the options, so there is no "ignored" warning.
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        FastExcel(str(path)).sheet("S", rows, columns=["b", "a"]).save()
+    assert path.read_text().splitlines() == ["b,a", "2,1", "4,3"]
+
+    buf = io.BytesIO()
+    FastExcel(buf).sheet("S", rows, columns=["b"]).save()
+    cells = cell_values(zipfile.ZipFile(io.BytesIO(buf.getvalue())))
+    assert cells == {"A1": "b", "A2": 2, "A3": 4}
+
+    # A 0 is passed on, and refused, rather than dropped as unset.
+    with pytest.raises(ValueError, match="scan_rows must be at least 1"):
+        FastExcel(io.BytesIO()).sheet("S", rows, extra_keys="append", scan_rows=0).save()
@@ -0,0 +1,125 @@
+"""Reproducible output — identical input gives byte-identical ``.xlsx`` files.
+
+The creation timestamp in ``docProps/core.xml`` is the only part of a workbook
+that changes between runs, so these tests write twice and compare the bytes,
+and check which date ends up pinned. The timestamp has one-second resolution,
+so the first records test sleeps past a second boundary between writes.
+"""
+
+import datetime
+import io
+import re
+import time
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_worksheet, write_worksheets
+
+ROWS = [
+    {"id": 1, "name": "a", "score": 1.5, "when": datetime.datetime(2024, 1, 2, 3, 4)},
+    {"id": 2, "name": "b", "score": 2.5, "when": datetime.datetime(2024, 5, 6, 7, 8)},
+]
+
+
+def _write(write, *args, **kwargs):
+    buf = io.BytesIO()
+    write(*args, buf, **kwargs)
+    return buf.getvalue()
+
+
+def _created(data):
+    core = zipfile.ZipFile(io.BytesIO(data)).read("docProps/core.xml").decode()
+    return re.search(r"<dcterms:created[^>]*>(.*?)<", core).group(1)
+
+
+@pytest.fixture(autouse=True)
+def _no_source_date_epoch(monkeypatch):
+    monkeypatch.delenv("SOURCE_DATE_EPOCH", raising=False)
+
+
+def test_off_by_default_stamps_the_time_of_writing():
+    year = time.strftime("%Y", time.gmtime())
+    assert _created(_write(write_worksheet, ROWS)).startswith(year)
+
+
+def test_records_are_byte_identical():
+    first = _write(write_worksheet, ROWS, reproducible=True)
+    time.sleep(1.1)
+    assert _write(write_worksheet, ROWS, reproducible=True) == first
+    assert _created(first) == "1980-01-01T00:00:00Z"
+
+
+def test_multi_sheet_is_byte_identical():
+    sheets = [("A", ROWS), ("B", ROWS)]
+    first = _write(write_worksheets, sheets, reproducible=True)
+    assert _write(write_worksheets, sheets, reproducible=True) == first
+    assert _created(first) == "1980-01-01T00:00:00Z"
+
+
+def test_dataframe_fallback_path_is_byte_identical():
+    from tests.test_row_layout import _FakeFrame
+
+    def frame():
+        return _FakeFrame({"id": [1, 2], "name": ["a", "b"]}, kinds=["i", "O"])
+
+    first = _write(write_worksheet, frame(), reproducible=True)
+    assert _write(write_worksheet, frame(), reproducible=True) == first
+    assert _created(first) == "1980-01-01T00:00:00Z"
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths_are_byte_identical(frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"id": [1, 2], "name": ["a", "b"], "score": [1.5, 2.5]})
+    first = _write(write_worksheet, df, reproducible=True)
+    assert _write(write_worksheet, df, reproducible=True) == first
+    assert _created(first) == "1980-01-01T00:00:00Z"
+
+
+def test_properties_keep_their_fields():
+    data = _write(write_worksheet, ROWS, reproducible=True, properties={"title": "T"})
+    core = zipfile.ZipFile(io.BytesIO(data)).read("docProps/core.xml").decode()
+    assert "<dc:title>T</dc:title>" in core
+    assert _created(data) == "1980-01-01T00:00:00Z"
+
+
+def test_explicit_created_wins(monkeypatch):
+    monkeypatch.setenv("SOURCE_DATE_EPOCH", "1700000000")
+    data = _write(
+        write_worksheet,
+        ROWS,
+        reproducible=True,
+        properties={"created": datetime.datetime(2024, 1, 1, 12)},
+    )
+    assert _created(data) == "2024-01-01T12:00:00Z"
+
+
+def test_source_date_epoch_is_honoured_without_the_flag(monkeypatch):
+    monkeypatch.setenv("SOURCE_DATE_EPOCH", "1700000000")
+    first = _write(write_worksheet, ROWS)
+    assert _created(first) == "2023-11-14T22:13:20Z"
+    assert _write(write_worksheet, ROWS) == first
+
+
+@pytest.mark.parametrize("value", ["yesterday", "1.5", "99999999999999"])
+def test_invalid_source_date_epoch_raises(monkeypatch, value):
+    monkeypatch.setenv("SOURCE_DATE_EPOCH", value)
+    with pytest.raises(ValueError, match="SOURCE_DATE_EPOCH"):
+        _write(write_worksheet, ROWS)
+
+
+def test_fastexcel_builder(tmp_path):
+    outputs = []
+    for n in range(2):
+        path = tmp_path / f"builder{n}.xlsx"
+        FastExcel(str(path), reproducible=True).sheet("S", ROWS).save()
+        outputs.append(path.read_bytes())
+    assert outputs[0] == outputs[1]
+    assert _created(outputs[0]) == "1980-01-01T00:00:00Z"
+
+
+def test_csv_does_not_warn(tmp_path, recwarn):
+    path = tmp_path / "o.csv"
+    FastExcel(str(path), reproducible=True).sheet("S", ROWS).save()
+    assert not [w for w in recwarn if "reproducible" in str(w.message)]
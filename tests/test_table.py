@@ -0,0 +1,285 @@
+"""Native Excel tables — ``table=True`` instead of hand-built filter/banding.
+
+The table is laid over the header and data once the row count is known, takes
+over ``totals_row`` as a structured totals row, and gives ``formula_columns``
+``[@Column]`` references. These tests pin the table XML and the conflicts that
+raise up front.
+"""
+
+import re
+import zipfile
+
+import openpyxl
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_worksheet, write_worksheets
+
+
+def _records(n=4):
+    return [{"name": f"n{i}", "price": i * 1.5, "qty": i} for i in range(n)]
+
+
+def _tables(path, sheet=None):
+    """``{name: Table}`` for one sheet."""
+    wb = openpyxl.load_workbook(path)
+    ws = wb[sheet] if sheet else wb.active
+    return dict(ws.tables.items())
+
+
+def _table_xml(path, n=1):
+    return zipfile.ZipFile(path).read(f"xl/tables/table{n}.xml").decode()
+
+
+def _formula(path, cell):
+    xml = zipfile.ZipFile(path).read("xl/worksheets/sheet1.xml").decode()
+    match = re.search(rf'<c r="{cell}"[^>]*>(?:<f[^>]*>(.*?)</f>)?', xml)
+    return match.group(1) if match and match.group(1) else None
+
+
+def test_off_by_default(tmp_path):
+    path = tmp_path / "off.xlsx"
+    write_worksheet(_records(), str(path))
+    assert _tables(path) == {}
+
+
+def test_default_table_covers_header_and_data(tmp_path):
+    path = tmp_path / "table.xlsx"
+    write_worksheet(_records(4), str(path), table=True)
+    (table,) = _tables(path).values()
+    assert table.name == "Table1"
+    assert table.ref == "A1:C5"
+    assert table.tableStyleInfo.name == "TableStyleMedium9"
+    assert [c.name for c in table.tableColumns] == ["name", "price", "qty"]
+    # The data is still there as plain values.
+    ws = openpyxl.load_workbook(path).active
+    assert ws["B3"].value == 1.5
+
+
+def test_name_and_options(tmp_path):
+    path = tmp_path / "named.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        table={
+            "name": "Sales",
+            "style": "Table Style Light 15",
+            "banded_rows": False,
+            "first_column": True,
+            "autofilter": False,
+        },
+    )
+    table = _tables(path)["Sales"]
+    info = table.tableStyleInfo
+    assert info.name == "TableStyleLight15"
+    assert not info.showRowStripes
+    assert info.showFirstColumn
+    assert table.autoFilter is None
+
+
+@pytest.mark.parametrize("style", ["TableStyleDark3", "dark 3", "Table Style Dark 3"])
+def test_style_spellings(tmp_path, style):
+    path = tmp_path / "style.xlsx"
+    write_worksheet(_records(2), str(path), table={"style": style})
+    (table,) = _tables(path).values()
+    assert table.tableStyleInfo.name == "TableStyleDark3"
+
+
+def test_follows_header_row(tmp_path):
+    path = tmp_path / "offset.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        header_row=2,
+        merge_ranges=[(0, 0, 0, 2, "Banner")],
+        table=True,
+    )
+    (table,) = _tables(path).values()
+    assert table.ref == "A3:C6"
+
+
+def test_keeps_header_format(tmp_path):
+    path = tmp_path / "hdr.xlsx"
+    write_worksheet(_records(2), str(path), bold_headers=True, table=True)
+    ws = openpyxl.load_workbook(path).active
+    assert ws["A1"].value == "name"
+    assert ws["A1"].font.b
+
+
+def test_totals_row_becomes_structured(tmp_path):
+    path = tmp_path / "totals.xlsx"
+    write_worksheet(
+        _records(4),
+        str(path),
+        table={"name": "Sales"},
+        totals_row={"price": "sum", "qty": "count"},
+        totals_label="Total",
+        totals_format=Format().set_bold(),
+    )
+    table = _tables(path)["Sales"]
+    # Header, 4 data rows, totals row.
+    assert table.ref == "A1:C6"
+    assert table.totalsRowCount == 1
+    cols = {c.name: c for c in table.tableColumns}
+    assert cols["name"].totalsRowLabel == "Total"
+    assert cols["price"].totalsRowFunction == "sum"
+    assert cols["qty"].totalsRowFunction == "countNums"
+    assert _formula(path, "B6") == "SUBTOTAL(109,[price])"
+    ws = openpyxl.load_workbook(path).active
+    assert ws["A6"].value == "Total"
+    assert ws["B6"].font.b
+
+
+def test_product_and_raw_formula_totals(tmp_path):
+    path = tmp_path / "custom.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        table=True,
+        totals_row={"qty": "product", "price": "=MAX({col}{first}:{col}{last})"},
+    )
+    xml = _table_xml(path)
+    assert "<totalsRowFormula>SUBTOTAL(106,[qty])</totalsRowFormula>" in xml
+    assert "<totalsRowFormula>MAX(B2:B4)</totalsRowFormula>" in xml
+
+
+def test_this_row_references_become_a_calculated_column(tmp_path):
+    path = tmp_path / "calc.xlsx"
+    write_worksheet(
+        _records(3),
+        str(path),
+        table={"name": "Sales"},
+        formula_columns={"total": "=[@price]*[@qty]"},
+    )
+    expected = "Sales[[#This Row],[price]]*Sales[[#This Row],[qty]]"
+    assert _formula(path, "D2") == expected
+    assert _formula(path, "D4") == expected
+    assert f"<calculatedColumnFormula>{expected}</calculatedColumnFormula>" in _table_xml(path)
+    (table,) = _tables(path).values()
+    assert table.ref == "A1:D4"
+
+
+def test_row_placeholder_formula_is_not_a_calculated_column(tmp_path):
+    path = tmp_path / "rowref.xlsx"
+    write_worksheet(
+        _records(3), str(path), table=True, formula_columns={"double": "=B{row}*2"}
+    )
+    assert _formula(path, "D3") == "B3*2"
+    assert "calculatedColumnFormula" not in _table_xml(path)
+
+
+def test_this_row_reference_in_string_literal_is_left_alone(tmp_path):
+    path = tmp_path / "literal.xlsx"
+    write_worksheet(
+        _records(2),
+        str(path),
+        table={"name": "T"},
+        formula_columns={"tag": '="[@x]"&[@name]'},
+    )
+    assert _formula(path, "D2") == '"[@x]"&amp;T[[#This Row],[name]]'
+
+
+def test_this_row_reference_without_table_raises(tmp_path):
+    path = tmp_path / "notable.xlsx"
+    with pytest.raises(ValueError, match=r"\[@Column\].*table=True"):
+        write_worksheet(_records(), str(path), formula_columns={"t": "=[@price]*2"})
+    assert not path.exists()
+
+
+@pytest.mark.parametrize(
+    "kwargs,message",
+    [
+        ({"table": True, "autofilter": True}, "autofilter and table"),
+        ({"table": True, "banded_rows": "#EEEEEE"}, "banded_rows and table"),
+        ({"table": "yes"}, "True or a dict"),
+        ({"table": {"colour": "red"}}, "unknown option 'colour'"),
+        ({"table": {"style": "fancy"}}, "unknown style 'fancy'"),
+        ({"table": {"name": "my table"}}, "invalid name"),
+        ({"table": {"name": "AB12"}}, "cell reference"),
+        ({"table": {"name": "R1C1"}}, "cell reference"),
+        ({"table": {"name": "1st"}}, "must start with"),
+    ],
+)
+def test_invalid_options_raise(tmp_path, kwargs, message):
+    path = tmp_path / "bad.xlsx"
+    with pytest.raises(ValueError, match=message):
+        write_worksheet(_records(), str(path), **kwargs)
+    assert not path.exists()
+
+
+def test_case_insensitive_duplicate_headers_raise(tmp_path):
+    path = tmp_path / "dup.xlsx"
+    with pytest.raises(ValueError, match="already exists"):
+        write_worksheet([{"a": 1, "A": 2}], str(path), table=True)
+
+
+def test_table_false_is_off(tmp_path):
+    path = tmp_path / "false.xlsx"
+    write_worksheet(_records(), str(path), table=False, autofilter=True)
+    assert _tables(path) == {}
+
+
+def test_empty_frame_keeps_one_blank_row(tmp_path):
+    from tests.test_row_layout import _FakeFrame
+
+    df = _FakeFrame({"a": [], "b": []}, kinds=["i", "f"])
+    path = tmp_path / "empty.xlsx"
+    write_worksheet(df, str(path), table=True, totals_row={"a": "sum"})
+    (table,) = _tables(path).values()
+    # No totals row over an empty range.
+    assert table.ref == "A1:B2"
+    assert not table.totalsRowCount
+
+
+@pytest.mark.parametrize("frame", ["pandas", "polars"])
+def test_dataframe_paths(tmp_path, frame):
+    mod = pytest.importorskip(frame)
+    df = mod.DataFrame({"a": [1, 2, 3], "b": [4.0, 5.0, 6.0]})
+    path = tmp_path / f"{frame}.xlsx"
+    write_worksheet(df, str(path), table=True)
+    (table,) = _tables(path).values()
+    assert table.ref == "A1:B4"
+
+
+def test_multi_sheet_names_are_numbered(tmp_path):
+    path = tmp_path / "multi.xlsx"
+    write_worksheets(
+        [("A", _records(2)), ("B", _records(3)), ("C", _records(1))],
+        str(path),
+        table={"A": True, "B": {"style": "Table Style Light 1"}},
+    )
+    assert list(_tables(path, "A")) == ["Table1"]
+    assert list(_tables(path, "B")) == ["Table2"]
+    assert _tables(path, "C") == {}
+
+
+def test_multi_sheet_default_name_skips_taken_ones(tmp_path):
+    path = tmp_path / "taken.xlsx"
+    write_worksheets(
+        [("A", _records(2)), ("B", _records(2))],
+        str(path),
+        table={"A": {"name": "Table2"}, "B": True},
+    )
+    assert list(_tables(path, "A")) == ["Table2"]
+    assert list(_tables(path, "B")) == ["Table1"]
+
+
+def test_multi_sheet_duplicate_name_raises(tmp_path):
+    path = tmp_path / "dup.xlsx"
+    with pytest.raises(ValueError, match="already used"):
+        write_worksheets(
+            [("A", _records(2)), ("B", _records(2))],
+            str(path),
+            table={"general": {"name": "Sales"}},
+        )
+
+
+def test_fastexcel_builder(tmp_path):
+    path = tmp_path / "builder.xlsx"
+    FastExcel(str(path)).sheet("S", _records(5), table={"name": "People"}).save()
+    assert _tables(path, "S")["People"].ref == "A1:C6"
+
+
+def test_csv_warns_that_table_is_dropped(tmp_path):
+    path = tmp_path / "o.csv"
+    with pytest.warns(UserWarning, match="table"):
+        FastExcel(str(path)).sheet("S", _records(2), table=True).save()
@@ -0,0 +1,187 @@
+"""Times of day and durations.
+
+``datetime.time`` and Arrow time columns become Excel times formatted
+``hh:mm:ss``; ``datetime.timedelta`` and Arrow duration columns become elapsed
+time formatted ``[h]:mm:ss``, or plain seconds with ``durations="seconds"``.
+Cells are read straight from the sheet XML.
+"""
+
+import datetime
+import io
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, Format, write_csv, write_worksheets
+
+from conftest import cell_number, cell_parts, num_format_of, xlsx_book
+
+ROWS = [
+    {"at": datetime.time(13, 45, 30), "took": datetime.timedelta(hours=26, minutes=3, seconds=4)},
+    {"at": datetime.time(6, 0), "took": datetime.timedelta(seconds=-5)},
+]
+
+
+def test_times_are_fractions_of_a_day():
+    book = xlsx_book(ROWS)
+    cells = cell_parts(book)
+    assert cell_number(cells["A2"]) == pytest.approx((13 * 3600 + 45 * 60 + 30) / 86400)
+    assert cell_number(cells["A3"]) == 0.25
+    assert num_format_of(book, cells["A2"][0]) == "hh:mm:ss"
+
+
+def test_durations_are_elapsed_time():
+    book = xlsx_book(ROWS)
+    cells = cell_parts(book)
+    assert cell_number(cells["B2"]) == pytest.approx((26 * 3600 + 3 * 60 + 4) / 86400)
+    assert cell_number(cells["B3"]) == pytest.approx(-5 / 86400)
+    assert num_format_of(book, cells["B2"][0]) == "[h]:mm:ss"
+
+
+def test_durations_as_seconds():
+    book = xlsx_book(ROWS, durations="seconds")
+    cells = cell_parts(book)
+    assert cell_number(cells["B2"]) == 26 * 3600 + 3 * 60 + 4
+    assert cell_number(cells["B3"]) == -5
+    assert num_format_of(book, cells["B2"][0]) is None
+
+
+def test_column_format_wins():
+    book = xlsx_book(
+        ROWS,
+        column_formats={
+            "at": Format().set_num_format("h:mm AM/PM"),
+            "took": Format().set_num_format("[m]"),
+        },
+    )
+    cells = cell_parts(book)
+    assert num_format_of(book, cells["A2"][0]) == "h:mm AM/PM"
+    assert num_format_of(book, cells["B2"][0]) == "[m]"
+
+
+def test_banded_rows_keep_the_format():
+    book = xlsx_book(ROWS * 2, banded_rows="#EEEEEE")
+    cells = cell_parts(book)
+    assert cells["A2"][0] != cells["A3"][0]
+    assert num_format_of(book, cells["A3"][0]) == "hh:mm:ss"
+    assert num_format_of(book, cells["B3"][0]) == "[h]:mm:ss"
+
+
+def test_unknown_mode_raises():
+    with pytest.raises(ValueError, match="durations must be 'elapsed' or 'seconds'"):
+        xlsx_book(ROWS, durations="minutes")
+
+
+def test_csv(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(ROWS, str(path))
+    assert path.read_text().splitlines() == ["at,took", "13:45:30,26:03:04", "06:00:00,-0:00:05"]
+
+
+def test_csv_part_seconds(tmp_path):
+    path = tmp_path / "out.csv"
+    rows = [{"at": datetime.time(6, 0, 0, 5_000), "took": datetime.timedelta(milliseconds=-1500)}]
+    write_csv(rows, str(path))
+    assert path.read_text().splitlines()[1] == "06:00:00.005,-0:00:01.500"
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Elapsed", ROWS), ("Seconds", ROWS)],
+        buf,
+        durations={"Seconds": "seconds"},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_number(cell_parts(book, 1)["B3"]) == pytest.approx(-5 / 86400)
+    assert cell_number(cell_parts(book, 2)["B3"]) == -5
+
+
+def test_fastexcel_sheet_option():
+    buf = io.BytesIO()
+    FastExcel(buf).sheet("Shifts", ROWS, durations="seconds").save()
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_number(cell_parts(book)["B2"]) == 26 * 3600 + 3 * 60 + 4
+
+
+def _arrow_table():
+    pa = pytest.importorskip("pyarrow")
+    noon = datetime.time(12, 0, 0, 500_000)
+    took = datetime.timedelta(hours=30, milliseconds=250)
+    return pa.table(
+        {
+            "t32s": pa.array([datetime.time(12, 0), None], type=pa.time32("s")),
+            "t32ms": pa.array([noon, None], type=pa.time32("ms")),
+            "t64us": pa.array([noon, None], type=pa.time64("us")),
+            "t64ns": pa.array([noon, None], type=pa.time64("ns")),
+            "ds": pa.array([datetime.timedelta(hours=30), None], type=pa.duration("s")),
+            "dms": pa.array([took, None], type=pa.duration("ms")),
+            "dus": pa.array([took, None], type=pa.duration("us")),
+            "dns": pa.array([took, None], type=pa.duration("ns")),
+        }
+    )
+
+
+def test_arrow_time_and_duration_columns():
+    book = xlsx_book(_arrow_table())
+    cells = cell_parts(book)
+    assert cell_number(cells["A2"]) == 0.5
+    for ref in ("B2", "C2", "D2"):
+        assert cell_number(cells[ref]) == pytest.approx((12 * 3600 + 0.5) / 86400)
+        assert num_format_of(book, cells[ref][0]) == "hh:mm:ss"
+    assert cell_number(cells["E2"]) == 1.25
+    for ref in ("F2", "G2", "H2"):
+        assert cell_number(cells[ref]) == pytest.approx((30 * 3600 + 0.25) / 86400)
+        assert num_format_of(book, cells[ref][0]) == "[h]:mm:ss"
+    assert "<v>" not in cells.get("A3", ("", ""))[1]
+
+
+def test_arrow_durations_as_seconds():
+    cells = cell_parts(xlsx_book(_arrow_table(), durations="seconds"))
+    assert cell_number(cells["E2"]) == 30 * 3600
+    assert cell_number(cells["H2"]) == pytest.approx(30 * 3600 + 0.25)
+
+
+def test_arrow_csv(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_arrow_table(), str(path))
+    lines = path.read_text().splitlines()
+    assert lines[1] == ",".join(
+        ["12:00:00"] + ["12:00:00.500"] * 3 + ["30:00:00"] + ["30:00:00.250"] * 3
+    )
+    assert lines[2] == "," * 7
+
+
+def test_arrow_csv_part_seconds(tmp_path):
+    pa = pytest.importorskip("pyarrow")
+    at = datetime.time(9, 30, 15, 123_456)
+    took = -datetime.timedelta(seconds=5, microseconds=250_500)
+    table = pa.table(
+        {
+            "at": pa.array([at], type=pa.time64("us")),
+            "took": pa.array([took], type=pa.duration("us")),
+        }
+    )
+    path = tmp_path / "out.csv"
+    write_csv(table, str(path))
+    assert path.read_text().splitlines()[1] == "09:30:15.123456,-0:00:05.250500"
+    write_csv(table, str(path), datetime_precision=3)
+    assert path.read_text().splitlines()[1] == "09:30:15.123,-0:00:05.250"
+
+
+def test_arrow_parallel_matches_serial():
+    table = _arrow_table()
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table), ("C", table)],
+            buf,
+            banded_rows={"B": "#EEEEEE"},
+            durations={"C": "seconds"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)
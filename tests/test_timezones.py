@@ -0,0 +1,236 @@
+"""Timezone-aware datetimes — ``timezone`` and CSV ``utc_offsets``.
+
+Aware Python datetimes and Arrow timestamp columns with a zone are written as
+the wall time of their own zone (``"keep"``) or of the one ``timezone`` names.
+Cells are read straight from the sheet XML.
+"""
+
+import datetime
+import io
+import time
+import warnings
+import zipfile
+
+import pytest
+
+from rustpy_xlsxwriter import FastExcel, write_csv, write_worksheets
+
+from conftest import cell_values, xlsx_book
+
+UTC = datetime.timezone.utc
+CET = datetime.timezone(datetime.timedelta(hours=1))
+# 08:30 UTC in winter, 10:00 UTC in summer.
+WINTER = datetime.datetime(2024, 3, 1, 9, 30, tzinfo=CET)
+SUMMER = datetime.datetime(2024, 7, 1, 10, 0, tzinfo=UTC)
+
+
+def _serial(*args):
+    """The Excel serial of a naive wall time, matched to a tenth of a second.
+    ``pytest.approx``'s relative default would let a whole hour through."""
+    delta = datetime.datetime(*args) - datetime.datetime(1899, 12, 30)
+    return pytest.approx(delta.days + delta.seconds / 86400, rel=0, abs=1e-6)
+
+
+def _rows(*values):
+    return [{"at": v} for v in values]
+
+
+def test_records_keep_their_own_wall_time():
+    values = cell_values(xlsx_book(_rows(WINTER, SUMMER)))
+    assert values["A2"] == _serial(2024, 3, 1, 9, 30)
+    assert values["A3"] == _serial(2024, 7, 1, 10, 0)
+
+
+def test_records_convert_to_utc():
+    values = cell_values(xlsx_book(_rows(WINTER, SUMMER), timezone="utc"))
+    assert values["A2"] == _serial(2024, 3, 1, 8, 30)
+    assert values["A3"] == _serial(2024, 7, 1, 10, 0)
+
+
+def test_records_convert_to_an_iana_zone_with_daylight_saving():
+    values = cell_values(xlsx_book(_rows(WINTER, SUMMER), timezone="America/New_York"))
+    assert values["A2"] == _serial(2024, 3, 1, 3, 30)
+    assert values["A3"] == _serial(2024, 7, 1, 6, 0)
+
+
+def test_records_convert_to_a_fixed_offset():
+    values = cell_values(xlsx_book(_rows(SUMMER), timezone="+05:30"))
+    assert values["A2"] == _serial(2024, 7, 1, 15, 30)
+
+
+def test_naive_values_are_left_alone():
+    naive = datetime.datetime(2024, 3, 1, 9, 30)
+    values = cell_values(xlsx_book(_rows(naive, naive), timezone="Asia/Tokyo"))
+    assert values["A2"] == values["A3"] == _serial(2024, 3, 1, 9, 30)
+
+
+def test_unknown_zone_raises():
+    with pytest.raises(ValueError, match="timezone must be 'keep', 'utc', 'local'"):
+        xlsx_book(_rows(SUMMER), timezone="Mars/Olympus_Mons")
+
+
+def test_mixed_column_warns_under_keep():
+    rows = _rows(WINTER, datetime.datetime(2024, 3, 1, 9, 30))
+    with pytest.warns(UserWarning, match="column 'at' mixes naive and timezone-aware"):
+        values = cell_values(xlsx_book(rows))
+    assert values["A2"] == values["A3"]
+
+
+def test_mixed_column_raises_when_converting():
+    rows = _rows(datetime.datetime(2024, 3, 1, 9, 30), WINTER)
+    with pytest.raises(ValueError, match="column 'at' mixes naive.*cannot be converted to 'utc'"):
+        xlsx_book(rows, timezone="utc")
+
+
+def test_local_follows_tz(monkeypatch):
+    if not hasattr(time, "tzset"):
+        pytest.skip("time.tzset is POSIX-only")
+    pa = pytest.importorskip("pyarrow")
+    monkeypatch.setenv("TZ", "Asia/Tokyo")
+    time.tzset()
+    try:
+        table = pa.table({"at": pa.array([SUMMER], type=pa.timestamp("us", tz="UTC"))})
+        book = xlsx_book(_rows(SUMMER), timezone="local")
+        assert cell_values(book)["A2"] == _serial(2024, 7, 1, 19, 0)
+        assert cell_values(xlsx_book(table, timezone="local"))["A2"] == _serial(2024, 7, 1, 19, 0)
+    finally:
+        monkeypatch.undo()
+        time.tzset()
+
+
+def test_local_follows_a_posix_tz_rule(monkeypatch):
+    # A TZ rule with no tz database entry behind it still has daylight time.
+    if not hasattr(time, "tzset"):
+        pytest.skip("time.tzset is POSIX-only")
+    pa = pytest.importorskip("pyarrow")
+    monkeypatch.setenv("TZ", "XST8XDT,M3.2.0,M11.1.0")
+    time.tzset()
+    try:
+        table = pa.table(
+            {"at": pa.array([WINTER, SUMMER], type=pa.timestamp("us", tz="UTC"))}
+        )
+        values = cell_values(xlsx_book(table, timezone="local"))
+        assert values["A2"] == _serial(2024, 3, 1, 0, 30)
+        assert values["A3"] == _serial(2024, 7, 1, 3, 0)
+    finally:
+        monkeypatch.undo()
+        time.tzset()
+
+
+def test_write_worksheets_per_sheet():
+    buf = io.BytesIO()
+    write_worksheets(
+        [("Kept", _rows(WINTER)), ("Utc", _rows(WINTER))],
+        buf,
+        timezone={"Utc": "utc"},
+    )
+    book = zipfile.ZipFile(io.BytesIO(buf.getvalue()))
+    assert cell_values(book, 1)["A2"] == _serial(2024, 3, 1, 9, 30)
+    assert cell_values(book, 2)["A2"] == _serial(2024, 3, 1, 8, 30)
+
+
+def test_records_csv_offsets(tmp_path):
+    path = tmp_path / "out.csv"
+    naive = datetime.datetime(2024, 1, 2, 3, 4, 5)
+    write_csv(_rows(WINTER, naive), str(path), timezone="keep", utc_offsets=True)
+    assert path.read_text().splitlines() == [
+        "at",
+        "2024-03-01T09:30:00+01:00",
+        "2024-01-02T03:04:05",
+    ]
+    with pytest.warns(UserWarning, match="mixes naive"):
+        write_csv(_rows(WINTER, naive), str(path))
+    write_csv(_rows(WINTER), str(path), timezone="America/New_York", utc_offsets=True)
+    assert path.read_text().splitlines()[1] == "2024-03-01T03:30:00-05:00"
+
+
+def test_fastexcel_csv_target_uses_the_options(tmp_path):
+    path = tmp_path / "out.csv"
+    with warnings.catch_warnings():
+        warnings.simplefilter("error")
+        FastExcel(str(path), utc_offsets=True).sheet("S", _rows(WINTER), timezone="utc").save()
+    assert path.read_text().splitlines()[1] == "2024-03-01T08:30:00+00:00"
+
+
+# ---------------------------------------------------------------------------
+# Arrow
+# ---------------------------------------------------------------------------
+
+
+def _arrow(tz, values=(WINTER, SUMMER), unit="us"):
+    pa = pytest.importorskip("pyarrow")
+    return pa.table({"at": pa.array(list(values), type=pa.timestamp(unit, tz=tz))})
+
+
+def test_arrow_keep_uses_the_column_zone():
+    values = cell_values(xlsx_book(_arrow("Europe/Berlin")))
+    # CET in winter, CEST in summer.
+    assert values["A2"] == _serial(2024, 3, 1, 9, 30)
+    assert values["A3"] == _serial(2024, 7, 1, 12, 0)
+
+
+def test_arrow_utc_column():
+    values = cell_values(xlsx_book(_arrow("UTC", unit="ms")))
+    assert values["A2"] == _serial(2024, 3, 1, 8, 30)
+
+
+def test_arrow_fixed_offset_column():
+    values = cell_values(xlsx_book(_arrow("+05:30", unit="s")))
+    assert values["A3"] == _serial(2024, 7, 1, 15, 30)
+
+
+def test_arrow_converts_to_the_target_zone():
+    values = cell_values(xlsx_book(_arrow("Asia/Kolkata"), timezone="America/New_York"))
+    assert values["A2"] == _serial(2024, 3, 1, 3, 30)
+    assert values["A3"] == _serial(2024, 7, 1, 6, 0)
+
+
+def test_arrow_rules_past_the_last_transition():
+    # Far enough out that only the zone's recurring rule covers it.
+    later = [
+        datetime.datetime(2061, 1, 15, 12, 0, tzinfo=UTC),
+        datetime.datetime(2061, 7, 15, 12, 0, tzinfo=UTC),
+        datetime.datetime(1990, 7, 15, 12, 0, tzinfo=UTC),
+    ]
+    values = cell_values(xlsx_book(_arrow("UTC", later, unit="ns"), timezone="Australia/Sydney"))
+    assert values["A2"] == _serial(2061, 1, 15, 23, 0)
+    assert values["A3"] == _serial(2061, 7, 15, 22, 0)
+    assert values["A4"] == _serial(1990, 7, 15, 22, 0)
+
+
+def test_arrow_naive_column_is_left_alone():
+    naive = [datetime.datetime(2024, 3, 1, 9, 30)]
+    values = cell_values(xlsx_book(_arrow(None, naive), timezone="Asia/Tokyo"))
+    assert values["A2"] == _serial(2024, 3, 1, 9, 30)
+
+
+def test_arrow_csv_offsets(tmp_path):
+    path = tmp_path / "out.csv"
+    write_csv(_arrow("Europe/Berlin"), str(path), utc_offsets=True)
+    assert path.read_text().splitlines() == [
+        "at",
+        "2024-03-01T09:30:00+01:00",
+        "2024-07-01T12:00:00+02:00",
+    ]
+    write_csv(_arrow("Europe/Berlin"), str(path), timezone="utc")
+    assert path.read_text().splitlines()[1:] == [
+        "2024-03-01T08:30:00",
+        "2024-07-01T10:00:00",
+    ]
+
+
+def test_arrow_parallel_matches_serial():
+    table = _arrow("Europe/Berlin")
+
+    def build(parallel):
+        buf = io.BytesIO()
+        write_worksheets(
+            [("A", table), ("B", table)],
+            buf,
+            timezone={"B": "utc"},
+            reproducible=True,
+            parallel=parallel,
+        )
+        return buf.getvalue()
+
+    assert build(True) == build(False)